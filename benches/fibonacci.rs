@@ -2,30 +2,25 @@
 //! The benchmark generates Fibonnaci numbers.
 
 use criterion::{criterion_group, criterion_main};
-use criterion::{BenchmarkId, Criterion};
+use criterion::{BatchSize, BenchmarkId, Criterion};
 
 #[cfg(unix)]
 use pprof::criterion::{Output, PProfProfiler};
 
 use std::any::type_name;
-use tortuga::Interpreter;
+use tortuga::{Interpreter, Program};
+
+const FIBONACCI: &str = r###"
+        @fibonacci(@n <= 1) = n
+        @fibonacci(@n) = fibonacci(n - 2) + fibonacci(n - 1)
+"###;
 
 fn benchmarks(c: &mut Criterion) {
     let mut group = c.benchmark_group("Tortuga Fibonnaci");
 
     let inputs: Vec<String> = (0..10)
-        .into_iter()
-        .map(|v| {
-            format!(
-                r###"
-        @fibonacci(@n <= 1) = n
-        @fibonacci(@n) = fibonacci(n - 2) + fibonacci(n - 1)
-
-        fibonacci({})
-    "###,
-                v
-            )
-        })
+        .chain([15, 20])
+        .map(|v| format!("{}\n        fibonacci({})", FIBONACCI, v))
         .collect();
 
     for input in inputs {
@@ -43,16 +38,113 @@ fn benchmarks(c: &mut Criterion) {
     group.finish();
 }
 
+/// Calls should not get slower as more names are defined in the enclosing environment.
+fn environment_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tortuga Environment Size");
+
+    for size in [0, 100, 1000] {
+        let definitions: String = (0..size)
+            .map(|i| format!("@constant{} = {}\n", i, i))
+            .collect();
+        let mut interpreter = Interpreter::default();
+        let program: Program = format!("{}{}", definitions, FIBONACCI)
+            .parse()
+            .expect("Invalid definitions.");
+
+        interpreter
+            .run(program)
+            .expect("Unable to define functions.");
+
+        let call: Program = "fibonacci(10)".parse().expect("Invalid call.");
+
+        group.bench_with_input(
+            BenchmarkId::new(type_name::<Interpreter>(), size),
+            &call,
+            |b, i| {
+                b.iter_batched(
+                    || i.clone(),
+                    |call| interpreter.run(call),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Memoized calls make the naive algorithm linear in its input.
+fn memoization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tortuga Memoized Fibonnaci");
+
+    for n in [10, 20, 30] {
+        let program: Program = format!("{}\nfibonacci({})", FIBONACCI, n)
+            .parse()
+            .expect("Invalid program.");
+
+        group.bench_with_input(
+            BenchmarkId::new(type_name::<Interpreter>(), n),
+            &program,
+            |b, i| {
+                b.iter_batched(
+                    || i.clone(),
+                    |program| Interpreter::default().with_memoization(1024).run(program),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Independent recursive calls are evaluated on a pool of threads.
+fn parallelism(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tortuga Parallel Fibonnaci");
+    let mut interpreter = Interpreter::default()
+        .with_parallelism()
+        .expect("Unable to start the thread pool.");
+    let definitions: Program = FIBONACCI.parse().expect("Invalid definitions.");
+
+    interpreter
+        .run(definitions)
+        .expect("Unable to define functions.");
+
+    for n in [10, 15, 20] {
+        let call: Program = format!("fibonacci({})", n).parse().expect("Invalid call.");
+
+        group.bench_with_input(
+            BenchmarkId::new(type_name::<Interpreter>(), n),
+            &call,
+            |b, i| {
+                b.iter_batched(
+                    || i.clone(),
+                    |call| interpreter.run(call),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
 #[cfg(unix)]
 criterion_group! {
     name = benches;
     config = {
         Criterion::default().with_profiler(PProfProfiler::new(100, Output::Protobuf))
     };
-    targets = benchmarks
+    targets = benchmarks, environment_size, memoization, parallelism
 }
 
 #[cfg(not(unix))]
-criterion_group!(benches, benchmarks);
+criterion_group!(
+    benches,
+    benchmarks,
+    environment_size,
+    memoization,
+    parallelism
+);
 
 criterion_main!(benches);
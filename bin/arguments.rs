@@ -1,7 +1,7 @@
 use crate::CommandLineError;
-use clap::{AppSettings, Args, Parser, Subcommand};
+use clap::{AppSettings, Args, Parser, Subcommand, ValueEnum};
 use std::fs::File;
-use std::io::{stdin, Read};
+use std::io::{stdin, Cursor, Read};
 use std::path::PathBuf;
 use tracing::subscriber::set_global_default;
 use tracing::Level;
@@ -36,35 +36,56 @@ pub struct Input {
     pub path: Option<PathBuf>,
     /// An inline expression to use as input.
     #[clap(short, long, conflicts_with("path"), forbid_empty_values(true))]
-    pub expression: String,
+    pub expression: Option<String>,
 }
 
-impl ToString for Input {
-    fn to_string(&self) -> String {
-        if !self.expression.is_empty() {
-            return self.expression.clone();
+impl Input {
+    /// Opens the Tortuga source for this [`Input`] as a [`Read`]er, without reading it into memory.
+    pub fn to_reader(&self) -> Box<dyn Read> {
+        if let Some(expression) = self.expression.as_ref() {
+            return Box::new(Cursor::new(expression.clone().into_bytes()));
         }
 
-        let mut buffer = String::new();
-        let result = match self.path.as_ref() {
-            None => stdin().read_to_string(&mut buffer),
-            Some(path) => File::open(path)
-                .expect(
-                    format!(
-                        "Unable to open file at {}.",
-                        path.as_os_str().to_string_lossy()
-                    )
-                    .as_str(),
+        match self.path.as_ref() {
+            None => Box::new(stdin()),
+            Some(path) => Box::new(File::open(path).unwrap_or_else(|_| {
+                panic!(
+                    "Unable to open file at {}.",
+                    path.as_os_str().to_string_lossy()
                 )
-                .read_to_string(&mut buffer),
-        };
+            })),
+        }
+    }
+
+    /// Reads the Tortuga source for this [`Input`] into a [`String`].
+    pub fn to_source(&self) -> String {
+        let mut buffer = String::new();
 
-        result.expect("Unable to read input to a string.");
+        self.to_reader()
+            .read_to_string(&mut buffer)
+            .expect("Unable to read input to a string.");
 
         buffer
     }
 }
 
+/// How to handle warnings about deprecated functionality.
+#[derive(Args, Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Warnings {
+    /// Fail instead of continuing when the input uses deprecated functionality.
+    #[clap(long)]
+    pub deny_warnings: bool,
+}
+
+/// The format of the output of a command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored text for a terminal.
+    Text,
+    /// A single JSON document, for editors and scripts to consume.
+    Json,
+}
+
 /// Set the logging verbosity or level.
 #[derive(Args, Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Verbosity {
@@ -104,7 +125,11 @@ impl Verbosity {
 
         LogTracer::init()?;
 
-        let collector = tracing_subscriber::fmt().with_max_level(level).finish();
+        // Logs go to stderr, so they never mix with the output of a command (e.g., a JSON report).
+        let collector = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(std::io::stderr)
+            .finish();
 
         Ok(set_global_default(collector)?)
     }
@@ -119,6 +144,18 @@ pub struct PromptCommand;
 pub struct RunCommand {
     #[clap(flatten)]
     pub input: Input,
+    #[clap(flatten)]
+    pub warnings: Warnings,
+    /// The format of the output.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// The level of optimization to apply before running:
+    /// 0 runs the source as written, 1 folds constants and 2 also simplifies algebraic identities.
+    #[clap(short = 'O', long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=2))]
+    pub opt_level: u8,
+    /// Print the numbers in the result as literals in the given radix (between 2 and 36).
+    #[clap(long, value_parser = clap::value_parser!(u32).range(2..=36))]
+    pub radix: Option<u32>,
 }
 
 #[derive(Clone, Debug, Eq, Parser, PartialEq)]
@@ -126,6 +163,11 @@ pub struct RunCommand {
 pub struct ParseCommand {
     #[clap(flatten)]
     pub input: Input,
+    #[clap(flatten)]
+    pub warnings: Warnings,
+    /// The format of the output.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
 }
 
 #[derive(Clone, Debug, Eq, Parser, PartialEq)]
@@ -133,6 +175,45 @@ pub struct ParseCommand {
 pub struct ScanCommand {
     #[clap(flatten)]
     pub input: Input,
+    #[clap(flatten)]
+    pub warnings: Warnings,
+    /// The format of the output.
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// The format of the generated documentation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum DocFormat {
+    /// Markdown pages, e.g. for an mdBook.
+    Markdown,
+    /// Static HTML pages.
+    Html,
+}
+
+#[derive(Clone, Debug, Eq, Parser, PartialEq)]
+/// Generates reference documentation for the functions declared in the given files.
+pub struct DocCommand {
+    /// The paths of the files to document; directories include all of their `.ta` files, recursively.
+    #[clap(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// The format of the generated documentation.
+    #[clap(short, long, value_enum, default_value_t = DocFormat::Markdown)]
+    pub format: DocFormat,
+    /// A directory to write one page per module (and an index) to, instead of printing to stdout.
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Eq, Parser, PartialEq)]
+/// Runs the assertions in test files and reports the ones that fail.
+pub struct TestCommand {
+    /// The test files to run; directories include all of their `_test.ta` files, recursively.
+    #[clap(default_value = ".")]
+    pub paths: Vec<PathBuf>,
+    /// Only run the assertions whose name (prefixed by the path of their file) contains this text.
+    #[clap(short, long)]
+    pub filter: Option<String>,
 }
 
 /// The sub-command to execute.
@@ -142,6 +223,8 @@ pub enum Commands {
     Run(RunCommand),
     Scan(ScanCommand),
     Parse(ParseCommand),
+    Doc(DocCommand),
+    Test(TestCommand),
 }
 
 impl Default for Commands {
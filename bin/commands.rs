@@ -0,0 +1,175 @@
+//! Meta-commands to inspect and manage the state of the interactive prompt.
+//! A meta-command is a line of input that starts with a colon (e.g., `:help`).
+
+use crate::CommandLineError;
+use std::fs;
+use std::io::{stderr, stdout, Stderr, Stdout, Write};
+use tortuga::compiler::{infer, Type};
+use tortuga::grammar::Symbol;
+use tortuga::runtime::Environment;
+use tortuga::{Interpreter, PrettyPrinter, Program, Scanner, Value};
+
+/// The meta-commands, their arguments and a description of each, as listed by `:help`.
+const HELP: &[(&str, &str, &str)] = &[
+    (":help", "", "Lists the available meta-commands."),
+    (
+        ":env",
+        "",
+        "Lists the names defined in the environment with their values or declarations.",
+    ),
+    (
+        ":type",
+        "<expr>",
+        "Prints the type of an expression, without evaluating it.",
+    ),
+    (":load", "<file>", "Runs a file in the current environment."),
+    (
+        ":reset",
+        "",
+        "Clears all the names defined in the environment.",
+    ),
+    (":tokens", "<expr>", "Prints the tokens of an expression."),
+    (":ast", "<expr>", "Prints the syntax tree of an expression."),
+];
+
+/// The names of the meta-commands, including the leading colon.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    HELP.iter().map(|(command, _, _)| *command)
+}
+
+/// A meta-command entered at the prompt.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Command<'a> {
+    Help,
+    Env,
+    Type(&'a str),
+    Load(&'a str),
+    Reset,
+    Tokens(&'a str),
+    Ast(&'a str),
+}
+
+impl<'a> Command<'a> {
+    /// Parses a meta-command from a line of input.
+    /// Returns [`None`] for input that is not a meta-command.
+    pub fn parse(input: &'a str) -> Option<Result<Self, CommandLineError>> {
+        let input = input.trim().strip_prefix(':')?;
+        let (name, argument) = match input.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input, ""),
+        };
+
+        let command = match (name, argument) {
+            ("help", _) => Command::Help,
+            ("env", _) => Command::Env,
+            ("reset", _) => Command::Reset,
+            ("type" | "load" | "tokens" | "ast", "") => {
+                return Some(Err(CommandLineError::MissingArgument(name.to_string())))
+            }
+            ("type", expression) => Command::Type(expression),
+            ("load", path) => Command::Load(path),
+            ("tokens", expression) => Command::Tokens(expression),
+            ("ast", expression) => Command::Ast(expression),
+            _ => return Some(Err(CommandLineError::UnknownCommand(name.to_string()))),
+        };
+
+        Some(Ok(command))
+    }
+
+    /// Executes this meta-command against the interpreter used by the prompt.
+    pub fn execute(&self, interpreter: &mut Interpreter) -> Result<(), CommandLineError> {
+        let mut printer = PrettyPrinter::new(stdout(), stderr());
+
+        match *self {
+            Command::Help => print_help(),
+            Command::Env => print_environment(&mut printer, interpreter.environment()),
+            Command::Type(expression) => match expression.parse::<Program>() {
+                Ok(program) => {
+                    let types = types(interpreter.environment());
+                    let names = types
+                        .iter()
+                        .map(|(symbol, kind)| (symbol.as_str(), kind.clone()));
+
+                    Ok(writeln!(stdout(), "=> {}", infer(&program, names))?)
+                }
+                Err(error) => Ok(printer.print_compilation_error(error)?),
+            },
+            Command::Load(path) => {
+                let source = fs::read_to_string(path)?;
+
+                match source.parse::<Program>() {
+                    Ok(program) => match interpreter.run(program) {
+                        Ok(value) => writeln!(stdout(), "=> {}", value)?,
+                        Err(error) => writeln!(stderr(), "=> {}", error)?,
+                    },
+                    Err(error) => printer.print_compilation_error(error)?,
+                }
+
+                Ok(())
+            }
+            Command::Reset => {
+                *interpreter = Interpreter::default();
+
+                Ok(())
+            }
+            Command::Tokens(expression) => {
+                for result in Scanner::from(expression) {
+                    match result {
+                        Ok(token) => printer.print_token(token)?,
+                        Err(error) => printer.print_lexical_error(error)?,
+                    }
+                }
+
+                Ok(())
+            }
+            Command::Ast(expression) => match expression.parse::<Program>() {
+                Ok(program) => Ok(printer.print_program(&program)?),
+                Err(error) => Ok(printer.print_compilation_error(error)?),
+            },
+        }
+    }
+}
+
+fn print_help() -> Result<(), CommandLineError> {
+    let mut out = stdout();
+
+    for (command, argument, description) in HELP {
+        let usage = format!("{command} {argument}");
+
+        writeln!(out, "{:<16}{description}", usage.trim_end())?;
+    }
+
+    Ok(())
+}
+
+/// Prints each name in the environment with its value, or with the signatures of its declarations for functions.
+fn print_environment(
+    printer: &mut PrettyPrinter<Stdout, Stderr>,
+    environment: &Environment,
+) -> Result<(), CommandLineError> {
+    for (symbol, value) in environment.variables() {
+        match value {
+            Value::FunctionReference(reference) => {
+                if reference.function().is_builtin() {
+                    printer.print(format_args!("@{} (built-in)\n", symbol.as_str()))?;
+                }
+
+                for signature in reference.function().signatures() {
+                    printer.print_signature(&signature)?;
+                    printer.print('\n')?;
+                }
+            }
+            value => printer.print(format_args!("@{} = {value}\n", symbol.as_str()))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// The types of the names in the environment, for type inference.
+fn types(environment: &Environment) -> Vec<(Symbol, Type)> {
+    environment
+        .variables()
+        .map(|(symbol, value)| (symbol, Type::from(value)))
+        .collect()
+}
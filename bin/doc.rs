@@ -0,0 +1,160 @@
+//! Generates reference documentation for the functions declared in Tortuga source files.
+
+use crate::arguments::DocFormat;
+use crate::CommandLineError;
+use std::fs;
+use std::io::{stderr, stdout, Write};
+use std::path::{Path, PathBuf};
+use tortuga::documentation::{html_index, markdown_index, Module};
+use tortuga::{PrettyPrinter, Program};
+
+/// Document each of the given files as a module, either printing the pages or writing them to an output directory.
+/// Files that fail to parse are reported and skipped, then the command fails once the other modules are documented.
+pub fn document_files(
+    paths: &[PathBuf],
+    format: DocFormat,
+    output: Option<&Path>,
+) -> Result<(), CommandLineError> {
+    let mut printer = PrettyPrinter::new(stdout(), stderr());
+    let mut names: Vec<String> = Vec::new();
+    let mut modules = Vec::new();
+    let mut failed = 0;
+
+    for (path, name) in source_files(paths)? {
+        if names.contains(&name) {
+            return Err(CommandLineError::DuplicateModule(name));
+        }
+
+        match fs::read_to_string(&path)?.parse::<Program>() {
+            Ok(program) => modules.push(Module::new(name.as_str(), &program)),
+            Err(error) => {
+                writeln!(stderr(), "Unable to parse {}.", path.display())?;
+                printer.print_compilation_error(error)?;
+                failed += 1;
+            }
+        }
+
+        names.push(name);
+    }
+
+    write_modules(modules.as_slice(), format, output)?;
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(CommandLineError::FailedModules(failed))
+    }
+}
+
+/// Prints the pages of the modules, or writes them to the output directory.
+fn write_modules(
+    modules: &[Module],
+    format: DocFormat,
+    output: Option<&Path>,
+) -> Result<(), CommandLineError> {
+    match output {
+        Some(directory) => write_pages(directory, modules, format),
+        None => {
+            let mut out = stdout();
+
+            for module in modules.iter() {
+                writeln!(out, "{}", render(module, format))?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Expands directories to the Tortuga source files they contain, recursively and in sorted order, along with the name of their module.
+/// A file in a directory is named by its path relative to that directory (e.g., `math/trig.ta` is `math.trig`); any other file by its stem.
+/// Hidden directories are skipped.
+fn source_files(paths: &[PathBuf]) -> Result<Vec<(PathBuf, String)>, CommandLineError> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            collect(path, path, &mut files)?;
+        } else {
+            files.push((
+                path.clone(),
+                module_name(Path::new(path.file_stem().unwrap_or_default())),
+            ));
+        }
+    }
+
+    Ok(files)
+}
+
+fn collect(
+    root: &Path,
+    directory: &Path,
+    files: &mut Vec<(PathBuf, String)>,
+) -> Result<(), CommandLineError> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            let hidden = entry
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+            if !hidden {
+                collect(root, entry.as_path(), files)?;
+            }
+        } else if entry.extension().is_some_and(|extension| extension == "ta") {
+            let name = module_name(entry.strip_prefix(root)?.with_extension("").as_path());
+
+            files.push((entry, name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Joins the components of a path (without its extension) into the name of a module.
+fn module_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn write_pages(
+    directory: &Path,
+    modules: &[Module],
+    format: DocFormat,
+) -> Result<(), CommandLineError> {
+    let extension = match format {
+        DocFormat::Markdown => "md",
+        DocFormat::Html => "html",
+    };
+
+    fs::create_dir_all(directory)?;
+
+    for module in modules {
+        let path = directory.join(format!("{}.{extension}", module.name()));
+
+        fs::write(path, render(module, format))?;
+    }
+
+    let index = match format {
+        DocFormat::Markdown => markdown_index(modules),
+        DocFormat::Html => html_index(modules),
+    };
+
+    fs::write(directory.join(format!("index.{extension}")), index)?;
+
+    Ok(())
+}
+
+fn render(module: &Module, format: DocFormat) -> String {
+    match format {
+        DocFormat::Markdown => module.to_markdown(),
+        DocFormat::Html => module.to_html(),
+    }
+}
@@ -15,4 +15,20 @@ pub enum CommandLineError {
     PromptError(#[from] rustyline::error::ReadlineError),
     #[error(transparent)]
     Runtime(#[from] tortuga::RuntimeError),
+    #[error(transparent)]
+    Stream(#[from] tortuga::compiler::StreamError),
+    #[error("Unable to write the output as JSON. {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Found {0} warning(s), but warnings are denied.")]
+    DeniedWarnings(usize),
+    #[error("{0} test(s) failed.")]
+    FailedTests(usize),
+    #[error("More than one file is documented as the module {0}.")]
+    DuplicateModule(String),
+    #[error("{0} file(s) could not be documented.")]
+    FailedModules(usize),
+    #[error("Unknown command :{0}. Enter :help to list the available commands.")]
+    UnknownCommand(String),
+    #[error("The :{0} command requires an argument.")]
+    MissingArgument(String),
 }
@@ -0,0 +1,120 @@
+//! Print the output of a command as a single JSON document, for editors and scripts to consume.
+//!
+//! Every document has the same shape: the `result` of the command (e.g., the tokens, syntax tree or value),
+//! followed by the `errors`, `warnings` and `lints` reported along the way.
+
+use crate::CommandLineError;
+use serde::Serialize;
+use std::fmt::Display;
+use std::io::{stdout, Write};
+use tortuga::compiler::{Lint, Warning};
+use tortuga::RuntimeError;
+
+/// The output of a command along with its diagnostics.
+#[derive(Debug, Serialize)]
+pub struct Report<T: Serialize> {
+    result: T,
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    lints: Vec<Diagnostic>,
+}
+
+/// A problem reported by the compiler or runtime.
+/// The details are the serialized error or warning, when it has a structure beyond its message.
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    details: serde_json::Value,
+}
+
+impl Diagnostic {
+    /// Creates a [`Diagnostic`] from an error or warning, with its message and details.
+    pub fn new<D: Display + Serialize>(diagnostic: &D) -> Result<Self, CommandLineError> {
+        Ok(Diagnostic {
+            message: diagnostic.to_string(),
+            code: None,
+            details: serde_json::to_value(diagnostic)?,
+        })
+    }
+}
+
+impl TryFrom<&Warning> for Diagnostic {
+    type Error = CommandLineError;
+
+    fn try_from(warning: &Warning) -> Result<Self, Self::Error> {
+        Ok(Diagnostic {
+            code: Some(warning.code()),
+            ..Diagnostic::new(warning)?
+        })
+    }
+}
+
+impl TryFrom<&Lint> for Diagnostic {
+    type Error = CommandLineError;
+
+    fn try_from(lint: &Lint) -> Result<Self, Self::Error> {
+        Ok(Diagnostic {
+            code: Some(lint.code()),
+            ..Diagnostic::new(lint)?
+        })
+    }
+}
+
+impl TryFrom<&RuntimeError> for Diagnostic {
+    type Error = CommandLineError;
+
+    /// Only syntax and semantic errors have details; the other runtime errors are described by their message.
+    fn try_from(error: &RuntimeError) -> Result<Self, Self::Error> {
+        let details = match error {
+            RuntimeError::Syntax(error) => serde_json::to_value(error)?,
+            RuntimeError::Semantic(errors) => serde_json::to_value(errors)?,
+            _ => serde_json::Value::Null,
+        };
+
+        Ok(Diagnostic {
+            message: error.to_string(),
+            code: None,
+            details,
+        })
+    }
+}
+
+impl<T: Serialize> Report<T> {
+    /// Creates a [`Report`] of the given result and diagnostics.
+    pub fn new(
+        result: T,
+        errors: Vec<Diagnostic>,
+        warnings: &[Warning],
+        lints: &[Lint],
+    ) -> Result<Self, CommandLineError> {
+        Ok(Report {
+            result,
+            errors,
+            warnings: warnings
+                .iter()
+                .map(Diagnostic::try_from)
+                .collect::<Result<_, _>>()?,
+            lints: lints
+                .iter()
+                .map(Diagnostic::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Prints this [`Report`] to stdout on a single line.
+    /// When warnings are denied, the report is printed before any warning fails the command.
+    pub fn print(&self, deny_warnings: bool) -> Result<(), CommandLineError> {
+        let json = serde_json::to_string(self)?;
+
+        writeln!(stdout(), "{json}")?;
+
+        if deny_warnings && !self.warnings.is_empty() {
+            Err(CommandLineError::DeniedWarnings(self.warnings.len()))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -1,19 +1,27 @@
 mod arguments;
+mod commands;
+mod doc;
 mod errors;
+mod json;
 mod parse;
 mod prompt;
 mod run;
 mod scan;
+mod test;
+mod warnings;
 
 pub use errors::CommandLineError;
 use prompt::run_prompt;
 use run::run;
 
 use std::io::ErrorKind::BrokenPipe;
+use std::process;
 
 use crate::arguments::{Arguments, Commands};
+use crate::doc::document_files;
 use crate::parse::parse_file;
 use crate::scan::scan_file;
+use crate::test::test_files;
 use mimalloc::MiMalloc;
 
 #[global_allocator]
@@ -22,7 +30,10 @@ static GLOBAL: MiMalloc = MiMalloc;
 fn main() {
     match execute() {
         Err(CommandLineError::IO(error)) if error.kind() == BrokenPipe => (),
-        Err(error) => eprintln!("{error}"),
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(1);
+        }
         Ok(_) => (),
     }
 }
@@ -37,8 +48,28 @@ fn execute() -> Result<(), CommandLineError> {
 fn run_subcommand(arguments: Arguments) -> Result<(), CommandLineError> {
     match arguments.command.unwrap_or_default() {
         Commands::Prompt(_) => run_prompt(),
-        Commands::Run(command) => run(command.input.to_string().as_str()),
-        Commands::Parse(command) => parse_file(command.input.to_string().as_str()),
-        Commands::Scan(command) => scan_file(command.input.to_string().as_str()),
+        Commands::Run(command) => run(
+            command.input.to_source().as_str(),
+            command.opt_level.into(),
+            command.warnings.deny_warnings,
+            command.radix,
+            command.format,
+        ),
+        Commands::Parse(command) => parse_file(
+            command.input.to_source().as_str(),
+            command.warnings.deny_warnings,
+            command.format,
+        ),
+        Commands::Scan(command) => scan_file(
+            command.input.to_reader(),
+            command.warnings.deny_warnings,
+            command.format,
+        ),
+        Commands::Doc(command) => document_files(
+            command.paths.as_slice(),
+            command.format,
+            command.output.as_deref(),
+        ),
+        Commands::Test(command) => test_files(command.paths.as_slice(), command.filter.as_deref()),
     }
 }
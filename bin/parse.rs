@@ -1,16 +1,40 @@
 //! Generates a syntax tree for a file and prints the scanned structure.
 
+use crate::arguments::OutputFormat;
+use crate::json::{Diagnostic, Report};
+use crate::warnings::{parse_with_warnings, report_lints, report_warnings};
 use crate::CommandLineError;
 use std::io::{stderr, stdout};
-use tortuga::{PrettyPrinter, Program};
+use tortuga::compiler::lint;
+use tortuga::PrettyPrinter;
+
+/// Pretty print the syntax tree for the given source, or report it as JSON.
+pub fn parse_file(
+    source: &str,
+    deny_warnings: bool,
+    format: OutputFormat,
+) -> Result<(), CommandLineError> {
+    let (program, warnings) = parse_with_warnings(source);
+    let lints = lint(source);
+
+    if format == OutputFormat::Json {
+        let (program, errors) = match program {
+            Ok(program) => (Some(program), Vec::new()),
+            Err(error) => (None, vec![Diagnostic::new(&error)?]),
+        };
+
+        return Report::new(program, errors, warnings.as_slice(), lints.as_slice())?
+            .print(deny_warnings);
+    }
 
-/// Pretty print the syntax tree for the given source.
-pub fn parse_file(source: &str) -> Result<(), CommandLineError> {
     let mut printer = PrettyPrinter::new(stdout(), stderr());
 
-    match source.parse::<Program>() {
+    report_lints(&mut printer, lints.as_slice())?;
+    report_warnings(&mut printer, warnings.as_slice(), deny_warnings)?;
+
+    match program {
         Ok(program) => printer.print_program(&program)?,
-        Err(error) => printer.print_syntactical_error(error)?,
+        Err(error) => printer.print_compilation_error(error)?,
     }
 
     Ok(())
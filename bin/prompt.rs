@@ -1,43 +1,101 @@
 //! Terminal prompt reading and printing with editing and history.
 
+use crate::commands::{self, Command};
+use crate::warnings::{parse_with_warnings, report_lints, report_warnings};
 use crate::CommandLineError;
 use colored::*;
-use rustyline::completion::Completer;
-use rustyline::config::Config;
+use rustyline::completion::{Completer, Pair};
+use rustyline::config::{CompletionType, Config};
 use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
-use rustyline::line_buffer::LineBuffer;
 use rustyline::validate::{ValidationContext, ValidationResult, Validator};
-use rustyline::{error::ReadlineError, Editor, Helper};
+use rustyline::{error::ReadlineError, Context, Editor, Helper};
+use std::borrow::Cow;
+use std::fs;
 use std::io::{stderr, stdout, Write};
-use tortuga::Program;
-use tortuga::{about, Interpreter};
-use tracing::error;
+use std::path::PathBuf;
+use tortuga::compiler::{lint, Kind};
+use tortuga::runtime::Environment;
+use tortuga::{about, Interpreter, PrettyPrinter, Program, Scanner, Value};
+use tracing::warn;
+use unicode_xid::UnicodeXID;
 
-struct PromptHelper;
+/// Highlights, completes and hints the input to the prompt.
+/// Keeps a snapshot of the names defined in the interpreter's environment, along with the signatures of their declarations.
+#[derive(Default)]
+struct PromptHelper {
+    names: Vec<(String, Vec<String>)>,
+}
+
+/// The maximum number of entries kept in the history file.
+const HISTORY_SIZE: usize = 1000;
 
 /// The prompt used to communicate with a user.
+/// The history of previous sessions is loaded from (and saved to) a file in the user's data directory, if any.
 pub struct Prompt {
     line: usize,
     editor: Editor<PromptHelper>,
+    history: Option<PathBuf>,
 }
 
 impl Default for Prompt {
     fn default() -> Self {
         let config = Config::builder()
             .auto_add_history(true)
+            .completion_type(CompletionType::List)
+            .max_history_size(HISTORY_SIZE)
             .tab_stop(2)
             .indent_size(2)
             .build();
         let mut editor = Editor::<PromptHelper>::with_config(config);
+        let history = dirs_next::data_dir()
+            .map(|directory| directory.join(about::PROGRAM).join("history.txt"));
 
-        editor.set_helper(Some(PromptHelper));
+        editor.set_helper(Some(PromptHelper::default()));
 
-        Prompt { line: 1, editor }
+        // The history file does not exist until the end of the first session.
+        if let Some(path) = history.as_ref() {
+            let _ = editor.load_history(path);
+        }
+
+        Prompt {
+            line: 1,
+            editor,
+            history,
+        }
     }
 }
 
 impl Prompt {
+    /// Updates the names used to complete and hint the input to those currently defined in the environment.
+    pub fn update_names(&mut self, environment: &Environment) {
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.names = environment
+                .variables()
+                .map(|(symbol, value)| (symbol.as_str().to_string(), signatures(value)))
+                .collect();
+        }
+    }
+
+    /// Saves the history of this session (and the previous ones) to the history file.
+    /// Failures are logged, since the history is not essential to the prompt.
+    pub fn save_history(&mut self) {
+        let path = match self.history.as_ref() {
+            Some(path) => path,
+            None => return,
+        };
+
+        if let Some(directory) = path.parent() {
+            if let Err(error) = fs::create_dir_all(directory) {
+                warn!("Unable to create the directory for the prompt history. {error}");
+            }
+        }
+
+        if let Err(error) = self.editor.save_history(path) {
+            warn!("Unable to save the prompt history. {error}");
+        }
+    }
+
     /// Read input from the user via a terminal prompt.
     pub fn prompt(&mut self) -> Result<Option<String>, CommandLineError> {
         let prompt = format!(
@@ -61,57 +119,263 @@ impl Prompt {
 impl Helper for PromptHelper {}
 
 impl Completer for PromptHelper {
-    type Candidate = String;
+    type Candidate = Pair;
+
+    /// Completes the name of a meta-command at the start of the line, or the name of a variable anywhere else.
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> Result<(usize, Vec<Pair>), ReadlineError> {
+        let start = word_start(line, pos);
+        let word = &line[start..pos];
+        let is_command = line[..start].trim_start() == ":";
 
-    fn update(&self, _line: &mut LineBuffer, _start: usize, _elected: &str) {
-        unreachable!()
+        let candidates: Vec<Pair> = if is_command {
+            commands::names()
+                .filter_map(|command| command.strip_prefix(':'))
+                .filter(|command| command.starts_with(word))
+                .map(|command| pair(command.to_string()))
+                .collect()
+        } else if word.is_empty() {
+            Vec::new()
+        } else {
+            self.names
+                .iter()
+                .map(|(name, _)| name)
+                .filter(|name| name.starts_with(word))
+                .map(|name| pair(name.clone()))
+                .collect()
+        };
+
+        Ok((start, candidates))
     }
 }
 
-impl Highlighter for PromptHelper {}
+impl Highlighter for PromptHelper {
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        match line.trim_start().strip_prefix(':') {
+            Some(command) => {
+                let end = line.len() - command.len() + word_end(command);
+
+                Cow::Owned(format!("{}{}", line[..end].yellow().bold(), &line[end..]))
+            }
+            None => Cow::Owned(highlight_code(line)),
+        }
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+
+    fn highlight_char(&self, _: &str, _: usize) -> bool {
+        true
+    }
+}
 
 impl Hinter for PromptHelper {
     type Hint = String;
+
+    /// Hints the signatures of the declarations of the function named at the end of the line.
+    /// No hint is shown while declaring a function, since the name may be new.
+    fn hint(&self, line: &str, pos: usize, _: &Context<'_>) -> Option<String> {
+        let start = word_start(line, pos);
+        let word = &line[start..pos];
+
+        if pos < line.len() || word.is_empty() || line[..start].ends_with('@') {
+            return None;
+        }
+
+        match self.names.iter().find(|(name, _)| name == word) {
+            Some((_, signatures)) if !signatures.is_empty() => {
+                Some(format!("  {}", signatures.join(" | ")))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Validator for PromptHelper {
     fn validate(&self, ctx: &mut ValidationContext) -> Result<ValidationResult, ReadlineError> {
-        if ctx.input().trim().is_empty() {
+        if ctx.input().trim().is_empty() || ctx.input().trim_start().starts_with(':') {
             return Ok(ValidationResult::Valid(None));
         }
 
+        // Invalid input is accepted, so its errors are reported in full when it is evaluated.
         match ctx.input().parse::<Program>() {
-            Ok(_) => Ok(ValidationResult::Valid(None)),
-            Err(error) if error.is_complete() => {
-                Ok(ValidationResult::Invalid(Some(format!("\t{}", error))))
+            Err(error) if !error.is_complete() => Ok(ValidationResult::Incomplete),
+            _ => Ok(ValidationResult::Valid(None)),
+        }
+    }
+}
+
+/// The signatures of the declarations of a function, or none for other values.
+fn signatures(value: &Value) -> Vec<String> {
+    let reference = match value {
+        Value::FunctionReference(reference) => reference,
+        _ => return Vec::new(),
+    };
+
+    reference
+        .function()
+        .signatures()
+        .iter()
+        .filter_map(|signature| {
+            let mut buffer = Vec::new();
+
+            PrettyPrinter::new(&mut buffer, Vec::new())
+                .print_signature(signature)
+                .ok()?;
+
+            String::from_utf8(buffer).ok()
+        })
+        .collect()
+}
+
+fn pair(name: String) -> Pair {
+    Pair {
+        display: name.clone(),
+        replacement: name,
+    }
+}
+
+/// The start of the name that ends at the given position.
+fn word_start(line: &str, pos: usize) -> usize {
+    line[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_xid_continue())
+        .last()
+        .map_or(pos, |(index, _)| index)
+}
+
+/// The end of the name that starts at the beginning of the text.
+fn word_end(text: &str) -> usize {
+    text.find(|c: char| !c.is_xid_continue())
+        .unwrap_or(text.len())
+}
+
+/// Colors the tokens in the input by their kind.
+/// The text between tokens is kept as is, except that comments are dimmed.
+fn highlight_code(line: &str) -> String {
+    let mut highlighted = String::with_capacity(line.len());
+    let mut offset = 0;
+
+    for result in Scanner::from(line) {
+        let (start, lexeme) = match result.as_ref() {
+            Ok(token) => (token.lexeme().start().offset(), token.as_str()),
+            Err(error) => (error.start().offset(), error.as_str()),
+        };
+
+        let end = start + lexeme.len();
+
+        if start < offset || end > line.len() {
+            continue;
+        }
+
+        highlight_between(&mut highlighted, &line[offset..start]);
+
+        let colored = match result.as_ref().map(|token| token.kind()) {
+            Err(_) => lexeme.red().underline(),
+            Ok(Kind::Number) => lexeme.blue(),
+            Ok(Kind::Identifier) => lexeme.normal(),
+            Ok(Kind::DocComment) => lexeme.dimmed().italic(),
+            Ok(Kind::At | Kind::Underscore | Kind::Exclamation) => lexeme.yellow().bold(),
+            Ok(
+                Kind::Equal
+                | Kind::NotEqual
+                | Kind::LessThan
+                | Kind::LessThanOrEqualTo
+                | Kind::GreaterThan
+                | Kind::GreaterThanOrEqualTo,
+            ) => lexeme.magenta(),
+            Ok(
+                Kind::LeftParenthesis
+                | Kind::RightParenthesis
+                | Kind::LeftBrace
+                | Kind::RightBrace
+                | Kind::LeftBracket
+                | Kind::RightBracket
+                | Kind::Comma,
+            ) => lexeme.normal(),
+            Ok(_) => lexeme.green(),
+        };
+
+        highlighted.push_str(colored.to_string().as_str());
+        offset = end;
+    }
+
+    highlight_between(&mut highlighted, &line[offset..]);
+    highlighted
+}
+
+/// Dims the comments in the text between two tokens.
+fn highlight_between(highlighted: &mut String, text: &str) {
+    for line in text.split_inclusive('\n') {
+        match line.find(';') {
+            Some(index) => {
+                highlighted.push_str(&line[..index]);
+                highlighted.push_str(line[index..].dimmed().to_string().as_str());
             }
-            Err(_) => Ok(ValidationResult::Incomplete),
+            None => highlighted.push_str(line),
         }
     }
 }
 
+/// Parses and runs a line of input, reporting its diagnostics the same way as `tortuga run`.
+/// Warnings are never denied in the prompt.
+fn evaluate(interpreter: &mut Interpreter, input: &str) -> Result<(), CommandLineError> {
+    let mut printer = PrettyPrinter::new(stdout(), stderr());
+    let (program, warnings) = parse_with_warnings(input);
+
+    report_lints(&mut printer, lint(input).as_slice())?;
+    report_warnings(&mut printer, warnings.as_slice(), false)?;
+
+    match program
+        .map_err(Into::into)
+        .and_then(|program| interpreter.run(program))
+    {
+        Ok(value) => writeln!(stdout(), "=> {}", value)?,
+        Err(error) => writeln!(stderr(), "=> {}", error)?,
+    }
+
+    Ok(())
+}
+
 /// Runs the read-evaluate-print loop.
 pub fn run_prompt() -> Result<(), CommandLineError> {
     let mut user = Prompt::default();
     let mut interpreter = Interpreter::default();
 
     println!("{} {}", about::PROGRAM.green(), about::VERSION);
-    println!("{}", "Press Ctrl-C to exit.".yellow().bold());
+    println!(
+        "{}",
+        "Press Ctrl-C to exit. Enter :help for commands."
+            .yellow()
+            .bold()
+    );
     println!();
 
     loop {
+        user.update_names(interpreter.environment());
+
         match user.prompt()? {
-            None => return Ok(()),
-            Some(input) if input.trim().is_empty() => continue,
-            Some(input) => {
-                match input.as_str().parse::<Program>() {
-                    Ok(program) => match interpreter.run(program) {
-                        Ok(value) => writeln!(stdout(), "=> {}", value)?,
-                        Err(error) => writeln!(stderr(), "=> {}", error)?,
-                    },
-                    Err(error) => error!("{:?}", error),
-                };
+            None => {
+                user.save_history();
+                return Ok(());
             }
+            Some(input) if input.trim().is_empty() => continue,
+            Some(input) => match Command::parse(input.as_str()) {
+                Some(command) => {
+                    if let Err(error) =
+                        command.and_then(|command| command.execute(&mut interpreter))
+                    {
+                        writeln!(stderr(), "=> {}", error)?;
+                    }
+                }
+                None => evaluate(&mut interpreter, input.as_str())?,
+            },
         }
     }
 }
@@ -1,13 +1,62 @@
 //! Execute a Tortuga source.
 
+use crate::arguments::OutputFormat;
+use crate::json::{Diagnostic, Report};
+use crate::warnings::{parse_with_warnings, report_lints, report_warnings};
 use crate::CommandLineError;
 use std::io::{stderr, stdout, Write};
-use tortuga::Interpreter;
+use tortuga::compiler::{lint, OptimizationLevel};
+use tortuga::runtime::NumberFormat;
+use tortuga::{Interpreter, PrettyPrinter};
 
-/// Parses the given source as a Tortuga [`Program`] and executes it.
-pub fn run(source: &str) -> Result<(), CommandLineError> {
-    match Interpreter::build_then_run(source) {
-        Ok(value) => Ok(writeln!(stdout(), "{}", value)?),
+/// Parses the given source as a Tortuga [`Program`](tortuga::Program), optimizes it and executes it.
+/// When warnings are denied, a source that uses deprecated functionality is not executed.
+/// With a radix, the numbers in the result are printed as literals in that radix.
+/// As JSON, the result is reported as a value instead, so the radix does not apply.
+pub fn run(
+    source: &str,
+    level: OptimizationLevel,
+    deny_warnings: bool,
+    radix: Option<u32>,
+    format: OutputFormat,
+) -> Result<(), CommandLineError> {
+    let (program, warnings) = parse_with_warnings(source);
+    let lints = lint(source);
+
+    if format == OutputFormat::Json {
+        let result =
+            if deny_warnings && !warnings.is_empty() {
+                None
+            } else {
+                Some(program.map_err(Into::into).and_then(|program| {
+                    Interpreter::default().with_optimization(level).run(program)
+                }))
+            };
+
+        let (value, errors) = match result {
+            Some(Ok(value)) => (Some(value), Vec::new()),
+            Some(Err(error)) => (None, vec![Diagnostic::try_from(&error)?]),
+            None => (None, Vec::new()),
+        };
+
+        return Report::new(value, errors, warnings.as_slice(), lints.as_slice())?
+            .print(deny_warnings);
+    }
+
+    let mut printer = PrettyPrinter::new(stdout(), stderr());
+
+    report_lints(&mut printer, lints.as_slice())?;
+    report_warnings(&mut printer, warnings.as_slice(), deny_warnings)?;
+
+    let result = program
+        .map_err(Into::into)
+        .and_then(|program| Interpreter::default().with_optimization(level).run(program));
+
+    match result {
+        Ok(value) => match radix.map(NumberFormat::new) {
+            Some(Ok(format)) => Ok(writeln!(stdout(), "{}", format.format_value(&value))?),
+            _ => Ok(writeln!(stdout(), "{}", value)?),
+        },
         Err(error) => Ok(writeln!(stderr(), "{}", error)?),
     }
 }
@@ -1,25 +1,75 @@
 //! Performs Lexical Analysis on a file and prints the scanned sequence of tokens, their lexemes and attributes.
+//! The input is scanned as a stream, so it is never read into memory all at once.
 
+use crate::arguments::OutputFormat;
+use crate::json::{Diagnostic, Report};
+use crate::warnings::{report_lints, report_warnings};
 use crate::CommandLineError;
-use std::io::{stderr, stdout};
-use tortuga::{PrettyPrinter, Scanner};
+use std::io::{stderr, stdout, Read};
+use tortuga::compiler::{check_token, Lexeme, Linter, StreamError, StreamScanner, Token};
+use tortuga::PrettyPrinter;
 
-/// Pretty print the sequence of tokens for the given source.
-pub fn scan_file(source: &str) -> Result<(), CommandLineError> {
+/// Pretty print the sequence of tokens for the given input, or report them as JSON.
+pub fn scan_file<R: Read>(
+    input: R,
+    deny_warnings: bool,
+    format: OutputFormat,
+) -> Result<(), CommandLineError> {
+    match format {
+        OutputFormat::Text => print_tokens(input, deny_warnings),
+        OutputFormat::Json => report_tokens(input, deny_warnings),
+    }
+}
+
+fn print_tokens<R: Read>(input: R, deny_warnings: bool) -> Result<(), CommandLineError> {
     let mut printer = PrettyPrinter::new(stdout(), stderr());
+    let mut warnings = Vec::new();
+    let mut linter = Linter::default();
+    let mut lints = Vec::new();
 
-    for (index, result) in Scanner::from(source).enumerate() {
+    for (index, result) in StreamScanner::new(input).enumerate() {
         match result {
             Ok(token) => {
+                let token = Token::new(Lexeme::new(*token.start(), token.as_str()), *token.kind());
+
+                warnings.extend(check_token(&token));
+                lints.extend(linter.check_token(&token));
                 printer.print(format!("{}) ", index + 1))?;
                 printer.print_token(token)?;
             }
-            Err(error) => {
+            Err(StreamError::Lexical(error)) => {
                 printer.print_err(format!("{}) ", index + 1))?;
                 printer.print_lexical_error(error)?;
             }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    report_lints(&mut printer, lints.as_slice())?;
+    report_warnings(&mut printer, warnings.as_slice(), deny_warnings)
+}
+
+/// Collects the tokens and lexical errors, since the report is a single JSON document.
+fn report_tokens<R: Read>(input: R, deny_warnings: bool) -> Result<(), CommandLineError> {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut linter = Linter::default();
+    let mut lints = Vec::new();
+
+    for result in StreamScanner::new(input) {
+        match result {
+            Ok(token) => {
+                let token = Token::new(Lexeme::new(*token.start(), token.as_str()), *token.kind());
+
+                warnings.extend(check_token(&token));
+                lints.extend(linter.check_token(&token));
+                tokens.push(token.into_owned_token());
+            }
+            Err(StreamError::Lexical(error)) => errors.push(Diagnostic::new(&error)?),
+            Err(error) => return Err(error.into()),
         }
     }
 
-    Ok(())
+    Report::new(tokens, errors, warnings.as_slice(), lints.as_slice())?.print(deny_warnings)
 }
@@ -0,0 +1,112 @@
+//! Runs the assertions in Tortuga test files.
+
+use crate::CommandLineError;
+use std::fs;
+use std::io::{stderr, stdout, Write};
+use std::path::{Path, PathBuf};
+use tortuga::testing::{Assertion, Suite};
+use tortuga::PrettyPrinter;
+
+/// Runs each test file in order, printing the outcome of every assertion whose name contains the filter.
+/// Fails when an assertion fails, or when a test file cannot be parsed or run.
+pub fn test_files(paths: &[PathBuf], filter: Option<&str>) -> Result<(), CommandLineError> {
+    let mut printer = PrettyPrinter::new(stdout(), stderr());
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut filtered = 0;
+
+    for path in test_paths(paths)? {
+        let file = path.display().to_string();
+        let suite = match fs::read_to_string(&path)?.parse::<Suite>() {
+            Ok(suite) => suite,
+            Err(error) => {
+                writeln!(stderr(), "Unable to parse test file {file}.")?;
+                printer.print_compilation_error(error)?;
+                failed += 1;
+                continue;
+            }
+        };
+
+        let name = |assertion: &Assertion| format!("{file}::{}", assertion.name());
+        let result = suite.run(|assertion| {
+            let accepted = name(assertion).contains(filter.unwrap_or_default());
+
+            if !accepted {
+                filtered += 1;
+            }
+
+            accepted
+        });
+
+        match result {
+            Ok(outcomes) => {
+                for (assertion, outcome) in outcomes {
+                    printer.print_test_outcome(name(assertion).as_str(), &outcome)?;
+
+                    if outcome.is_passed() {
+                        passed += 1;
+                    } else {
+                        failed += 1;
+                    }
+                }
+            }
+            Err(error) => {
+                writeln!(stderr(), "Unable to run test file {file}. {error}")?;
+                failed += 1;
+            }
+        }
+    }
+
+    writeln!(
+        stdout(),
+        "\ntest result: {}. {passed} passed; {failed} failed; {filtered} filtered out",
+        if failed == 0 { "ok" } else { "FAILED" }
+    )?;
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(CommandLineError::FailedTests(failed))
+    }
+}
+
+/// Expands directories to the test files they contain (i.e., files ending in `_test.ta`), recursively and in sorted order.
+/// Hidden directories are skipped.
+fn test_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>, CommandLineError> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            collect(path, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    Ok(files)
+}
+
+fn collect(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), CommandLineError> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    entries.sort();
+
+    for entry in entries {
+        let name = entry
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if entry.is_dir() {
+            if !name.starts_with('.') {
+                collect(entry.as_path(), files)?;
+            }
+        } else if name.ends_with("_test.ta") {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Report the uses of deprecated functionality and the misleading identifiers found by the compiler.
+
+use crate::CommandLineError;
+use std::io::Write;
+use tortuga::compiler::{Lint, Parser, Warning};
+use tortuga::{CompilationError, PrettyPrinter, Program};
+
+/// Parses the given source as a Tortuga [`Program`], keeping any [`Warning`]s.
+pub fn parse_with_warnings(source: &str) -> (Result<Program, CompilationError>, Vec<Warning>) {
+    match Parser::try_from(source) {
+        Ok(parser) => parser.parse_with_warnings(),
+        Err(error) => (Err(error.into()), Vec::new()),
+    }
+}
+
+/// Prints the given [`Warning`]s.
+/// When warnings are denied, they are printed as errors and any warning fails the command.
+pub fn report_warnings<StdOut: Write, StdErr: Write>(
+    printer: &mut PrettyPrinter<StdOut, StdErr>,
+    warnings: &[Warning],
+    deny: bool,
+) -> Result<(), CommandLineError> {
+    for warning in warnings {
+        if deny {
+            printer.print_denied_warning(warning)?;
+        } else {
+            printer.print_warning(warning)?;
+        }
+    }
+
+    if deny && !warnings.is_empty() {
+        Err(CommandLineError::DeniedWarnings(warnings.len()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Prints the given [`Lint`]s; lints never fail the command.
+pub fn report_lints<StdOut: Write, StdErr: Write>(
+    printer: &mut PrettyPrinter<StdOut, StdErr>,
+    lints: &[Lint],
+) -> Result<(), CommandLineError> {
+    for lint in lints {
+        printer.print_lint(lint)?;
+    }
+
+    Ok(())
+}
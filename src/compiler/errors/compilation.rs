@@ -0,0 +1,34 @@
+//! Errors that may occur while compiling Tortuga input into a checked syntax tree.
+
+use crate::compiler::{SemanticError, SyntacticalError};
+
+/// An error that prevented Tortuga input from compiling.
+/// Semantic analysis only runs on grammatically correct input, so the two kinds of errors are never mixed.
+#[derive(thiserror::Error, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CompilationError {
+    #[error(transparent)]
+    Syntactical(#[from] SyntacticalError),
+    #[error("Encountered one or more semantic errors. {}", stringify_errors(.0.as_slice()))]
+    Semantic(Vec<SemanticError>),
+}
+
+/// Joins the messages of the given [`SemanticError`]s into a single sentence-separated string.
+pub(crate) fn stringify_errors(errors: &[SemanticError]) -> String {
+    errors
+        .iter()
+        .map(SemanticError::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+impl CompilationError {
+    /// Tests whether the parser had complete input or ran out of tokens prematurely.
+    /// [`false`] if the parser ran out of tokens. Otherwise, [`true`].
+    pub fn is_complete(&self) -> bool {
+        match self {
+            Self::Syntactical(error) => error.is_complete(),
+            Self::Semantic(_) => true,
+        }
+    }
+}
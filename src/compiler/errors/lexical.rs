@@ -1,6 +1,7 @@
 //! Errors that may occur during lexical analysis.
 
-use crate::compiler::{Lexeme, Location};
+use crate::compiler::errors::number::NumberErrorKind;
+use crate::compiler::{number, Lexeme, Location};
 use crate::Kind;
 use std::fmt;
 use std::fmt::{Display, Formatter};
@@ -8,6 +9,7 @@ use std::fmt::{Display, Formatter};
 /// An error that occurred during lexical analysis of a specific lexeme.
 /// After an error is encountered, the scanner may continue to analyze the lexeme.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LexicalError {
     lexeme: String,
     start: Location,
@@ -21,7 +23,16 @@ impl Display for LexicalError {
             f,
             "Encountered a {} error during lexical analysis on {}",
             self.kind, self.start
-        )
+        )?;
+
+        if let ErrorKind::Number(kind) = self.kind {
+            write!(f, "; {kind}.")?;
+        }
+
+        match self.suggestion() {
+            Some(suggestion) => write!(f, " Did you mean `{suggestion}`?"),
+            None => Ok(()),
+        }
     }
 }
 
@@ -29,8 +40,10 @@ impl std::error::Error for LexicalError {}
 
 /// The kind of lexical error that occurred.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ErrorKind {
-    Number,
+    /// A malformed number literal.
+    Number(NumberErrorKind),
     Invalid,
 }
 
@@ -49,7 +62,7 @@ impl LexicalError {
 
     /// This `LexicalError`'s lexeme.
     pub fn as_str(&self) -> &str {
-        &self.lexeme.as_str()
+        self.lexeme.as_str()
     }
 
     /// This `LexicalError`'s start [`Location`] in the input.
@@ -57,16 +70,35 @@ impl LexicalError {
         &self.start
     }
 
+    /// Mutable version of [`Self::start`].
+    pub(crate) fn start_mut(&mut self) -> &mut Location {
+        &mut self.start
+    }
+
+    /// Moves this `LexicalError`, found in an excerpt of the input that begins at `start`, to its [`Location`] in the entire input.
+    pub(crate) fn relative_to(mut self, start: Location) -> Self {
+        self.start = self.start.relative_to(start);
+        self
+    }
+
     /// This `LexicalError`'s variant.
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    /// A valid lexeme that was likely intended instead, if any.
+    pub fn suggestion(&self) -> Option<String> {
+        match self.kind {
+            ErrorKind::Number(kind) => number::suggest(self.lexeme.as_str(), kind),
+            ErrorKind::Invalid => None,
+        }
+    }
 }
 
 impl Display for ErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            ErrorKind::Number => f.write_str("NUMBER"),
+            ErrorKind::Number(_) => f.write_str("NUMBER"),
             ErrorKind::Invalid => f.write_str("INVALID"),
         }
     }
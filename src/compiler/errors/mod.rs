@@ -1,9 +1,15 @@
 //! Errors that may occur in the compilation of Tortuga input.
 
+pub mod compilation;
 pub mod lexical;
 pub mod number;
+pub mod semantic;
+pub mod stream;
 pub mod syntactical;
 
-pub use lexical::LexicalError;
-pub use number::ParseNumberError;
+pub use compilation::CompilationError;
+pub use lexical::{ErrorKind as LexicalErrorKind, LexicalError};
+pub use number::{NumberErrorKind, ParseNumberError};
+pub use semantic::SemanticError;
+pub use stream::StreamError;
 pub use syntactical::SyntacticalError;
@@ -1,36 +1,106 @@
 //! Error parsing a numerical literal into a value.
 
-use std::fmt::{Display, Formatter};
+use crate::compiler::number;
+use std::fmt::{self, Display, Formatter};
+
+/// The reason a numerical literal is malformed.
+/// Shared by [`ParseNumberError`] and lexical errors, so both report the same problem for the same literal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum NumberErrorKind {
+    /// The radix, integer or exponent portion starts with a 0 (e.g., `0008`).
+    LeadingZeros,
+    /// The fraction portion ends with a 0 (e.g., `2#.100`).
+    TrailingZeros,
+    /// The radix is not between 2 and 36 (e.g., `300#1`).
+    UnsupportedRadix(u32),
+    /// A digit is too large for the radix of the number (e.g., `16#G`).
+    InvalidDigit { digit: char, radix: u32 },
+    /// Neither the integer nor the fraction portion has any digits (e.g., `20#.`).
+    EmptyFraction,
+    /// The radix or exponent marker is not surrounded by digits (e.g., `1*^`).
+    MissingDigits,
+    /// An underscore does not separate two digits (e.g., `1__000`).
+    MisplacedSeparator,
+    /// The number is too large to be represented.
+    Overflow,
+    /// The number does not have the structure of a numerical literal (e.g., `1.2.3`).
+    Malformed,
+}
+
+impl Display for NumberErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberErrorKind::LeadingZeros => f.write_str("numbers cannot have leading zeros"),
+            NumberErrorKind::TrailingZeros => f.write_str("fractions cannot have trailing zeros"),
+            NumberErrorKind::UnsupportedRadix(radix) => {
+                write!(f, "the radix {radix} is not between 2 and 36")
+            }
+            NumberErrorKind::InvalidDigit { digit, radix } => {
+                write!(f, "the digit '{digit}' is not valid in radix {radix}")
+            }
+            NumberErrorKind::EmptyFraction => {
+                f.write_str("a number must have digits before or after its decimal point")
+            }
+            NumberErrorKind::MissingDigits => {
+                f.write_str("a number must have digits around its radix and exponent markers")
+            }
+            NumberErrorKind::MisplacedSeparator => {
+                f.write_str("an underscore may only separate two digits")
+            }
+            NumberErrorKind::Overflow => f.write_str("the number is too large"),
+            NumberErrorKind::Malformed => f.write_str(
+                "expected an optional radix (e.g., 16#), digits with an optional fraction (e.g., 1.5) and an optional exponent in the same radix (e.g., *^-3)",
+            ),
+        }
+    }
+}
 
 /// An error that occurred parsing a numerical literal into runtime value.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ParseNumberError {
     lexeme: String,
+    kind: NumberErrorKind,
 }
 
 impl Display for ParseNumberError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Unable to parse number \"{}\" to a runtime value.",
-            self.lexeme
-        )
+            "Unable to parse number \"{}\" to a runtime value; {}.",
+            self.lexeme, self.kind
+        )?;
+
+        match self.suggestion() {
+            Some(suggestion) => write!(f, " Did you mean `{suggestion}`?"),
+            None => Ok(()),
+        }
     }
 }
 
 impl std::error::Error for ParseNumberError {}
 
 impl ParseNumberError {
+    /// Creates a new [`ParseNumberError`] for the given number literal.
+    pub fn new<S: Into<String>>(lexeme: S, kind: NumberErrorKind) -> Self {
+        ParseNumberError {
+            lexeme: lexeme.into(),
+            kind,
+        }
+    }
+
     /// The number literal that could not be parsed.
     pub fn lexeme(&self) -> &str {
         self.lexeme.as_str()
     }
-}
 
-impl From<&str> for ParseNumberError {
-    fn from(lexeme: &str) -> Self {
-        ParseNumberError {
-            lexeme: lexeme.to_string(),
-        }
+    /// The reason the number literal could not be parsed.
+    pub fn kind(&self) -> &NumberErrorKind {
+        &self.kind
+    }
+
+    /// A valid number literal that was likely intended instead, if any.
+    pub fn suggestion(&self) -> Option<String> {
+        number::suggest(self.lexeme.as_str(), self.kind)
     }
 }
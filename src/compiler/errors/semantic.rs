@@ -0,0 +1,51 @@
+//! Errors that may occur during semantic analysis of a syntax tree.
+
+use crate::compiler::{Location, Type};
+use crate::grammar::Operator;
+
+/// An error found by analyzing a syntax tree that is otherwise grammatically correct.
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SemanticError {
+    #[error(
+        "Function {function} calls procedure {procedure}, but functions may not have side-effects."
+    )]
+    ImpureFunction { function: String, procedure: String },
+    #[error("Pattern calls procedure {0}, but patterns may not have side-effects.")]
+    ImpurePattern(String),
+    #[error("Procedure @{0}! must be called with an exclamation mark (e.g., {0}!(...)).")]
+    MissingExclamation(String),
+    #[error("Function @{0} is not a procedure and must be called without an exclamation mark.")]
+    NotAProcedure(String),
+    #[error("@{0} is declared as both a function and a procedure.")]
+    ConflictingDeclaration(String),
+    #[error(
+        "Operator {operator} cannot be applied to operands of type {lhs} and {rhs} on {location}."
+    )]
+    MismatchedTypes {
+        location: Location,
+        operator: Operator,
+        lhs: Type,
+        rhs: Type,
+    },
+    #[error("Only functions may be called, but found a {found} on {location}.")]
+    NotAFunction { location: Location, found: Type },
+    #[error("A {function} cannot be called with {arguments} argument(s) on {location}.")]
+    WrongNumberOfArguments {
+        location: Location,
+        function: Type,
+        arguments: usize,
+    },
+}
+
+impl SemanticError {
+    /// Mutable reference to the [`Location`] of this error in the source, if it has one.
+    pub(crate) fn location_mut(&mut self) -> Option<&mut Location> {
+        match self {
+            SemanticError::MismatchedTypes { location, .. }
+            | SemanticError::NotAFunction { location, .. }
+            | SemanticError::WrongNumberOfArguments { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! Errors that may occur while scanning a stream of input.
+
+use crate::compiler::{LexicalError, Location};
+use std::io;
+
+/// An error that occurred while scanning Tortuga input from a [`Read`](std::io::Read)er.
+#[derive(thiserror::Error, Debug)]
+pub enum StreamError {
+    #[error(transparent)]
+    Lexical(#[from] LexicalError),
+    #[error("Unable to read from the input stream.")]
+    Read(#[from] io::Error),
+    #[error("The input stream contains invalid UTF-8 on {0}.")]
+    Encoding(Location),
+}
@@ -6,13 +6,14 @@ use crate::LexicalError;
 /// An error that occurred while generating a syntax tree from a sequence of tokens.
 /// After an error is encountered, the parser may continue to generate a tree in panic mode.
 #[derive(thiserror::Error, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SyntacticalError {
     #[error("Reached the end of file prematurely; unable to complete parsing a grammar rule.")]
     Incomplete,
     #[error("No grammar rule matched the {0}.")]
     NoMatch(OwnedToken),
-    #[error("Encountered multiple syntax errors.")]
-    Multiple,
+    #[error("Encountered {} syntax error(s).", .0.len())]
+    Multiple(Vec<SyntacticalError>),
     #[error("Encountered one or more lexical errors.")]
     Lexical(Vec<LexicalError>),
 }
@@ -0,0 +1,698 @@
+//! Incrementally re-scans and re-parses Tortuga input as it is edited (e.g., by an editor on every keystroke).
+//!
+//! No token spans multiple lines, so only the lines touched by an edit are scanned again.
+//! A top-level expression is parsed again only when its tokens (or the token after it) changed;
+//! the rest of the previous syntax tree is reused, with its [`Location`]s moved to account for the edit.
+//!
+//! Likewise, only the expressions parsed again are checked again, starting from the declarations before them.
+//! The expressions after them are checked again only when the names they declare changed what they refer to.
+
+use crate::compiler::parser::{validate, Tokens};
+use crate::compiler::patterns::{self, Analyses};
+use crate::compiler::purity::{self, Declared};
+use crate::compiler::types::{self, Type};
+use crate::compiler::{warnings, CompilationError, LexicalError, Location, Parser, Scanner};
+use crate::compiler::{SemanticError, Token, Warning};
+use crate::grammar::syntax::*;
+use crate::SyntacticalError;
+use std::ops::Range;
+
+type ScanResult = Result<Token<'static>, LexicalError>;
+
+/// Replaces a range of bytes in the input with new text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edit {
+    range: Range<usize>,
+    replacement: String,
+}
+
+impl Edit {
+    /// Creates a new [`Edit`] that replaces the given range of bytes with the `replacement`.
+    pub fn new<S: Into<String>>(range: Range<usize>, replacement: S) -> Self {
+        Edit {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// The range of bytes in the input to replace.
+    pub fn range(&self) -> &Range<usize> {
+        &self.range
+    }
+
+    /// The text to replace the range with.
+    pub fn replacement(&self) -> &str {
+        self.replacement.as_str()
+    }
+}
+
+/// The tokens a top-level expression was parsed from, along with the results of checking it.
+/// The expression itself is kept in the [`Program`] of the [`Document`].
+#[derive(Clone, Debug)]
+struct Segment {
+    tokens: Range<usize>,
+    warnings: Vec<Warning>,
+    checked: Checked,
+}
+
+/// The results of checking a top-level expression, given the declarations of the expressions before it.
+#[derive(Clone, Debug, Default)]
+struct Checked {
+    impure: Vec<SemanticError>,
+    mistyped: Vec<SemanticError>,
+    calls: Vec<Warning>,
+    patterns: Vec<Warning>,
+    /// What each name declared by the expression refers to afterwards, in order of declaration.
+    declarations: Vec<(String, Option<Declared>, Option<Type>)>,
+}
+
+/// The top-level scopes of the checks that depend on the declarations before an expression.
+#[derive(Debug)]
+struct Scopes {
+    purity: purity::Scope,
+    types: types::Scope,
+}
+
+/// Tortuga input that keeps its tokens and syntax tree up to date as it is edited.
+/// The results are always the same as scanning and parsing the entire input again.
+///
+/// # Examples
+/// ```rust
+/// use tortuga::compiler::{Document, Edit};
+///
+/// let mut document = Document::new("@x = 2\nx + 1");
+///
+/// document.edit(Edit::new(5..6, "40"));
+///
+/// assert_eq!(document.source(), "@x = 40\nx + 1");
+/// assert_eq!(document.program(), "@x = 40\nx + 1".parse().as_ref());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Document {
+    source: String,
+    tokens: Vec<ScanResult>,
+    segments: Option<Vec<Segment>>,
+    program: Option<Program>,
+    error: Option<CompilationError>,
+    warnings: Vec<Warning>,
+    analyses: Analyses,
+}
+
+impl Document {
+    /// Scans and parses the given source.
+    pub fn new<S: Into<String>>(source: S) -> Self {
+        let source = source.into();
+        let tokens = scan(source.as_str(), Location::default());
+        let mut document = Document {
+            source,
+            tokens,
+            segments: None,
+            program: None,
+            error: None,
+            warnings: Vec::new(),
+            analyses: Analyses::default(),
+        };
+
+        document.parse(0..document.tokens.len(), 0, 0, 0);
+        document
+    }
+
+    /// The current source of this [`Document`].
+    pub fn source(&self) -> &str {
+        self.source.as_str()
+    }
+
+    /// The results of scanning the current source.
+    pub fn tokens(&self) -> &[ScanResult] {
+        self.tokens.as_slice()
+    }
+
+    /// The result of parsing and checking the current source.
+    pub fn program(&self) -> Result<&Program, &CompilationError> {
+        match (&self.error, &self.program) {
+            (None, Some(program)) => Ok(program),
+            (Some(error), _) => Err(error),
+            (None, None) => Err(&CompilationError::Syntactical(SyntacticalError::Incomplete)),
+        }
+    }
+
+    /// The warnings found while parsing and checking the current source.
+    pub fn warnings(&self) -> &[Warning] {
+        self.warnings.as_slice()
+    }
+
+    /// Applies the [`Edit`] to the source, then re-scans and re-parses the affected parts.
+    ///
+    /// # Panics
+    /// Panics if the range of the edit is out of bounds or does not lie on [`char`] boundaries.
+    pub fn edit(&mut self, edit: Edit) {
+        let Range { start, end } = edit.range;
+        let removed = &self.source[start..end];
+        let lines = count_lines(edit.replacement.as_str()) as isize - count_lines(removed) as isize;
+        let bytes = edit.replacement.len() as isize - removed.len() as isize;
+
+        // Tokens never span lines, so the lines touched by the edit are re-scanned in their entirety.
+        let line_start = self.source[..start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line_end = self.source[end..]
+            .find('\n')
+            .map_or(self.source.len(), |index| end + index + 1);
+
+        self.source
+            .replace_range(start..end, edit.replacement.as_str());
+
+        let origin = Location::new(count_lines(&self.source[..line_start]) + 1, 1, line_start);
+        let window = line_start..line_end.saturating_add_signed(bytes);
+        let scanned = scan(&self.source[window], origin);
+
+        let first = self
+            .tokens
+            .partition_point(|result| offset(result) < line_start);
+        let last = self
+            .tokens
+            .partition_point(|result| offset(result) < line_end);
+
+        for result in &mut self.tokens[last..] {
+            shift(result, lines, bytes);
+        }
+
+        let count = scanned.len();
+        let moved = count as isize - (last - first) as isize;
+
+        self.tokens.splice(first..last, scanned);
+        self.parse(first..first + count, moved, lines, bytes);
+    }
+
+    /// Parses the tokens again, reusing the top-level expressions that do not depend on the `changed` tokens.
+    /// The unchanged tokens after the changed ones moved by the given number of tokens, lines and bytes.
+    fn parse(&mut self, changed: Range<usize>, moved: isize, lines: isize, bytes: isize) {
+        let errors: Vec<LexicalError> = self
+            .tokens
+            .iter()
+            .filter_map(|result| result.as_ref().err().cloned())
+            .collect();
+
+        if !errors.is_empty() {
+            self.segments = None;
+            self.program = None;
+            self.error = Some(SyntacticalError::Lexical(errors).into());
+            self.warnings = Vec::new();
+            return;
+        }
+
+        let tokens: Vec<Token<'static>> = self.tokens.iter().flatten().cloned().collect();
+        let count = tokens.len();
+        let mut parser = Parser::from(Tokens::from(tokens));
+        let (mut segments, mut expressions): (Vec<Segment>, Vec<Expression>) =
+            match (self.segments.take(), self.program.take()) {
+                (Some(segments), Some(Program::Expressions(expressions))) => {
+                    (segments, expressions.into_iter().collect())
+                }
+                _ => (Vec::new(), Vec::new()),
+            };
+
+        // The end of an expression depends on the token after it, which must also be unchanged.
+        let reused = segments
+            .iter()
+            .take_while(|segment| segment.tokens.end < changed.start)
+            .count();
+        let mut previous = segments.split_off(reused);
+        let mut suffix = expressions.split_off(reused);
+        let mut next = previous.partition_point(|segment| {
+            segment.tokens.start < changed.end.saturating_add_signed(-moved)
+        });
+        let mut resumed = previous.len();
+        let mut index = segments.last().map_or(0, |segment| segment.tokens.end);
+
+        while index < count {
+            if index >= changed.end {
+                let start = index.saturating_add_signed(-moved);
+
+                while previous
+                    .get(next)
+                    .is_some_and(|segment| segment.tokens.start < start)
+                {
+                    next += 1;
+                }
+
+                if previous.get(next).map(|segment| segment.tokens.start) == Some(start) {
+                    resumed = next;
+                    break;
+                }
+            }
+
+            match parser.parse_top_level(index) {
+                Ok((expression, warnings)) if !parser.next_is_comparator() => {
+                    segments.push(Segment {
+                        tokens: index..parser.position(),
+                        warnings,
+                        checked: Checked::default(),
+                    });
+                    expressions.push(expression);
+                    index = parser.position();
+                }
+                // Comparisons and syntax errors are parsed from scratch to recover the same way as a fresh parse.
+                _ => return self.parse_all(parser),
+            }
+        }
+
+        if segments.is_empty() && resumed == previous.len() {
+            return self.parse_all(parser);
+        }
+
+        let replaced: Vec<Segment> = previous.drain(..resumed).collect();
+        let mut scopes = Scopes::new();
+
+        for segment in segments[..reused].iter() {
+            scopes.declare(&segment.checked);
+        }
+
+        for (segment, expression) in segments.iter_mut().zip(&expressions).skip(reused) {
+            segment.checked = scopes.check(expression);
+        }
+
+        // The expressions after the changed ones only need to be checked again when the declarations before them changed.
+        let declarations = |segments: &[Segment]| {
+            segments
+                .iter()
+                .flat_map(|segment| segment.checked.declarations.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let unchanged = declarations(&segments[reused..]) == declarations(replaced.as_slice());
+        let checked = segments.len();
+
+        for mut segment in previous {
+            segment.shift(moved, lines, bytes);
+            segments.push(segment);
+        }
+
+        for mut expression in suffix.drain(resumed..) {
+            relocate(&mut expression, lines, bytes);
+            expressions.push(expression);
+        }
+
+        if !unchanged {
+            for (segment, expression) in segments.iter_mut().zip(&expressions).skip(checked) {
+                segment.checked = scopes.check(expression);
+            }
+        }
+
+        let mut warnings: Vec<Warning> = segments
+            .iter()
+            .flat_map(|segment| segment.warnings.iter())
+            .chain(
+                segments
+                    .iter()
+                    .flat_map(|segment| segment.checked.calls.iter()),
+            )
+            .cloned()
+            .collect();
+
+        warnings.extend(self.analyses.check_top_level(expressions.iter()));
+        warnings.extend(
+            segments
+                .iter()
+                .flat_map(|segment| segment.checked.patterns.iter())
+                .cloned(),
+        );
+
+        let errors = segments
+            .iter()
+            .flat_map(|segment| segment.checked.impure.iter())
+            .chain(
+                segments
+                    .iter()
+                    .flat_map(|segment| segment.checked.mistyped.iter()),
+            )
+            .cloned()
+            .collect();
+        let mut expressions = expressions.into_iter();
+        let head = expressions.next().unwrap_or_else(|| unreachable!());
+
+        self.program = Some(List::new(head, expressions.collect()).into());
+        self.error = validate(errors).err();
+        self.warnings = warnings;
+        self.segments = Some(segments);
+    }
+
+    /// Parses all of the tokens from the start without reusing any previous expressions.
+    fn parse_all(&mut self, mut parser: Parser<'static>) {
+        parser.rewind();
+
+        let (program, warnings) = parser.parse_with_warnings();
+
+        self.segments = None;
+        self.warnings = warnings;
+
+        match program {
+            Ok(program) => {
+                self.program = Some(program);
+                self.error = None;
+            }
+            Err(error) => {
+                self.program = None;
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+impl Segment {
+    /// Moves this segment by the given number of tokens, lines and bytes.
+    fn shift(&mut self, tokens: isize, lines: isize, bytes: isize) {
+        self.tokens = self.tokens.start.saturating_add_signed(tokens)
+            ..self.tokens.end.saturating_add_signed(tokens);
+
+        let checked = &mut self.checked;
+        let warnings = self
+            .warnings
+            .iter_mut()
+            .chain(&mut checked.calls)
+            .chain(&mut checked.patterns);
+
+        for location in warnings.filter_map(Warning::location_mut) {
+            location.shift(lines, bytes);
+        }
+
+        for error in checked.impure.iter_mut().chain(&mut checked.mistyped) {
+            if let Some(location) = error.location_mut() {
+                location.shift(lines, bytes);
+            }
+        }
+    }
+}
+
+impl Scopes {
+    /// The scopes before the first top-level expression.
+    fn new() -> Self {
+        Scopes {
+            purity: purity::builtins(),
+            types: types::Scope::new(),
+        }
+    }
+
+    /// Adds the declarations of an expression that was already checked.
+    fn declare(&mut self, checked: &Checked) {
+        for (name, declared, kind) in checked.declarations.iter() {
+            match declared {
+                Some(declared) => self.purity.insert(name.clone(), *declared),
+                None => self.purity.remove(name),
+            };
+
+            match kind {
+                Some(kind) => self.types.insert(name.clone(), kind.clone()),
+                None => self.types.remove(name),
+            };
+        }
+    }
+
+    /// Checks a top-level expression and adds its declarations.
+    fn check(&mut self, expression: &Expression) -> Checked {
+        let impure = purity::check_top_level(&mut self.purity, expression);
+        let mistyped = types::check_top_level(&mut self.types, expression);
+        let mut assignments = Vec::new();
+        let mut declarations: Vec<(String, Option<Declared>, Option<Type>)> = Vec::new();
+
+        patterns::collect(expression, &mut assignments);
+
+        for assignment in assignments {
+            match assignment.function().name().as_str() {
+                Some(name) if declarations.iter().all(|(declared, _, _)| declared != name) => {
+                    declarations.push((
+                        name.to_string(),
+                        self.purity.get(name).copied(),
+                        self.types.get(name).cloned(),
+                    ))
+                }
+                _ => (),
+            }
+        }
+
+        Checked {
+            impure,
+            mistyped,
+            calls: warnings::check_top_level_calls(expression),
+            patterns: patterns::check_nested(expression),
+            declarations,
+        }
+    }
+}
+
+/// Scans an excerpt of the input that starts at the given [`Location`].
+fn scan(excerpt: &str, origin: Location) -> Vec<ScanResult> {
+    Scanner::from(excerpt)
+        .map(|result| match result {
+            Ok(token) => {
+                let mut token = token.into_owned_token();
+                let start = token.lexeme().start().relative_to(origin);
+
+                *token.lexeme_mut().start_mut() = start;
+
+                Ok(token)
+            }
+            Err(error) => Err(error.relative_to(origin)),
+        })
+        .collect()
+}
+
+fn count_lines(text: &str) -> usize {
+    text.bytes().filter(|byte| *byte == b'\n').count()
+}
+
+/// The offset of the start of a scanned token or error.
+fn offset(result: &ScanResult) -> usize {
+    match result {
+        Ok(token) => token.lexeme().start().offset(),
+        Err(error) => error.start().offset(),
+    }
+}
+
+fn shift(result: &mut ScanResult, lines: isize, bytes: isize) {
+    match result {
+        Ok(token) => token.lexeme_mut().start_mut().shift(lines, bytes),
+        Err(error) => error.start_mut().shift(lines, bytes),
+    }
+}
+
+/// Moves the [`Location`]s in a syntax tree by the given number of lines and bytes.
+fn relocate(expression: &mut Expression, lines: isize, bytes: isize) {
+    match expression {
+        Expression::Assignment(assignment) => {
+            assignment.location_mut().shift(lines, bytes);
+            relocate_patterns(assignment.function_mut().parameters_mut(), lines, bytes);
+
+            for expression in assignment.block_mut().iter_mut() {
+                relocate(expression, lines, bytes);
+            }
+        }
+        Expression::Call(call) => {
+            call.location_mut().shift(lines, bytes);
+            relocate(call.callee_mut(), lines, bytes);
+
+            for argument in call.arguments_mut().iter_mut() {
+                relocate(argument, lines, bytes);
+            }
+        }
+        Expression::Operation(operation) => {
+            operation.location_mut().shift(lines, bytes);
+            relocate(&mut operation.lhs, lines, bytes);
+            relocate(&mut operation.rhs, lines, bytes);
+        }
+        Expression::Grouping(grouping) => relocate(grouping.inner_mut(), lines, bytes),
+        Expression::Identifier(_) | Expression::Number(_) => (),
+    }
+}
+
+fn relocate_patterns(patterns: &mut [Pattern], lines: isize, bytes: isize) {
+    for pattern in patterns {
+        match pattern {
+            Pattern::Function(function) => {
+                relocate_patterns(function.parameters_mut(), lines, bytes)
+            }
+            Pattern::Refinement(refinement) => relocate(refinement.constraint_mut(), lines, bytes),
+            Pattern::Bounds(bounds) => {
+                relocate(bounds.left_mut().constraint_mut(), lines, bytes);
+                relocate(bounds.right_mut().constraint_mut(), lines, bytes);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, thread};
+
+    /// A small pseudo-random number generator (xorshift), so the tests are reproducible.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            (self.0 % bound.max(1) as u64) as usize
+        }
+
+        /// A random offset in the text on a character boundary.
+        fn boundary(&mut self, text: &str) -> usize {
+            let mut offset = self.below(text.len() + 1);
+
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+
+            offset
+        }
+    }
+
+    const REPLACEMENTS: &[&str] = &[
+        "",
+        " ",
+        "\n",
+        "1",
+        "42",
+        "x",
+        "@",
+        "(",
+        ")",
+        "+ 2",
+        "* y",
+        "=",
+        "[",
+        "]",
+        ";",
+        ",",
+        "<",
+        "é",
+        "$",
+        "1.",
+        "\n@z = 3\n",
+        "f(1, 2)",
+        "_(@n) = n ^ 2",
+    ];
+
+    fn assert_fresh(document: &Document) {
+        let source = document.source();
+        let tokens: Vec<ScanResult> = scan(source, Location::default());
+        let (program, warnings) = match Parser::try_from(source) {
+            Ok(parser) => parser.parse_with_warnings(),
+            Err(error) => (Err(error.into()), Vec::new()),
+        };
+
+        assert_eq!(document.tokens(), tokens.as_slice(), "{source:?}");
+        assert_eq!(document.program(), program.as_ref(), "{source:?}");
+        assert_eq!(document.warnings(), warnings.as_slice(), "{source:?}");
+
+        // Equality of syntax trees ignores locations, but their debug representation does not.
+        assert_eq!(
+            format!("{:?}", document.program()),
+            format!("{program:?}"),
+            "{source:?}"
+        );
+    }
+
+    #[test]
+    fn reuses_unchanged_expressions() {
+        let mut document = Document::new("@x = 1 + 2\n@y = x * 3\ny - x");
+
+        document.edit(Edit::new(5..6, "10"));
+
+        assert_fresh(&document);
+        assert_eq!(document.segments.as_ref().map(Vec::len), Some(3));
+
+        document.edit(Edit::new(0..0, "@w(@n > 0) = n\n"));
+
+        assert_fresh(&document);
+        assert_eq!(document.warnings().len(), 1);
+
+        document.edit(Edit::new(0..0, "\n"));
+
+        assert_fresh(&document);
+        assert_eq!(
+            document.warnings()[0].location(),
+            Some(&Location::new(2, 1, 1))
+        );
+    }
+
+    #[test]
+    fn checks_after_changed_declarations() {
+        let mut document = Document::new("@x = 1\n@y = 2\nx(2) + y");
+
+        assert!(matches!(
+            document.program(),
+            Err(CompilationError::Semantic(_))
+        ));
+
+        document.edit(Edit::new(2..2, "(@n)"));
+        assert_fresh(&document);
+        assert!(document.program().is_ok());
+
+        document.edit(Edit::new(16..17, "x(3)"));
+        assert_fresh(&document);
+        assert!(document.program().is_ok());
+
+        document.edit(Edit::new(0..0, "\n\n"));
+        assert_fresh(&document);
+    }
+
+    #[test]
+    fn recovers_from_errors() {
+        let mut document = Document::new("@f(@x) = x\nf(2)");
+
+        document.edit(Edit::new(11..11, "$ "));
+        assert_fresh(&document);
+
+        document.edit(Edit::new(11..13, ""));
+        assert_fresh(&document);
+
+        document.edit(Edit::new(10..10, " = 4"));
+        assert_fresh(&document);
+    }
+
+    #[test]
+    fn randomized_edits() {
+        // Recovering from errors in deeply nested input recurses further than the default test thread allows.
+        thread::Builder::new()
+            .stack_size(64 * 1024 * 1024)
+            .spawn(edit_examples)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    fn edit_examples() {
+        let mut random = Random(0x2545F4914F6CDD1D);
+
+        for entry in fs::read_dir("examples").unwrap() {
+            let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut document = Document::new(source.as_str());
+
+            assert_fresh(&document);
+
+            for _ in 0..100 {
+                let start = random.boundary(document.source());
+                let end = start + random.boundary(&document.source()[start..]).min(8);
+                let end = (start..=end)
+                    .rev()
+                    .find(|end| document.source().is_char_boundary(*end))
+                    .unwrap_or(start);
+
+                // Undo most edits to keep the document close to a valid program.
+                let removed = document.source()[start..end].to_string();
+                let replacement = REPLACEMENTS[random.below(REPLACEMENTS.len())];
+
+                document.edit(Edit::new(start..end, replacement));
+                assert_fresh(&document);
+
+                if random.below(4) != 0 {
+                    document.edit(Edit::new(start..start + replacement.len(), removed));
+                    assert_fresh(&document);
+                }
+            }
+        }
+    }
+}
@@ -52,6 +52,11 @@ impl<'a, I: Iterator<Item = char>> Input<'a, I> {
         self.peeked
     }
 
+    /// Lookahead by 2 Unicode code points without advancing the `Location` of the current `Lexeme`.
+    pub fn peek_second(&self) -> Option<char> {
+        self.source[self.end.offset()..].chars().nth(1)
+    }
+
     /// If the next character is equal to the `expected` value, advance the `Location` of the current `Lexeme`.
     /// Otherwise, the current `Location` is unchanged.
     pub fn next_if_eq(&mut self, expected: char) -> Option<char> {
@@ -269,6 +274,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn peek_second() {
+        let mut input = Input::from("abc");
+
+        assert_eq!(input.peek_second(), Some('b'));
+        assert_eq!(input.peek(), Some('a'));
+        assert_eq!(input.peek_second(), Some('b'));
+        assert_eq!(input.next(), Some('a'));
+        assert_eq!(input.peek_second(), Some('c'));
+        assert_eq!(input.next(), Some('b'));
+        assert_eq!(input.peek_second(), None);
+    }
+
     #[test]
     fn next_digit_when_true() {
         assert_eq!(Input::from("abc").next_digit(16), Some('a'));
@@ -58,6 +58,11 @@ impl<'a> Lexeme<'a> {
         &self.start
     }
 
+    /// Mutable version of [`Self::start`].
+    pub(crate) fn start_mut(&mut self) -> &mut Location {
+        &mut self.start
+    }
+
     /// The length in bytes of this [`Lexeme`].
     pub fn len(&self) -> usize {
         self.lexeme.len()
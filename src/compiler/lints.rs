@@ -0,0 +1,206 @@
+//! Lints flag identifiers that are likely to mislead a reader of the source code,
+//! following the recommendations for identifiers in [UTS #39](https://www.unicode.org/reports/tr39/).
+//!
+//! Unlike warnings, lints are never treated as errors; they only inform.
+//! Each identifier is checked once, at its first use, in its NFC normalized form.
+//! Identifiers that only use ASCII characters are never flagged on their own,
+//! nor as confusable with another ASCII identifier (e.g., `l` and `I`).
+
+use crate::compiler::unicode::UnicodeProperties;
+use crate::compiler::{Kind, Location, Scanner, Token};
+use crate::grammar::Symbol;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use unicode_security::{skeleton, MixedScript};
+
+/// The reason an identifier is flagged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum LintKind {
+    /// The identifier uses a character that is restricted from use in identifiers (e.g., `ǉ`).
+    Restricted(char),
+    /// The identifier mixes characters from scripts that are not used together (e.g., Latin `p` and Cyrillic `а` in `pа`).
+    MixedScript,
+    /// The identifier looks the same as a different identifier that was used earlier (e.g., Cyrillic `а` and Latin `a`).
+    Confusable(String),
+}
+
+/// An identifier flagged by a lint in the source code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Lint {
+    identifier: String,
+    kind: LintKind,
+    location: Location,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let identifier = self.identifier.as_str();
+        let location = self.location;
+
+        match &self.kind {
+            LintKind::Restricted(character) => write!(
+                f,
+                "Identifier {identifier} on {location} uses the character '{character}' (U+{:04X}), which is restricted from use in identifiers.",
+                *character as u32
+            ),
+            LintKind::MixedScript => write!(
+                f,
+                "Identifier {identifier} on {location} mixes characters from multiple scripts."
+            ),
+            LintKind::Confusable(other) => write!(
+                f,
+                "Identifier {identifier} on {location} is confusable with the identifier {other}."
+            ),
+        }
+    }
+}
+
+impl Lint {
+    /// The stable code used to refer to the lint that flagged an identifier.
+    pub fn code(&self) -> &'static str {
+        match self.kind {
+            LintKind::Restricted(_) => "L0001",
+            LintKind::MixedScript => "L0002",
+            LintKind::Confusable(_) => "L0003",
+        }
+    }
+
+    /// The flagged identifier.
+    pub fn identifier(&self) -> &str {
+        self.identifier.as_str()
+    }
+
+    /// The reason the identifier was flagged.
+    pub fn kind(&self) -> &LintKind {
+        &self.kind
+    }
+
+    /// The start [`Location`] in the input of the first use of the identifier.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+/// Checks a sequence of tokens for misleading identifiers.
+/// Confusable identifiers are detected across all of the tokens passed to the same [`Linter`].
+#[derive(Clone, Debug, Default)]
+pub struct Linter {
+    checked: HashSet<Symbol>,
+    skeletons: HashMap<String, Symbol>,
+}
+
+impl Linter {
+    /// Checks a single token; tokens other than identifiers are ignored.
+    pub fn check_token(&mut self, token: &Token<'_>) -> Vec<Lint> {
+        let symbol = match (token.kind(), token.symbol()) {
+            (Kind::Identifier, Some(symbol)) if self.checked.insert(symbol.clone()) => symbol,
+            _ => return Vec::new(),
+        };
+
+        let identifier = symbol.as_str();
+        let location = *token.lexeme().start();
+        let lint = |kind| Lint {
+            identifier: identifier.to_string(),
+            kind,
+            location,
+        };
+
+        let mut lints = Vec::new();
+
+        if let Some(character) = identifier.chars().find(|c| !c.is_identifier_allowed()) {
+            lints.push(lint(LintKind::Restricted(character)));
+        } else if !identifier.is_single_script() {
+            lints.push(lint(LintKind::MixedScript));
+        }
+
+        match self
+            .skeletons
+            .get(&skeleton(identifier).collect::<String>())
+        {
+            Some(other) if !(identifier.is_ascii() && other.as_str().is_ascii()) => {
+                lints.push(lint(LintKind::Confusable(other.as_str().to_string())));
+            }
+            Some(_) => (),
+            None => {
+                self.skeletons
+                    .insert(skeleton(identifier).collect(), symbol);
+            }
+        }
+
+        lints
+    }
+}
+
+/// Checks all of the identifiers in the given source; invalid tokens are skipped.
+pub fn lint(source: &str) -> Vec<Lint> {
+    let mut linter = Linter::default();
+
+    Scanner::from(source)
+        .filter_map(Result::ok)
+        .flat_map(|token| linter.check_token(&token))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<(String, LintKind)> {
+        lint(source)
+            .into_iter()
+            .map(|lint| (lint.identifier, lint.kind))
+            .collect()
+    }
+
+    #[test]
+    fn ascii_identifiers() {
+        assert_eq!(kinds("@l = 1\n@I = l + 1\nI * 2"), Vec::new());
+    }
+
+    #[test]
+    fn single_script_identifiers() {
+        assert_eq!(
+            kinds("@caf\u{00E9} = 1\n@\u{03C0} = 3.14\ncaf\u{00E9} * \u{03C0}"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn restricted_identifier() {
+        assert_eq!(
+            kinds("@\u{01C9}ubav = 1"),
+            vec![(
+                String::from("\u{01C9}ubav"),
+                LintKind::Restricted('\u{01C9}')
+            )]
+        );
+    }
+
+    #[test]
+    fn mixed_script_identifier() {
+        let lints = lint("x + p\u{0430}ss");
+
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind(), &LintKind::MixedScript);
+        assert_eq!(lints[0].location(), &Location::from("x + "));
+        assert_eq!(lints[0].code(), "L0002");
+    }
+
+    #[test]
+    fn confusable_identifiers() {
+        assert_eq!(
+            kinds("@a = 1\n\u{0430} + a"),
+            vec![(
+                String::from("\u{0430}"),
+                LintKind::Confusable(String::from("a"))
+            )]
+        );
+    }
+
+    #[test]
+    fn normalized_identifiers_are_not_confusable() {
+        assert_eq!(kinds("@caf\u{00E9} = 1\ncafe\u{0301}"), Vec::new());
+    }
+}
@@ -7,6 +7,7 @@ use std::ops::Add;
 
 /// The line and column of the start of a lexeme.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Location {
     offset: usize,
     line: usize,
@@ -58,6 +59,25 @@ impl Location {
         self.offset
     }
 
+    /// Converts this `Location`, relative to an excerpt of the input that begins at `start`, into a `Location` in the entire input.
+    pub(crate) fn relative_to(self, start: Location) -> Location {
+        Location {
+            line: start.line + self.line - 1,
+            column: if self.line == 1 {
+                start.column + self.column - 1
+            } else {
+                self.column
+            },
+            offset: start.offset + self.offset,
+        }
+    }
+
+    /// Moves this `Location` by the given number of lines and bytes, keeping the same column.
+    pub(crate) fn shift(&mut self, lines: isize, offset: isize) {
+        self.line = self.line.saturating_add_signed(lines);
+        self.offset = self.offset.saturating_add_signed(offset);
+    }
+
     /// Advance this `Location` based on the given character `c`.
     pub fn advance(&mut self, c: char) {
         match c {
@@ -94,6 +114,20 @@ impl Default for Location {
 mod tests {
     use super::*;
 
+    #[test]
+    fn relative_location() {
+        let start = Location::new(3, 5, 20);
+
+        assert_eq!(
+            Location::new(1, 2, 1).relative_to(start),
+            Location::new(3, 6, 21)
+        );
+        assert_eq!(
+            Location::new(2, 2, 4).relative_to(start),
+            Location::new(4, 2, 24)
+        );
+    }
+
     #[test]
     fn default_location() {
         assert_eq!(Location::default(), Location::new(1, 1, 0));
@@ -1,19 +1,39 @@
 //! The necessary tools to compile Tortuga input into an Abstract Syntax Tree,
 
 mod errors;
+mod incremental;
 mod input;
 mod lexeme;
+mod lints;
 mod location;
 mod number;
+mod optimizer;
 mod parser;
+mod patterns;
+mod purity;
+mod resolver;
 mod scanner;
+mod stream;
 mod token;
+mod types;
 mod unicode;
+mod warnings;
 
-pub use errors::{LexicalError, ParseNumberError, SyntacticalError};
+pub(crate) use errors::compilation::stringify_errors;
+pub use errors::{
+    CompilationError, LexicalError, LexicalErrorKind, NumberErrorKind, ParseNumberError,
+    SemanticError, StreamError, SyntacticalError,
+};
+pub use incremental::{Document, Edit};
 pub use input::Input;
 pub use lexeme::Lexeme;
+pub use lints::{lint, Lint, LintKind, Linter};
 pub use location::Location;
+pub use optimizer::{optimize, OptimizationLevel};
 pub use parser::Parser;
+pub use resolver::resolve;
 pub use scanner::Scanner;
+pub use stream::StreamScanner;
 pub use token::{Kind, OwnedToken, Token};
+pub use types::{infer, Type};
+pub use warnings::{check_token, Deprecation, Warning, WarningKind};
@@ -1,10 +1,18 @@
 //! Parse a number lexeme into a runtime `Number`.
 //! Also, used to validate the lexical structure of a number.
-//! Numbers in Tortuga cannot have leading 0s in the radix or integer portion,
+//! Numbers in Tortuga cannot have leading 0s in the radix, integer or exponent portion,
 //! and cannot have trailing 0s in the fraction portion.
 //! Also, the radix for a number cannot be more than 2 digits.
+//!
+//! Numbers may be written in scientific notation with an exponent after a `*^` marker (e.g., `6.02*^23`).
+//! The exponent is written in the number's radix and scales the number by a power of the radix
+//! (i.e., `16#1*^A` is 16 to the power of 10).
+//! The usual `e` marker would be ambiguous, since `e` is a digit in radices above 14.
+//!
+//! The digits of the integer, fraction and exponent portions may be grouped by underscores (e.g., `1_000_000`).
+//! An underscore must be preceded and followed by a digit.
 
-use crate::compiler::errors::ParseNumberError;
+use crate::compiler::errors::number::{NumberErrorKind, ParseNumberError};
 use crate::runtime::Number;
 use lazy_static::lazy_static;
 use regex::{Captures, Match, Regex};
@@ -16,6 +24,12 @@ pub const DECIMAL: u32 = 10;
 /// The largest supported radix for numbers with an explicit base.
 pub const MAX_RADIX: u32 = 36;
 
+/// Separates the significand of a number from its exponent.
+pub const EXPONENT_MARKER: &str = "*^";
+
+/// Separates groups of digits in a number.
+pub const DIGIT_SEPARATOR: char = '_';
+
 const DEFAULT_NUMBER_PART: &str = "0";
 const DEFAULT_RADIX: &str = "10";
 
@@ -27,12 +41,13 @@ lazy_static! {
             (?: ( [[:digit:]--0] [[:digit:]]{0, 1}) \# )?
             (?: 
                 (?:
-                    ( 0 | [[:alnum:]--0] [[:alnum:]]* )
-                    (?: \. ( 0? | [[:alnum:]]*? [[:alnum:]--0] ) )?
+                    ( 0 | [[:alnum:]--0] (?: _? [[:alnum:]] )* )
+                    (?: \. ( 0? | (?: [[:alnum:]] _? )*? [[:alnum:]--0] ) )?
                 )
                 |
-                (?: ( 0? ) \. ( 0 | [[:alnum:]]*? [[:alnum:]--0] ) )
+                (?: ( 0? ) \. ( 0 | (?: [[:alnum:]] _? )*? [[:alnum:]--0] ) )
             )
+            (?: \*\^ ( -? (?: 0 | [[:alnum:]--0] (?: _? [[:alnum:]] )* ) ) )?
             $
         "###
     )
@@ -43,36 +58,289 @@ impl FromStr for Number {
     type Err = ParseNumberError;
 
     fn from_str(number: &str) -> Result<Self, Self::Err> {
+        let error = |kind| ParseNumberError::new(number, kind);
         let captures = NUMBER_REGEX
             .captures(number)
-            .ok_or_else(|| ParseNumberError::from(number))?;
+            .ok_or_else(|| error(diagnose(number)))?;
 
         let radix_part = get_match(&captures, 1).unwrap_or(DEFAULT_RADIX);
-        let integer_part = get_matches(&captures, &[2, 4, 6]);
-        let fraction_part = get_matches(&captures, &[3, 5, 7]);
+        let integer_part = get_matches(&captures, &[2, 4]).map(without_separators);
+        let fraction_part = get_matches(&captures, &[3, 5]).map(without_separators);
+        let exponent_part = get_match(&captures, 6).map(without_separators);
+
+        if integer_part.is_none() && fraction_part.is_none() {
+            return Err(error(NumberErrorKind::EmptyFraction));
+        }
 
         let radix: u32 = radix_part
             .parse()
-            .map_err(|_| ParseNumberError::from(number))?;
+            .map_err(|_| error(NumberErrorKind::Malformed))?;
+
+        if !(2..=MAX_RADIX).contains(&radix) {
+            return Err(error(NumberErrorKind::UnsupportedRadix(radix)));
+        }
+
+        let digits = [&integer_part, &fraction_part, &exponent_part]
+            .into_iter()
+            .flatten()
+            .flat_map(|part| part.chars());
 
-        if radix > MAX_RADIX {
-            return Err(number.into());
+        for digit in digits.filter(|c| *c != '-') {
+            if !digit.is_digit(radix) {
+                return Err(error(NumberErrorKind::InvalidDigit { digit, radix }));
+            }
         }
 
-        let integer = u128::from_str_radix(integer_part.unwrap_or(DEFAULT_NUMBER_PART), radix)
-            .map_err(|_| ParseNumberError::from(number))?;
+        let overflow = |_| error(NumberErrorKind::Overflow);
+        let integer = u128::from_str_radix(
+            integer_part.as_deref().unwrap_or(DEFAULT_NUMBER_PART),
+            radix,
+        )
+        .map_err(overflow)?;
 
-        let numerator_part = fraction_part.unwrap_or(DEFAULT_NUMBER_PART);
-        let numerator = u128::from_str_radix(numerator_part, radix)
-            .map_err(|_| ParseNumberError::from(number))?;
+        let numerator_part = fraction_part.as_deref().unwrap_or(DEFAULT_NUMBER_PART);
+        let numerator = u128::from_str_radix(numerator_part, radix).map_err(overflow)?;
         let fraction = (numerator as f64) / (radix as f64).powf(numerator_part.len() as f64);
 
-        if integer_part.is_none() && fraction_part.is_none() {
-            Err(number.into())
+        let exponent = i32::from_str_radix(
+            exponent_part.as_deref().unwrap_or(DEFAULT_NUMBER_PART),
+            radix,
+        )
+        .map_err(overflow)?;
+
+        // Dividing by a positive power is more accurate than multiplying by a negative one.
+        let scale = (radix as f64).powi(exponent.saturating_abs());
+        let significand = (integer as f64) + fraction;
+        let value = if exponent < 0 {
+            significand / scale
+        } else {
+            significand * scale
+        };
+
+        if value.is_finite() {
+            Ok(Number::from(value))
         } else {
-            Ok(Number::from((integer as f64) + fraction))
+            Err(error(NumberErrorKind::Overflow))
+        }
+    }
+}
+
+/// The portions of a number literal, split at the radix, decimal point and exponent markers.
+/// The exponent excludes its sign.
+#[derive(Clone, Copy, Debug)]
+struct Parts<'a> {
+    radix: Option<&'a str>,
+    integer: &'a str,
+    fraction: Option<&'a str>,
+    exponent: Option<&'a str>,
+    negative: bool,
+}
+
+impl<'a> Parts<'a> {
+    /// Splits a number literal into its portions.
+    /// Returns [`None`] if a marker appears more than once or a portion contains characters other than digits.
+    fn split(number: &'a str) -> Option<Self> {
+        let (radix, rest) = match number.split_once('#') {
+            Some((radix, rest)) => (Some(radix), rest),
+            None => (None, number),
+        };
+        let (significand, exponent) = match rest.split_once(EXPONENT_MARKER) {
+            Some((significand, exponent)) => (significand, Some(exponent)),
+            None => (rest, None),
+        };
+        let (integer, fraction) = match significand.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (significand, None),
+        };
+        let negative = exponent.is_some_and(|exponent| exponent.starts_with('-'));
+
+        let parts = Parts {
+            radix,
+            integer,
+            fraction,
+            exponent: exponent.map(|exponent| exponent.strip_prefix('-').unwrap_or(exponent)),
+            negative,
+        };
+        let is_digits = |part: &str| {
+            part.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == DIGIT_SEPARATOR)
+        };
+
+        if parts.iter().all(is_digits) {
+            Some(parts)
+        } else {
+            None
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &'a str> {
+        [self.radix, Some(self.integer), self.fraction, self.exponent]
+            .into_iter()
+            .flatten()
+    }
+
+    /// The digits of this number, excluding the radix.
+    fn digits(&self) -> impl Iterator<Item = char> + 'a {
+        [Some(self.integer), self.fraction, self.exponent]
+            .into_iter()
+            .flatten()
+            .flat_map(str::chars)
+            .filter(|c| *c != DIGIT_SEPARATOR)
+    }
+
+    /// Writes this number in the given radix, without leading zeros, trailing zeros or misplaced separators.
+    fn normalize(&self, radix: Option<&str>) -> String {
+        let radix = radix.map(|radix| trim_leading_zeros(without_separators(radix)));
+        let mut integer = trim_leading_zeros(regroup(self.integer));
+        let mut fraction = self
+            .fraction
+            .map(|fraction| trim_trailing_zeros(regroup(fraction)));
+        let exponent = self
+            .exponent
+            .map(|exponent| trim_leading_zeros(regroup(exponent)))
+            .filter(|exponent| !exponent.is_empty());
+
+        if integer.is_empty() && fraction.as_deref().unwrap_or_default().is_empty() {
+            integer.push('0');
+            fraction = None;
+        }
+
+        let mut number = String::new();
+
+        if let Some(radix) = radix {
+            number.push_str(radix.as_str());
+            number.push('#');
+        }
+
+        number.push_str(integer.as_str());
+
+        if let Some(fraction) = fraction {
+            number.push('.');
+            number.push_str(fraction.as_str());
+        }
+
+        if let Some(exponent) = exponent {
+            number.push_str(EXPONENT_MARKER);
+
+            if self.negative {
+                number.push('-');
+            }
+
+            number.push_str(exponent.as_str());
+        }
+
+        number
+    }
+}
+
+/// Determines why a number literal does not match the [`NUMBER_REGEX`].
+fn diagnose(number: &str) -> NumberErrorKind {
+    let parts = match Parts::split(number) {
+        Some(parts) => parts,
+        None => return NumberErrorKind::Malformed,
+    };
+
+    if let Some(radix) = parts.radix {
+        let value = without_separators(radix).parse().unwrap_or(u32::MAX);
+
+        if radix.is_empty() || radix.chars().any(|c| c.is_ascii_alphabetic()) {
+            return NumberErrorKind::Malformed;
+        } else if radix.contains(DIGIT_SEPARATOR) {
+            return NumberErrorKind::MisplacedSeparator;
+        } else if has_leading_zeros(radix) {
+            return NumberErrorKind::LeadingZeros;
+        } else if !(2..=MAX_RADIX).contains(&value) {
+            return NumberErrorKind::UnsupportedRadix(value);
         }
     }
+
+    let misplaced_separator = |part: &str| {
+        part.starts_with(DIGIT_SEPARATOR) || part.ends_with(DIGIT_SEPARATOR) || part.contains("__")
+    };
+
+    if parts.iter().any(misplaced_separator) {
+        NumberErrorKind::MisplacedSeparator
+    } else if parts.integer.is_empty() && parts.fraction == Some("") {
+        NumberErrorKind::EmptyFraction
+    } else if parts.integer.is_empty() && parts.fraction.is_none() || parts.exponent == Some("") {
+        NumberErrorKind::MissingDigits
+    } else if has_leading_zeros(parts.integer) || parts.exponent.is_some_and(has_leading_zeros) {
+        NumberErrorKind::LeadingZeros
+    } else if parts.fraction.is_some_and(has_trailing_zeros) {
+        NumberErrorKind::TrailingZeros
+    } else {
+        NumberErrorKind::Malformed
+    }
+}
+
+/// Suggests a valid number literal to use instead of a malformed one, if there is one.
+pub(crate) fn suggest(number: &str, kind: NumberErrorKind) -> Option<String> {
+    let parts = Parts::split(number)?;
+    let suggestion = match kind {
+        NumberErrorKind::LeadingZeros
+        | NumberErrorKind::TrailingZeros
+        | NumberErrorKind::EmptyFraction
+        | NumberErrorKind::MissingDigits
+        | NumberErrorKind::MisplacedSeparator => parts.normalize(parts.radix),
+        NumberErrorKind::InvalidDigit { .. } => {
+            // The smallest radix that includes every digit, or decimal if that is large enough.
+            let radix = parts
+                .digits()
+                .filter_map(|digit| digit.to_digit(MAX_RADIX))
+                .max()?
+                + 1;
+
+            if radix <= DECIMAL {
+                parts.normalize(None)
+            } else {
+                parts.normalize(Some(radix.to_string().as_str()))
+            }
+        }
+        _ => return None,
+    };
+
+    Some(suggestion)
+        .filter(|suggestion| suggestion != number && suggestion.parse::<Number>().is_ok())
+}
+
+fn has_leading_zeros(part: &str) -> bool {
+    let digits = without_separators(part);
+
+    digits.len() > 1 && digits.starts_with('0')
+}
+
+fn has_trailing_zeros(part: &str) -> bool {
+    let digits = without_separators(part);
+
+    digits.len() > 1 && digits.ends_with('0')
+}
+
+fn trim_leading_zeros(digits: String) -> String {
+    match digits.trim_start_matches(['0', DIGIT_SEPARATOR]) {
+        "" if !digits.is_empty() => String::from("0"),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn trim_trailing_zeros(digits: String) -> String {
+    match digits.trim_end_matches(['0', DIGIT_SEPARATOR]) {
+        "" if !digits.is_empty() => String::from("0"),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Removes any separators that do not separate two digits.
+fn regroup(digits: &str) -> String {
+    digits
+        .split(DIGIT_SEPARATOR)
+        .filter(|group| !group.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Removes the separators between groups of digits.
+fn without_separators(digits: &str) -> String {
+    digits.replace(DIGIT_SEPARATOR, "")
 }
 
 fn get_matches<'a>(captures: &Captures<'a>, indices: &[usize]) -> Option<&'a str> {
@@ -132,25 +400,115 @@ mod tests {
         validate_number("32#7.002", 7.00006103515625);
     }
 
-    fn invalidate_number(number: &str) {
+    #[test]
+    fn parse_scientific_notation() {
+        validate_number("1*^3", 1000);
+        validate_number("1.5*^3", 1500);
+        validate_number("25*^-2", 0.25);
+        validate_number(".5*^1", 5);
+        validate_number("0*^0", 0);
+        validate_number("6.02214076*^23", 6.02214076 * 10f64.powi(23));
+        validate_number("2#1*^10", 4);
+        validate_number("2#1.1*^-1", 0.75);
+        validate_number("16#1*^A", 16f64.powi(10));
+    }
+
+    #[test]
+    fn parse_digit_separators() {
+        validate_number("1_000_000", 1000000);
+        validate_number("1_0.2_5", 10.25);
+        validate_number("2#1111_0000", 240);
+        validate_number("16#FF_FF.8", 65535.5);
+        validate_number("1*^1_0", 10f64.powi(10));
+        validate_number("0.0_5", 0.05);
+    }
+
+    fn invalidate_number(number: &str, kind: NumberErrorKind) {
         assert_eq!(
             number.parse::<Number>(),
-            Err(ParseNumberError::from(number))
+            Err(ParseNumberError::new(number, kind))
         );
     }
 
     #[test]
     fn parse_invalid_number() {
-        invalidate_number(".");
-        invalidate_number("20#.");
-        invalidate_number("008#1.0");
-        invalidate_number("0#1.0");
-        invalidate_number("0008");
-        invalidate_number(".1000");
-        invalidate_number("2#.100");
-        invalidate_number("37#1.0");
-        invalidate_number("2#4.0");
-        invalidate_number("#1.0");
-        invalidate_number("#.");
+        invalidate_number(".", NumberErrorKind::EmptyFraction);
+        invalidate_number("20#.", NumberErrorKind::EmptyFraction);
+        invalidate_number("008#1.0", NumberErrorKind::LeadingZeros);
+        invalidate_number("0#1.0", NumberErrorKind::UnsupportedRadix(0));
+        invalidate_number("1#1", NumberErrorKind::UnsupportedRadix(1));
+        invalidate_number("0008", NumberErrorKind::LeadingZeros);
+        invalidate_number(".1000", NumberErrorKind::TrailingZeros);
+        invalidate_number("2#.100", NumberErrorKind::TrailingZeros);
+        invalidate_number("37#1.0", NumberErrorKind::UnsupportedRadix(37));
+        invalidate_number("300#1", NumberErrorKind::UnsupportedRadix(300));
+        invalidate_number(
+            "2#4.0",
+            NumberErrorKind::InvalidDigit {
+                digit: '4',
+                radix: 2,
+            },
+        );
+        invalidate_number("#1.0", NumberErrorKind::Malformed);
+        invalidate_number("#.", NumberErrorKind::Malformed);
+        invalidate_number("1.2.3", NumberErrorKind::Malformed);
+        invalidate_number("16#", NumberErrorKind::MissingDigits);
+        invalidate_number("1*^", NumberErrorKind::MissingDigits);
+        invalidate_number("1*^01", NumberErrorKind::LeadingZeros);
+        invalidate_number("1*^-", NumberErrorKind::MissingDigits);
+        invalidate_number("*^1", NumberErrorKind::MissingDigits);
+        invalidate_number(
+            "2#1*^2",
+            NumberErrorKind::InvalidDigit {
+                digit: '2',
+                radix: 2,
+            },
+        );
+        invalidate_number("1*^400", NumberErrorKind::Overflow);
+        invalidate_number("36#1*^ZZZZZZZ", NumberErrorKind::Overflow);
+        invalidate_number("1__0", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("_1", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("1_", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("1_.5", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("1._5", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("1.5_", NumberErrorKind::MisplacedSeparator);
+        invalidate_number("1.5_0", NumberErrorKind::TrailingZeros);
+        invalidate_number("1_6#1", NumberErrorKind::MisplacedSeparator);
+    }
+
+    fn suggestion(number: &str) -> Option<String> {
+        number.parse::<Number>().unwrap_err().suggestion()
+    }
+
+    #[test]
+    fn suggest_number() {
+        assert_eq!(suggestion("0008").as_deref(), Some("8"));
+        assert_eq!(suggestion("008#1.0").as_deref(), Some("8#1.0"));
+        assert_eq!(suggestion("00.5").as_deref(), Some("0.5"));
+        assert_eq!(suggestion("1.00").as_deref(), Some("1.0"));
+        assert_eq!(suggestion("2#.100").as_deref(), Some("2#.1"));
+        assert_eq!(suggestion("1.5_0").as_deref(), Some("1.5"));
+        assert_eq!(suggestion("2#4.0").as_deref(), Some("4.0"));
+        assert_eq!(suggestion("16#G").as_deref(), Some("17#G"));
+        assert_eq!(suggestion(".").as_deref(), Some("0"));
+        assert_eq!(suggestion("20#.").as_deref(), Some("20#0"));
+        assert_eq!(suggestion("1__000").as_deref(), Some("1_000"));
+        assert_eq!(suggestion("1*^").as_deref(), Some("1"));
+        assert_eq!(suggestion("1*^-01").as_deref(), Some("1*^-1"));
+        assert_eq!(suggestion("37#1.0"), None);
+        assert_eq!(suggestion("1*^400"), None);
+        assert_eq!(suggestion("1.2.3"), None);
+    }
+
+    #[test]
+    fn error_message() {
+        assert_eq!(
+            "0008".parse::<Number>().unwrap_err().to_string(),
+            "Unable to parse number \"0008\" to a runtime value; numbers cannot have leading zeros. Did you mean `8`?"
+        );
+        assert_eq!(
+            "37#1".parse::<Number>().unwrap_err().to_string(),
+            "Unable to parse number \"37#1\" to a runtime value; the radix 37 is not between 2 and 36."
+        );
     }
 }
@@ -0,0 +1,373 @@
+//! Optimizes a syntax tree before it is run, without changing the result of running it.
+//!
+//! Optimizations are grouped into levels; each level includes the optimizations of the levels below it:
+//! 1. Numeric literals are parsed ahead of time and operations on constants are folded into a single literal.
+//!    Constant comparisons at the start of a chain of comparisons are folded as well.
+//! 2. The algebraic identities `x * 1`, `1 * x`, `x / 1`, `x ^ 1` and `x - 0` are simplified to `x`.
+//!
+//! Constants are folded by evaluating them, so folding follows the same rules as the runtime (e.g., for tolerances).
+//! Results that cannot be written as a literal (e.g., division by zero) are left for the runtime.
+//! The identities only hold for numbers and tolerances, and an operation evaluates to a number or a tolerance, or fails,
+//! so they are only simplified when `x` is the result of an operation.
+//! `x + 0` is not an identity, since `-0 + 0` is `0`.
+
+use crate::grammar::*;
+use crate::runtime::{self, compare, Environment, Interpret, Value};
+
+/// How aggressively to optimize a [`Program`].
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OptimizationLevel {
+    /// Run the program as written.
+    #[default]
+    None,
+    /// Pre-parse numeric literals and fold constants.
+    Fold,
+    /// Fold constants and simplify algebraic identities.
+    Simplify,
+}
+
+impl From<u8> for OptimizationLevel {
+    fn from(level: u8) -> Self {
+        match level {
+            0 => OptimizationLevel::None,
+            1 => OptimizationLevel::Fold,
+            _ => OptimizationLevel::Simplify,
+        }
+    }
+}
+
+/// Optimizes the given [`Program`] in place.
+pub fn optimize(program: &mut Program, level: OptimizationLevel) {
+    if level == OptimizationLevel::None {
+        return;
+    }
+
+    let optimizer = Optimizer { level };
+
+    match program {
+        Program::Expressions(expressions) => {
+            for expression in expressions.iter_mut() {
+                optimizer.optimize_expression(expression);
+            }
+        }
+        Program::Comparisons(comparisons) => optimizer.optimize_comparisons(comparisons),
+    }
+}
+
+/// Walks a syntax tree bottom-up, so the operands of an operation are optimized before the operation itself.
+#[derive(Debug)]
+struct Optimizer {
+    level: OptimizationLevel,
+}
+
+impl Optimizer {
+    fn optimize_expression(&self, expression: &mut Expression) {
+        match expression {
+            Expression::Number(number) => parse(number),
+            Expression::Identifier(_) => (),
+            Expression::Grouping(grouping) => {
+                self.optimize_expression(grouping.inner_mut());
+
+                if is_constant(grouping.inner()) {
+                    *expression = grouping.inner().clone();
+                }
+            }
+            Expression::Call(call) => {
+                self.optimize_expression(call.callee_mut());
+
+                for argument in call.arguments_mut().iter_mut() {
+                    self.optimize_expression(argument);
+                }
+            }
+            Expression::Operation(operation) => {
+                self.optimize_expression(&mut operation.lhs);
+                self.optimize_expression(&mut operation.rhs);
+
+                if let Some(folded) = fold(operation) {
+                    *expression = folded;
+                } else if self.level >= OptimizationLevel::Simplify {
+                    if let Some(simplified) = simplify(operation) {
+                        *expression = simplified;
+                    }
+                }
+            }
+            Expression::Assignment(assignment) => {
+                for parameter in assignment.function_mut().parameters_mut() {
+                    self.optimize_pattern(parameter);
+                }
+
+                for expression in assignment.block_mut().iter_mut() {
+                    self.optimize_expression(expression);
+                }
+            }
+        }
+    }
+
+    fn optimize_pattern(&self, pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Function(_) => (),
+            Pattern::Refinement(refinement) => {
+                self.optimize_expression(refinement.constraint_mut())
+            }
+            Pattern::Bounds(bounds) => {
+                self.optimize_expression(bounds.left_mut().constraint_mut());
+                self.optimize_expression(bounds.right_mut().constraint_mut());
+            }
+        }
+    }
+
+    /// Optimizes each operand, then folds the leading constant comparisons.
+    /// A constant comparison that is false ends the chain, since the remaining comparisons are never evaluated.
+    /// Constant comparisons that are true are dropped, unless no other comparisons remain.
+    fn optimize_comparisons(&self, comparisons: &mut Comparisons) {
+        self.optimize_expression(comparisons.lhs_mut());
+
+        for comparison in comparisons.comparisons_mut().iter_mut() {
+            self.optimize_expression(comparison.rhs_mut());
+        }
+
+        let mut lhs = comparisons.lhs().clone();
+        let mut remaining: Vec<Comparison> = comparisons.comparisons().iter().cloned().collect();
+        let mut environment = Environment::default();
+
+        while remaining.len() > 1 && is_constant(&lhs) && is_constant(remaining[0].rhs()) {
+            let comparison = &remaining[0];
+            let value = match (
+                lhs.execute(&mut environment),
+                comparison.rhs().execute(&mut environment),
+            ) {
+                (Ok(a), Ok(b)) => compare(&a, comparison.comparator(), &b),
+                _ => break,
+            };
+
+            if value == Value::Boolean(false) {
+                remaining.truncate(1);
+            } else {
+                lhs = remaining.remove(0).rhs().clone();
+            }
+        }
+
+        let head = remaining.remove(0);
+
+        *comparisons = Comparisons::new(lhs, List::new(head, remaining));
+    }
+}
+
+/// The value of an expression that folds to a number literal (e.g., `-2 ^ 3`), if any.
+pub(crate) fn constant(expression: &Expression) -> Option<f64> {
+    let mut expression = expression.clone();
+    let optimizer = Optimizer {
+        level: OptimizationLevel::Fold,
+    };
+
+    optimizer.optimize_expression(&mut expression);
+
+    match expression {
+        Expression::Number(number) => number.value(),
+        _ => None,
+    }
+}
+
+/// Parses a numeric literal ahead of time.
+/// Literals that fail to parse are left for the runtime to report.
+fn parse(number: &mut Number) {
+    if number.value().is_some() {
+        return;
+    }
+
+    if let Ok(value) = number.number().as_str().parse::<runtime::Number>() {
+        let value = value.to_f64();
+
+        number.set_value(if number.is_negative() { -value } else { value });
+    }
+}
+
+/// Tests whether an expression is a literal number or tolerance.
+fn is_constant(expression: &Expression) -> bool {
+    match expression {
+        Expression::Number(number) => number.value().is_some(),
+        Expression::Operation(operation) => {
+            operation.operator() == &Operator::Tolerance
+                && matches!(operation.lhs(), Expression::Number(number) if number.value().is_some())
+                && matches!(operation.rhs(), Expression::Number(number) if number.value().is_some())
+        }
+        _ => false,
+    }
+}
+
+/// Tests whether an expression evaluates to a number or a tolerance, or fails.
+fn is_numeric(expression: &Expression) -> bool {
+    match expression {
+        Expression::Number(_) | Expression::Operation(_) => true,
+        Expression::Grouping(grouping) => is_numeric(grouping.inner()),
+        _ => false,
+    }
+}
+
+fn is_literal(expression: &Expression, literal: f64) -> bool {
+    match expression {
+        Expression::Number(number) => number.value().map(f64::to_bits) == Some(literal.to_bits()),
+        _ => false,
+    }
+}
+
+/// Evaluates an operation on constants, if the result can be written as a literal.
+fn fold(operation: &Operation) -> Option<Expression> {
+    if !is_constant(operation.lhs()) || !is_constant(operation.rhs()) {
+        return None;
+    }
+
+    match operation.execute(&mut Environment::default()).ok()? {
+        Value::Number(number) if number.to_f64().is_finite() => {
+            Some(Number::from(number.to_f64()).into())
+        }
+        Value::Tolerance(tolerance)
+            if tolerance.center().to_f64().is_finite()
+                && tolerance.epsilon().to_f64().is_finite() =>
+        {
+            Some(
+                Operation::new(
+                    Number::from(tolerance.center().to_f64()).into(),
+                    Operator::Tolerance,
+                    Number::from(tolerance.epsilon().to_f64()).into(),
+                )
+                .into(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Simplifies an operation with an identity element to its other operand.
+fn simplify(operation: &Operation) -> Option<Expression> {
+    let (lhs, rhs) = (operation.lhs(), operation.rhs());
+
+    match operation.operator() {
+        Operator::Multiply | Operator::Divide | Operator::Exponent
+            if is_literal(rhs, 1.0) && is_numeric(lhs) =>
+        {
+            Some(lhs.clone())
+        }
+        Operator::Multiply if is_literal(lhs, 1.0) && is_numeric(rhs) => Some(rhs.clone()),
+        Operator::Subtract if is_literal(rhs, 0.0) && is_numeric(lhs) => Some(lhs.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+
+    fn optimized(source: &str, level: OptimizationLevel) -> Program {
+        let mut program: Program = source.parse().unwrap();
+
+        optimize(&mut program, level);
+
+        program
+    }
+
+    fn head(program: &Program) -> &Expression {
+        match program {
+            Program::Expressions(expressions) => expressions.head(),
+            _ => panic!("Expected expressions."),
+        }
+    }
+
+    #[test]
+    fn fold_constants() {
+        let program = optimized(
+            "@f(@x) = x + 2 ^ (10 - 1 * 8)\nf(1) + (2 + 1)",
+            OptimizationLevel::Fold,
+        );
+        let expressions: Vec<&Expression> = match &program {
+            Program::Expressions(expressions) => expressions.iter().collect(),
+            _ => panic!("Expected expressions."),
+        };
+
+        match expressions[0] {
+            Expression::Assignment(assignment) => assert_eq!(
+                assignment.block().head(),
+                &"x + 4"
+                    .parse::<Program>()
+                    .map(|p| head(&p).clone())
+                    .unwrap()
+            ),
+            _ => panic!("Expected an assignment."),
+        }
+
+        assert!(
+            matches!(expressions[1], Expression::Operation(operation) if operation.rhs() == &Number::from(3.0).into())
+        );
+        assert_eq!(
+            head(&optimized("(1 ~ 0.5) * 3 + 2", OptimizationLevel::Fold)),
+            &Operation::new(
+                Number::from(5.0).into(),
+                Operator::Tolerance,
+                Number::from(0.5).into()
+            )
+            .into()
+        );
+        assert_eq!(
+            head(&optimized("1 / 0", OptimizationLevel::Fold)),
+            head(&"1 / 0".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn simplify_identities() {
+        let simplify = |source| head(&optimized(source, OptimizationLevel::Simplify)).clone();
+        let parse = |source: &str| head(&source.parse().unwrap()).clone();
+
+        assert_eq!(simplify("(x + y) * 1"), parse("(x + y)"));
+        assert_eq!(simplify("1 * (x ^ 2) ^ 1 / 1 - 0"), parse("(x ^ 2)"));
+        assert_eq!(simplify("x * 1"), parse("x * 1"));
+        assert_eq!(simplify("(x + y) + 0"), parse("(x + y) + 0"));
+        assert_eq!(
+            head(&optimized("(x + y) * 1", OptimizationLevel::Fold)),
+            &parse("(x + y) * 1")
+        );
+    }
+
+    #[test]
+    fn fold_comparisons() {
+        let fold = |source: &str| match optimized(source, OptimizationLevel::Fold) {
+            Program::Comparisons(comparisons) => comparisons,
+            _ => panic!("Expected comparisons."),
+        };
+
+        assert_eq!(fold("1 < 2 < x = 3"), fold("2 < x = 3"));
+        assert_eq!(fold("1 < 2 > 3 < x"), fold("2 > 3"));
+        assert_eq!(fold("1 < 2 < 3"), fold("2 < 3"));
+        assert_eq!(fold("x < 2 < 3"), fold("x < 2 < 3"));
+    }
+
+    #[test]
+    fn preserve_results() {
+        let sources = [
+            include_str!("../../examples/factorial.ta"),
+            include_str!("../../examples/simple.ta"),
+            "@f(@x) = (x ~ 1) * 1 + 2 ^ 1\nf(3)",
+            "@f(@x) = (x - 0) * 1\n@g(@x) = 1 * (x * 2) / 1\nf(-0) + g(2 ~ 1)",
+            "-0 * 1 - 0",
+            "1 < 2 < 2 ^ 2 = 4 ~ 1",
+            "(-2 + 1) ^ (1 / 2)",
+            "2 ^ 10 + -3 % 2 - 7 % -2",
+        ];
+
+        for source in sources {
+            let expected = Interpreter::build_then_run(source);
+
+            for level in [OptimizationLevel::Fold, OptimizationLevel::Simplify] {
+                let program: Program = source.parse().unwrap();
+                let actual = Interpreter::default().with_optimization(level).run(program);
+
+                assert_eq!(
+                    actual.as_ref().map(ToString::to_string),
+                    expected.as_ref().map(ToString::to_string),
+                    "{source}"
+                );
+            }
+        }
+    }
+}
@@ -3,13 +3,16 @@
 mod tokens;
 
 use crate::compiler::parser::tokens::TokenMatcher;
-use crate::compiler::{Kind, Token};
+use crate::compiler::{patterns, purity, types, warnings};
+use crate::compiler::{CompilationError, SemanticError, Warning};
+use crate::compiler::{Kind, Location, Token};
 use crate::grammar::lexical;
 use crate::grammar::syntax::*;
 use crate::{Scanner, SyntacticalError};
+use std::mem;
 use std::str::FromStr;
-use tokens::Tokens;
-use tracing::{debug, error};
+pub(crate) use tokens::Tokens;
+use tracing::{debug, error, warn};
 
 const COMPARISON_KINDS: &[Kind] = &[
     Kind::LessThan,
@@ -45,6 +48,7 @@ const SYNC_KINDS: &[Kind] = &[
 pub struct Parser<'a> {
     tokens: Tokens<'a>,
     errors: Vec<SyntacticalError>,
+    warnings: Vec<Warning>,
 }
 
 impl<'a> From<Tokens<'a>> for Parser<'a> {
@@ -52,10 +56,19 @@ impl<'a> From<Tokens<'a>> for Parser<'a> {
         Parser {
             tokens,
             errors: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 }
 
+impl<'a> TryFrom<&'a str> for Parser<'a> {
+    type Error = SyntacticalError;
+
+    fn try_from(source: &'a str) -> Result<Self, Self::Error> {
+        Ok(Parser::from(Tokens::try_from(Scanner::from(source))?))
+    }
+}
+
 impl<'a> Parser<'a> {
     /// Advances the token sequence and returns the next value if the token is one of the expected [`Kind`]s.
     ///
@@ -79,7 +92,91 @@ impl<'a> Parser<'a> {
     }
 
     /// Generate a syntax tree rooted at a `Program` for this `Parser`'s sequence of tokens.
-    pub fn parse(mut self) -> Result<Program, SyntacticalError> {
+    /// The tree is checked to ensure functions never call procedures and operators are applied to operands of the right types.
+    /// Any [`Warning`] (e.g., a use of deprecated functionality) is logged.
+    pub fn parse(self) -> Result<Program, CompilationError> {
+        let (result, warnings) = self.parse_with_warnings();
+
+        for warning in warnings {
+            warn!("{warning}");
+        }
+
+        result
+    }
+
+    /// Generate a syntax tree like [`Self::parse`], returning any [`Warning`]s instead of logging them.
+    /// The warnings are returned even if the source fails to parse.
+    pub fn parse_with_warnings(mut self) -> (Result<Program, CompilationError>, Vec<Warning>) {
+        let result = self.parse_program();
+
+        (result, self.warnings)
+    }
+
+    /// Parses each top-level expression as its own [`Program`], along with its documentation comments and start [`Location`].
+    /// An expression followed by comparisons is parsed as a comparisons program, so comparisons may appear between other expressions.
+    /// The expressions are checked together, while each comparisons program is checked on its own.
+    /// Any [`Warning`] is logged.
+    pub(crate) fn parse_statements(
+        mut self,
+    ) -> Result<Vec<(Program, Option<String>, Location)>, CompilationError> {
+        let mut statements = Vec::new();
+
+        while let Some(location) = self.tokens.peek().map(|token| *token.lexeme().start()) {
+            let doc = self.tokens.doc_comment();
+            let expression = self.parse_expression()?;
+
+            let program = if self.next_is_comparator() {
+                let head = self.parse_comparison()?;
+                let mut comparisons = Vec::new();
+
+                while self.next_is_comparator() {
+                    comparisons.push(self.parse_comparison()?);
+                }
+
+                Comparisons::new(expression, List::new(head, comparisons)).into()
+            } else {
+                List::new(expression, Vec::new()).into()
+            };
+
+            statements.push((program, doc, location));
+        }
+
+        if !self.errors.is_empty() {
+            for error in self.errors.iter().rev() {
+                error!("{error}");
+            }
+
+            return Err(SyntacticalError::Multiple(self.errors).into());
+        }
+
+        let mut expressions = statements
+            .iter()
+            .filter_map(|(program, _, _)| match program {
+                Program::Expressions(expressions) => Some(expressions.head().clone()),
+                Program::Comparisons(_) => None,
+            });
+
+        if let Some(head) = expressions.next() {
+            check(
+                List::new(head, expressions.collect()).into(),
+                &mut self.warnings,
+            )?;
+        }
+
+        for (program, _, _) in statements.iter() {
+            if let Program::Comparisons(_) = program {
+                check(program.clone(), &mut self.warnings)?;
+            }
+        }
+
+        for warning in self.warnings.iter() {
+            warn!("{warning}");
+        }
+
+        Ok(statements)
+    }
+
+    fn parse_program(&mut self) -> Result<Program, CompilationError> {
         let expression = self.parse_expression()?;
 
         let result = match self.tokens.peek_kind() {
@@ -95,20 +192,57 @@ impl<'a> Parser<'a> {
         };
 
         if self.errors.is_empty() {
-            result
+            check(result?, &mut self.warnings)
         } else {
-            if let Err(error) = result {
+            if let Err(error) = &result {
                 error!("{error}");
             }
 
-            for error in self.errors.into_iter().rev() {
+            for error in self.errors.iter().rev() {
                 error!("{error}");
             }
 
-            Err(SyntacticalError::Multiple)
+            self.errors.extend(result.err());
+
+            Err(SyntacticalError::Multiple(mem::take(&mut self.errors)).into())
         }
     }
 
+    /// Parses the top-level expression that starts at the token with the given index, without recovering from errors.
+    /// Returns the expression and the uses of deprecated functionality in it.
+    pub(crate) fn parse_top_level(
+        &mut self,
+        index: usize,
+    ) -> Result<(Expression, Vec<Warning>), SyntacticalError> {
+        self.tokens.seek(index);
+
+        let expression = self.parse_expression_not_synchronized()?;
+
+        // Nested blocks recover from errors on their own, so the expression may still be invalid.
+        if !self.errors.is_empty() {
+            return Err(SyntacticalError::Multiple(mem::take(&mut self.errors)));
+        }
+
+        Ok((expression, mem::take(&mut self.warnings)))
+    }
+
+    /// Moves back to the first token, discarding any errors and warnings found so far.
+    pub(crate) fn rewind(&mut self) {
+        self.tokens.rewind();
+        self.errors.clear();
+        self.warnings.clear();
+    }
+
+    /// The index of the next token to parse.
+    pub(crate) fn position(&self) -> usize {
+        self.tokens.position()
+    }
+
+    /// Tests whether the next token to parse compares two expressions.
+    pub(crate) fn next_is_comparator(&self) -> bool {
+        self.tokens.next_matches(COMPARISON_KINDS) == Some(true)
+    }
+
     fn parse_expressions(&mut self, expression: Expression) -> Result<Program, SyntacticalError> {
         let mut expressions = Vec::new();
 
@@ -174,20 +308,22 @@ impl<'a> Parser<'a> {
         if let Some(true) = self.tokens.next_matches(NAME_KINDS) {
             self.parse_assignment().map(Expression::from)
         } else {
-            self.parse_arithmetic().map(Expression::from)
+            self.parse_arithmetic()
         }
     }
 
     fn parse_arithmetic(&mut self) -> Result<Expression, SyntacticalError> {
-        Ok(self.parse_epsilon()?.into())
+        self.parse_epsilon()
     }
 
     fn parse_epsilon(&mut self) -> Result<Expression, SyntacticalError> {
         let lhs = self.parse_modulo()?;
 
-        if self.tokens.next_if_match(Kind::Tilde).is_some() {
+        if let Some(token) = self.tokens.next_if_match(Kind::Tilde) {
             let rhs = self.parse_modulo()?;
-            Ok(Operation::new(lhs, Operator::Tolerance, rhs).into())
+            Ok(Operation::new(lhs, Operator::Tolerance, rhs)
+                .at(*token.lexeme().start())
+                .into())
         } else {
             Ok(lhs)
         }
@@ -196,10 +332,12 @@ impl<'a> Parser<'a> {
     fn parse_modulo(&mut self) -> Result<Expression, SyntacticalError> {
         let mut lhs = self.parse_sum()?;
 
-        while self.tokens.next_if_match(Kind::Percent).is_some() {
+        while let Some(token) = self.tokens.next_if_match(Kind::Percent) {
             let rhs = self.parse_sum()?;
 
-            lhs = Operation::new(lhs, Operator::Modulo, rhs).into();
+            lhs = Operation::new(lhs, Operator::Modulo, rhs)
+                .at(*token.lexeme().start())
+                .into();
         }
 
         Ok(lhs)
@@ -208,14 +346,16 @@ impl<'a> Parser<'a> {
     fn parse_sum(&mut self) -> Result<Expression, SyntacticalError> {
         let mut lhs = self.parse_product()?;
 
-        while let Some(token) = self.tokens.next_if_match(&[Kind::Plus, Kind::Minus]) {
+        while let Some(token) = self.tokens.next_if_match([Kind::Plus, Kind::Minus]) {
             let rhs = self.parse_product()?;
             let operator = match token.kind() {
                 Kind::Minus => Operator::Subtract,
                 _ => Operator::Add,
             };
 
-            lhs = Operation::new(lhs, operator, rhs).into();
+            lhs = Operation::new(lhs, operator, rhs)
+                .at(*token.lexeme().start())
+                .into();
         }
 
         Ok(lhs)
@@ -224,14 +364,16 @@ impl<'a> Parser<'a> {
     fn parse_product(&mut self) -> Result<Expression, SyntacticalError> {
         let mut lhs = self.parse_power()?;
 
-        while let Some(token) = self.tokens.next_if_match(&[Kind::Star, Kind::Slash]) {
+        while let Some(token) = self.tokens.next_if_match([Kind::Star, Kind::Slash]) {
             let rhs = self.parse_power()?;
             let operator = match token.kind() {
                 Kind::Slash => Operator::Divide,
                 _ => Operator::Multiply,
             };
 
-            lhs = Operation::new(lhs, operator, rhs).into();
+            lhs = Operation::new(lhs, operator, rhs)
+                .at(*token.lexeme().start())
+                .into();
         }
 
         Ok(lhs)
@@ -240,9 +382,11 @@ impl<'a> Parser<'a> {
     fn parse_power(&mut self) -> Result<Expression, SyntacticalError> {
         let mut lhs = self.parse_call()?;
 
-        while self.tokens.next_if_match(Kind::Caret).is_some() {
+        while let Some(token) = self.tokens.next_if_match(Kind::Caret) {
             let rhs = self.parse_call()?;
-            lhs = Operation::new(lhs, Operator::Exponent, rhs).into();
+            lhs = Operation::new(lhs, Operator::Exponent, rhs)
+                .at(*token.lexeme().start())
+                .into();
         }
 
         Ok(lhs)
@@ -251,16 +395,30 @@ impl<'a> Parser<'a> {
     fn parse_call(&mut self) -> Result<Expression, SyntacticalError> {
         let mut expression = self.parse_primary()?;
 
-        while let Some(true) = self.tokens.next_matches(Kind::LeftParenthesis) {
-            let arguments = self.parse_arguments()?;
-            expression = Call::new(expression, arguments).into();
+        while let Some(true) = self
+            .tokens
+            .next_matches([Kind::LeftParenthesis, Kind::Exclamation])
+        {
+            let location = self
+                .tokens
+                .peek()
+                .map(|token| *token.lexeme().start())
+                .unwrap_or_default();
+
+            if self.tokens.next_if_match(Kind::Exclamation).is_some() {
+                let arguments = self.parse_arguments(true)?;
+                expression = Call::procedure(expression, arguments).at(location).into();
+            } else {
+                let arguments = self.parse_arguments(false)?;
+                expression = Call::new(expression, arguments).at(location).into();
+            }
         }
 
-        Ok(expression.into())
+        Ok(expression)
     }
 
     fn parse_primary(&mut self) -> Result<Expression, SyntacticalError> {
-        let token = self.next_kind(&[
+        let token = self.next_kind([
             Kind::Minus,
             Kind::Number,
             Kind::Identifier,
@@ -275,36 +433,48 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_number(&mut self, token: Token) -> Result<Number, SyntacticalError> {
-        match token.kind() {
-            Kind::Minus => {
-                let number = self.next_kind(Kind::Number)?;
+        let (negative, number) = match token.kind() {
+            Kind::Minus => (true, self.next_kind(Kind::Number)?),
+            _ => (false, token),
+        };
 
-                Ok(Number::new(true, lexical::Number::new(number.as_str())))
+        // Panic mode may parse the same token more than once.
+        if let Some(warning) = warnings::check_token(&number) {
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
             }
-            _ => Ok(Number::new(false, lexical::Number::new(token.as_str()))),
         }
+
+        Ok(Number::new(negative, lexical::Number::new(number.as_str())))
     }
 
     fn parse_identifier(
         &mut self,
         identifier: Token,
     ) -> Result<lexical::Identifier, SyntacticalError> {
-        Ok(lexical::Identifier::new(identifier.as_str()))
+        Ok(identifier
+            .symbol()
+            .map(lexical::Identifier::from)
+            .unwrap_or_else(|| lexical::Identifier::new(identifier.as_str())))
     }
 
-    fn parse_arguments(&mut self) -> Result<Arguments, SyntacticalError> {
+    // Procedures may have side-effects, so unlike functions they may be called without arguments.
+    fn parse_arguments(&mut self, procedure: bool) -> Result<Arguments, SyntacticalError> {
         self.next_kind(Kind::LeftParenthesis)?;
 
-        let head = self.parse_expression()?;
-        let mut tail = Vec::new();
+        if procedure && self.tokens.next_if_match(Kind::RightParenthesis).is_some() {
+            return Ok(Vec::new());
+        }
+
+        let mut arguments = vec![self.parse_expression()?];
 
         while self.tokens.next_if_match(Kind::Comma).is_some() {
-            tail.push(self.parse_expression()?);
+            arguments.push(self.parse_expression()?);
         }
 
         self.next_kind(Kind::RightParenthesis)?;
 
-        Ok(List::new(head, tail))
+        Ok(arguments)
     }
 
     fn parse_grouping(&mut self, _: Token) -> Result<Grouping, SyntacticalError> {
@@ -316,20 +486,33 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_assignment(&mut self) -> Result<Assignment, SyntacticalError> {
+        let doc = self.tokens.doc_comment();
+        let location = self.tokens.peek().map(|token| *token.lexeme().start());
         let function = self.parse_function()?;
 
         self.next_kind(Kind::Equal)?;
 
         let block = self.parse_block()?;
+        let assignment = Assignment::new(function, block).at(location.unwrap_or_default());
 
-        Ok(Assignment::new(function, block))
+        Ok(match doc {
+            Some(doc) => assignment.with_doc(doc),
+            None => assignment,
+        })
     }
 
     fn parse_function(&mut self) -> Result<Function, SyntacticalError> {
         let name = self.parse_name()?;
-        let parameters = self.parse_parameters()?;
 
-        Ok(Function::new(name, parameters))
+        if self.tokens.next_if_match(Kind::Exclamation).is_some() {
+            let parameters = self.parse_procedure_parameters()?;
+
+            Ok(Function::procedure(name, parameters))
+        } else {
+            let parameters = self.parse_parameters()?;
+
+            Ok(Function::new(name, parameters))
+        }
     }
 
     fn parse_name(&mut self) -> Result<Name, SyntacticalError> {
@@ -339,7 +522,7 @@ impl<'a> Parser<'a> {
             Kind::At => {
                 let identifier = self.next_kind(Kind::Identifier)?;
 
-                Ok(Name::from(lexical::Identifier::new(identifier.as_str())))
+                Ok(Name::from(self.parse_identifier(identifier)?))
             }
             _ => Ok(Name::Anonymous),
         }
@@ -361,11 +544,23 @@ impl<'a> Parser<'a> {
         Ok(parameters)
     }
 
+    // Declarations without parameters are variables, so procedures must declare at least one parameter.
+    fn parse_procedure_parameters(&mut self) -> Result<Vec<Pattern>, SyntacticalError> {
+        match self.tokens.next_matches(Kind::LeftParenthesis) {
+            Some(true) => self.parse_parameters(),
+            _ => self.next_kind(Kind::LeftParenthesis).map(|_| Vec::new()),
+        }
+    }
+
     fn parse_pattern(&mut self) -> Result<Pattern, SyntacticalError> {
         if let Some(true) = self.tokens.next_matches(NAME_KINDS) {
             let name = self.parse_name()?;
 
-            if let Some(true) = self.tokens.next_matches(COMPARISON_KINDS) {
+            if self.tokens.next_if_match(Kind::Exclamation).is_some() {
+                let parameters = self.parse_procedure_parameters()?;
+
+                Ok(Function::procedure(name, parameters).into())
+            } else if let Some(true) = self.tokens.next_matches(COMPARISON_KINDS) {
                 self.parse_refinement(name)
             } else {
                 let parameters = self.parse_parameters()?;
@@ -429,14 +624,40 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Checks a syntax tree to ensure functions never call procedures and operators are applied to operands of the right types.
+/// Declarations that can never match and functions that do not match every number are added to the given [`Warning`]s,
+/// even when the checks fail.
+pub(crate) fn check(
+    program: Program,
+    warnings: &mut Vec<Warning>,
+) -> Result<Program, CompilationError> {
+    let mut errors = purity::check(&program);
+
+    errors.extend(types::check(&program));
+    warnings.extend(warnings::check_calls(&program));
+    warnings.extend(patterns::check(&program));
+
+    validate(errors).map(|()| program)
+}
+
+/// Logs the semantic errors found in a program, failing if there are any.
+pub(crate) fn validate(errors: Vec<SemanticError>) -> Result<(), CompilationError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        for error in errors.iter() {
+            error!("{error}");
+        }
+
+        Err(CompilationError::Semantic(errors))
+    }
+}
+
 impl FromStr for Program {
-    type Err = SyntacticalError;
+    type Err = CompilationError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens = Tokens::try_from(Scanner::from(s))?;
-        let parser = Parser::from(tokens);
-
-        parser.parse()
+        Parser::try_from(s)?.parse()
     }
 }
 
@@ -453,7 +674,10 @@ mod tests {
     fn parse_with_panic() {
         let result = "+x".parse::<Program>();
 
-        assert_eq!(result, Err(SyntacticalError::Multiple));
+        assert!(matches!(
+            result,
+            Err(CompilationError::Syntactical(SyntacticalError::Multiple(errors))) if errors.len() == 1
+        ));
     }
 
     #[test]
@@ -480,6 +704,69 @@ mod tests {
             .is_ok())
     }
 
+    #[test]
+    fn parse_procedure() {
+        let program = "@p!(@x) = x\np!(1)".parse::<Program>().unwrap();
+        let expected: Program = List::new(
+            Assignment::new(
+                Function::procedure(
+                    Name::from(lexical::Identifier::new("p")),
+                    vec![
+                        Function::new(Name::from(lexical::Identifier::new("x")), Vec::new()).into(),
+                    ],
+                ),
+                List::new(lexical::Identifier::new("x"), Vec::new()),
+            ),
+            vec![Call::procedure(
+                lexical::Identifier::new("p").into(),
+                vec![Number::new(false, lexical::Number::new("1")).into()],
+            )
+            .into()],
+        )
+        .into();
+
+        assert_eq!(program, expected);
+    }
+
+    #[test]
+    fn parse_procedure_without_arguments() {
+        let program = "p!()".parse::<Program>().unwrap();
+        let expected: Program = List::new(
+            Call::procedure(lexical::Identifier::new("p").into(), Vec::new()),
+            Vec::new(),
+        )
+        .into();
+
+        assert_eq!(program, expected);
+        assert!("p()".parse::<Program>().is_err());
+    }
+
+    #[test]
+    fn parse_doc_comments() {
+        let program: Program = "; Not documented.\n;; Squares a number.\n;;   Indented.\n@square(@x) = x * x\n;; Dangling.\nsquare(2)"
+            .parse()
+            .unwrap();
+
+        let expressions = match program {
+            Program::Expressions(expressions) => expressions,
+            Program::Comparisons(_) => panic!("Expected a sequence of expressions."),
+        };
+
+        match expressions.head() {
+            Expression::Assignment(assignment) => {
+                assert_eq!(assignment.doc(), Some("Squares a number.\n  Indented."))
+            }
+            expression => panic!("Expected an assignment, found {expression:?}."),
+        }
+
+        assert_eq!(expressions.tail().len(), 1);
+    }
+
+    #[test]
+    fn parse_procedure_without_parameters() {
+        assert!("@p! = 1".parse::<Program>().is_err());
+    }
+
     #[test]
     fn parse_bad() {
         assert!(include_str!("../../../examples/bad.ta")
@@ -46,6 +46,7 @@ impl<const N: usize> TokenMatcher for &[Kind; N] {
 }
 
 /// An iterator sequence of tokens obtained through Lexical Analysis.
+/// Documentation comments are skipped, except by [`Tokens::doc_comment`].
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Tokens<'a> {
     offset: usize,
@@ -75,6 +76,22 @@ impl<'a> Tokens<'a> {
         }
     }
 
+    /// The index of the next token in the sequence.
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Moves the offset to the token with the given index.
+    pub fn seek(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    /// Moves the offset back to the first token and forgets any backtracking points.
+    pub fn rewind(&mut self) {
+        self.offset = 0;
+        self.marker.clear();
+    }
+
     /// Marks the current offset as a backtracking point.
     /// Backtracking is only possible when an offset has been marked.
     pub fn mark(&mut self) {
@@ -90,6 +107,10 @@ impl<'a> Tokens<'a> {
     }
 
     fn next(&mut self) -> Option<Token<'a>> {
+        while let Some(Kind::DocComment) = self.tokens.get(self.offset).map(Token::kind) {
+            self.offset += 1;
+        }
+
         let token = self.tokens.get(self.offset);
 
         if token.is_some() {
@@ -101,7 +122,24 @@ impl<'a> Tokens<'a> {
 
     /// Peeks at the next [`Token`] in the sequence without advancing.
     pub fn peek(&self) -> Option<&Token<'a>> {
-        self.tokens.get(self.offset)
+        self.tokens
+            .get(self.offset..)?
+            .iter()
+            .find(|token| token.kind() != &Kind::DocComment)
+    }
+
+    /// The text of the documentation comments immediately before the next [`Token`], one line per comment.
+    /// Returns [`None`] when the next token is not documented.
+    pub fn doc_comment(&self) -> Option<String> {
+        let lines: Vec<&str> = self
+            .tokens
+            .get(self.offset..)?
+            .iter()
+            .take_while(|token| token.kind() == &Kind::DocComment)
+            .map(|token| doc_text(token.as_str()))
+            .collect();
+
+        (!lines.is_empty()).then(|| lines.join("\n"))
     }
 
     pub fn next_token(&mut self) -> Result<Token<'a>, SyntacticalError> {
@@ -145,10 +183,17 @@ impl<'a> Tokens<'a> {
 
     /// Tests whether the `Token` stream has any more tokens without consuming any.
     pub fn has_next(&self) -> bool {
-        self.offset < self.tokens.len()
+        self.peek().is_some()
     }
 }
 
+/// The text of a documentation comment, without its leading `;;` and the single space that usually follows it.
+fn doc_text(comment: &str) -> &str {
+    let text = comment.trim_start_matches(';').trim_end();
+
+    text.strip_prefix(' ').unwrap_or(text)
+}
+
 impl<'a> From<Vec<Token<'a>>> for Tokens<'a> {
     fn from(tokens: Vec<Token<'a>>) -> Self {
         Tokens {
@@ -256,7 +301,7 @@ mod tests {
     fn next_if_match_when_empty() {
         let mut tokens = Tokens::default();
 
-        assert_eq!(tokens.next_if_match(&[Kind::Number]), None);
+        assert_eq!(tokens.next_if_match([Kind::Number]), None);
     }
 
     #[test]
@@ -264,7 +309,7 @@ mod tests {
         let mut tokens = new_tokens();
 
         assert_eq!(
-            tokens.next_if_match(&[Kind::Number]),
+            tokens.next_if_match([Kind::Number]),
             Some(Token::new("1", Kind::Number))
         );
     }
@@ -276,7 +321,7 @@ mod tests {
         tokens.peek().unwrap();
 
         assert_eq!(
-            tokens.next_if_match(&[Kind::Number]),
+            tokens.next_if_match([Kind::Number]),
             Some(Token::new("1", Kind::Number))
         );
     }
@@ -295,14 +340,14 @@ mod tests {
     fn next_unless_match_when_empty() {
         let mut tokens = Tokens::default();
 
-        assert_eq!(tokens.next_unless_match(&[Kind::Number]), None);
+        assert_eq!(tokens.next_unless_match([Kind::Number]), None);
     }
 
     #[test]
     fn next_unless_match_with_tokens() {
         let mut tokens = new_tokens();
 
-        assert_eq!(tokens.next_unless_match(&[Kind::Number]), None);
+        assert_eq!(tokens.next_unless_match([Kind::Number]), None);
     }
 
     #[test]
@@ -311,7 +356,7 @@ mod tests {
 
         tokens.peek().unwrap();
 
-        assert_eq!(tokens.next_unless_match(&[Kind::Number]), None);
+        assert_eq!(tokens.next_unless_match([Kind::Number]), None);
     }
 
     #[test]
@@ -363,6 +408,25 @@ mod tests {
         )
     }
 
+    #[test]
+    fn doc_comments() {
+        let mut tokens: Tokens = vec![
+            Token::new(";; Adds one.", Kind::DocComment),
+            Token::new(Lexeme::new(";; Adds one.\n", ";;"), Kind::DocComment),
+            Token::new(Lexeme::new(";; Adds one.\n;;\n", "1"), Kind::Number),
+        ]
+        .into();
+
+        assert_eq!(tokens.doc_comment(), Some(String::from("Adds one.\n")));
+        assert_eq!(tokens.peek_kind(), Some(Kind::Number));
+        assert_eq!(
+            tokens.next_token().map(|token| *token.kind()),
+            Ok(Kind::Number)
+        );
+        assert_eq!(tokens.doc_comment(), None);
+        assert!(!tokens.has_next());
+    }
+
     fn new_tokens() -> Tokens<'static> {
         vec![
             Token::new("1", Kind::Number),
@@ -0,0 +1,617 @@
+//! Analyzes the patterns of a function's declarations to find declarations that can never match,
+//! and numbers that none of the declarations match.
+//!
+//! Declarations are tried in order, so a declaration can never match when the declarations before it match all of its arguments.
+//! Only patterns with constant constraints are analyzed: names (e.g., `@n`), refinements (e.g., `@n < 0`) and bounds (e.g., `0 < @n <= 1`).
+//! Whether such a pattern matches only depends on where an argument lies relative to the constants,
+//! so testing one argument at and between each of the constants is enough.
+//!
+//! Tolerances are compared by their minimum and maximum, so every pair of minimum and maximum among those arguments is tested as well.
+//! Values that are not numbers only match names and `<>` refinements.
+//! Tolerances with a negative epsilon and not-a-number are not considered.
+//! Declarations are only analyzed together with the other declarations in the same scope.
+
+use crate::compiler::optimizer::constant;
+use crate::compiler::{Location, Warning, WarningKind};
+use crate::grammar::*;
+use std::collections::HashMap;
+use std::mem;
+
+/// The maximum number of combinations of arguments to test for a single function.
+const LIMIT: usize = 1 << 16;
+
+/// A representative argument.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Sample {
+    /// Any value other than a number or tolerance.
+    Other,
+    /// A tolerance with the given minimum and maximum, or a number when both are equal.
+    Range(f64, f64),
+}
+
+/// A pattern with constant constraints.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Predicate {
+    Any,
+    Refinement(Comparator, f64),
+    Bounds(f64, Inequality, Inequality, f64),
+}
+
+impl Predicate {
+    fn new(pattern: &Pattern) -> Option<Self> {
+        match pattern {
+            _ if pattern.name() == &Name::Anonymous => None,
+            Pattern::Function(function) if function.parameters().is_empty() => Some(Predicate::Any),
+            Pattern::Function(_) => None,
+            Pattern::Refinement(refinement) => Some(Predicate::Refinement(
+                *refinement.comparator(),
+                constant(refinement.constraint())?,
+            )),
+            Pattern::Bounds(bounds) => Some(Predicate::Bounds(
+                constant(bounds.left().constraint())?,
+                *bounds.left().inequality(),
+                *bounds.right().inequality(),
+                constant(bounds.right().constraint())?,
+            )),
+        }
+    }
+
+    fn constants(&self) -> Vec<f64> {
+        match self {
+            Predicate::Any => Vec::new(),
+            Predicate::Refinement(_, constant) => vec![*constant],
+            Predicate::Bounds(left, _, _, right) => vec![*left, *right],
+        }
+    }
+
+    /// Tests whether the pattern matches the given argument, following the runtime's comparison of values.
+    fn matches(&self, sample: Sample) -> bool {
+        let (min, max) = match (self, sample) {
+            (Predicate::Any, _) => return true,
+            (Predicate::Refinement(Comparator::NotEqualTo, _), Sample::Other) => return true,
+            (_, Sample::Other) => return false,
+            (_, Sample::Range(min, max)) => (min, max),
+        };
+
+        match *self {
+            Predicate::Any => true,
+            Predicate::Refinement(comparator, constant) => match comparator {
+                Comparator::LessThan => max < constant,
+                Comparator::LessThanOrEqualTo => min <= constant,
+                Comparator::GreaterThan => min > constant,
+                Comparator::GreaterThanOrEqualTo => max >= constant,
+                Comparator::EqualTo => min <= constant && constant <= max,
+                Comparator::NotEqualTo => !(min <= constant && constant <= max),
+            },
+            Predicate::Bounds(left, lower, upper, right) => {
+                let lower = match lower {
+                    Inequality::LessThan => left < min,
+                    Inequality::LessThanOrEqualTo => left <= max,
+                    Inequality::GreaterThan => left > max,
+                    Inequality::GreaterThanOrEqualTo => left >= min,
+                };
+                let upper = match upper {
+                    Inequality::LessThan => max < right,
+                    Inequality::LessThanOrEqualTo => min <= right,
+                    Inequality::GreaterThan => min > right,
+                    Inequality::GreaterThanOrEqualTo => max >= right,
+                };
+
+                lower && upper
+            }
+        }
+    }
+}
+
+/// The patterns of a single declaration, along with its number among the declarations of the function and its [`Location`].
+/// Patterns that cannot be analyzed are [`None`].
+/// Equality ignores the [`Location`] of the declaration.
+#[derive(Clone, Debug)]
+pub(crate) struct Signature(Vec<Option<Predicate>>, usize, Location);
+
+impl PartialEq for Signature {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
+}
+
+impl Signature {
+    fn is_known(&self) -> bool {
+        self.0.iter().all(Option::is_some)
+    }
+
+    /// Tests whether the declaration matches the arguments.
+    /// Patterns that cannot be analyzed are assumed to match.
+    fn may_match(&self, arguments: &[Sample]) -> bool {
+        self.0
+            .iter()
+            .zip(arguments)
+            .all(|(predicate, argument)| match predicate {
+                Some(predicate) => predicate.matches(*argument),
+                None => true,
+            })
+    }
+
+    /// Tests whether the declaration is known to match the arguments.
+    fn matches(&self, arguments: &[Sample]) -> bool {
+        self.is_known() && self.may_match(arguments)
+    }
+}
+
+/// Walks a syntax tree to analyze the declarations in each scope.
+#[derive(Debug, Default)]
+struct PatternChecker {
+    warnings: Vec<Warning>,
+}
+
+/// The analyses of the functions declared at the top-level of a program, by name and arity.
+/// An analysis is reused as long as the patterns of the function's declarations stay the same.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Analyses(HashMap<(String, usize), (Vec<Signature>, Vec<Warning>)>);
+
+/// Checks the given [`Program`] for declarations that can never match and functions that do not match every number.
+/// Returns a [`Warning`] for each one found.
+pub fn check(program: &Program) -> Vec<Warning> {
+    let expressions: Vec<&Expression> = match program {
+        Program::Expressions(expressions) => expressions.iter().collect(),
+        Program::Comparisons(comparisons) => std::iter::once(comparisons.lhs())
+            .chain(comparisons.comparisons().iter().map(Comparison::rhs))
+            .collect(),
+    };
+    let mut warnings = Analyses::default().check_top_level(expressions.iter().copied());
+
+    for expression in expressions {
+        warnings.extend(check_nested(expression));
+    }
+
+    warnings
+}
+
+/// Checks the scopes nested in the declarations of a top-level expression.
+pub(crate) fn check_nested(expression: &Expression) -> Vec<Warning> {
+    let mut checker = PatternChecker::default();
+    let mut assignments = Vec::new();
+
+    collect(expression, &mut assignments);
+    checker.check_nested(assignments.as_slice());
+    checker.warnings
+}
+
+impl Analyses {
+    /// Checks the functions declared at the top-level of a program, but not the scopes nested in their declarations.
+    pub(crate) fn check_top_level<'a, I>(&mut self, expressions: I) -> Vec<Warning>
+    where
+        I: IntoIterator<Item = &'a Expression>,
+    {
+        let mut assignments = Vec::new();
+
+        for expression in expressions {
+            collect(expression, &mut assignments);
+        }
+
+        let mut previous = mem::take(&mut self.0);
+        let mut warnings = Vec::new();
+
+        for (name, arity, signatures) in group(assignments.as_slice()) {
+            let key = (name.to_string(), arity);
+            let found = match previous.remove(&key) {
+                Some((analyzed, mut found)) if analyzed == signatures => {
+                    for warning in &mut found {
+                        relocate(warning, signatures.as_slice());
+                    }
+
+                    found
+                }
+                _ => {
+                    let mut checker = PatternChecker::default();
+
+                    checker.check_function(name, arity, signatures.as_slice());
+                    checker.warnings
+                }
+            };
+
+            warnings.extend(found.iter().cloned());
+            self.0.insert(key, (signatures, found));
+        }
+
+        warnings
+    }
+}
+
+impl PatternChecker {
+    fn check_scope<'a, I>(&mut self, expressions: I)
+    where
+        I: IntoIterator<Item = &'a Expression>,
+    {
+        let mut assignments = Vec::new();
+
+        for expression in expressions {
+            collect(expression, &mut assignments);
+        }
+
+        for (name, arity, signatures) in group(assignments.as_slice()) {
+            self.check_function(name, arity, signatures.as_slice());
+        }
+
+        self.check_nested(assignments.as_slice());
+    }
+
+    fn check_nested(&mut self, assignments: &[&Assignment]) {
+        for assignment in assignments {
+            self.check_scope(assignment.block().iter());
+        }
+    }
+
+    fn check_function(&mut self, name: &str, arity: usize, signatures: &[Signature]) {
+        let mut samples = Vec::with_capacity(arity);
+        let mut numbers = Vec::with_capacity(arity);
+
+        for index in 0..arity {
+            let mut constants: Vec<f64> = signatures
+                .iter()
+                .filter_map(|signature| signature.0[index])
+                .flat_map(|predicate| predicate.constants())
+                .collect();
+
+            constants.sort_by(f64::total_cmp);
+            constants.dedup();
+
+            let reals = representatives(constants.as_slice());
+            let mut sample = vec![Sample::Other];
+
+            for (i, min) in reals.iter().enumerate() {
+                for max in &reals[i..] {
+                    sample.push(Sample::Range(*min, *max));
+                }
+            }
+
+            numbers.push(reals.iter().map(|x| Sample::Range(*x, *x)).collect());
+            samples.push(sample);
+        }
+
+        let combinations = samples
+            .iter()
+            .try_fold(1usize, |total, sample| total.checked_mul(sample.len()));
+
+        if !matches!(combinations, Some(total) if total <= LIMIT) {
+            return;
+        }
+
+        for (index, signature) in signatures.iter().enumerate().skip(1) {
+            let earlier = &signatures[..index];
+            let reachable = find(samples.as_slice(), |arguments| {
+                signature.may_match(arguments) && !earlier.iter().any(|e| e.matches(arguments))
+            });
+
+            if reachable.is_none() {
+                self.warnings.push(Warning::located(
+                    WarningKind::UnreachableDeclaration {
+                        function: name.to_string(),
+                        declaration: signature.1,
+                    },
+                    signature.2,
+                ));
+            }
+        }
+
+        let analyzable = signatures.iter().all(Signature::is_known)
+            && signatures
+                .iter()
+                .flat_map(|signature| signature.0.iter())
+                .any(|predicate| predicate != &Some(Predicate::Any));
+
+        if !analyzable {
+            return;
+        }
+
+        let gap = find(numbers.as_slice(), |arguments| {
+            !signatures.iter().any(|s| s.matches(arguments))
+        });
+
+        if let Some(arguments) = gap {
+            self.warnings.push(Warning::located(
+                WarningKind::UncoveredNumbers {
+                    function: name.to_string(),
+                    arguments: arguments
+                        .into_iter()
+                        .map(|argument| match argument {
+                            Sample::Range(number, _) => number,
+                            Sample::Other => f64::NAN,
+                        })
+                        .collect(),
+                },
+                signatures[0].2,
+            ));
+        }
+    }
+}
+
+/// Moves a cached [`Warning`] to the current [`Location`] of the declaration it refers to.
+/// Uncovered numbers are reported on the first declaration of the function.
+fn relocate(warning: &mut Warning, signatures: &[Signature]) {
+    let signature = match warning.kind() {
+        WarningKind::UnreachableDeclaration { declaration, .. } => signatures
+            .iter()
+            .find(|signature| signature.1 == *declaration),
+        _ => signatures.first(),
+    };
+
+    if let (Some(signature), Some(location)) = (signature, warning.location_mut()) {
+        *location = signature.2;
+    }
+}
+
+/// Groups the declarations of functions in a scope by name and arity, in order of declaration.
+fn group<'a>(assignments: &[&'a Assignment]) -> Vec<(&'a str, usize, Vec<Signature>)> {
+    let mut functions: Vec<(&str, usize, Vec<Signature>)> = Vec::new();
+    let mut declarations: Vec<(&str, usize)> = Vec::new();
+
+    for assignment in assignments {
+        let function = assignment.function();
+        let (name, arity) = match function.name().as_str() {
+            Some(name) if !function.parameters().is_empty() => (name, function.parameters().len()),
+            _ => continue,
+        };
+        let number = match declarations.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, count)) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                declarations.push((name, 1));
+                1
+            }
+        };
+        let signature = Signature(
+            function.parameters().iter().map(Predicate::new).collect(),
+            number,
+            assignment.location(),
+        );
+
+        match functions
+            .iter_mut()
+            .find(|(n, a, _)| *n == name && *a == arity)
+        {
+            Some((_, _, signatures)) => signatures.push(signature),
+            None => functions.push((name, arity, vec![signature])),
+        }
+    }
+
+    functions
+}
+
+/// Collects the assignments declared in the scope of an expression.
+pub(crate) fn collect<'a>(expression: &'a Expression, assignments: &mut Vec<&'a Assignment>) {
+    match expression {
+        Expression::Assignment(assignment) => assignments.push(assignment),
+        Expression::Call(call) => {
+            collect(call.callee(), assignments);
+
+            for argument in call.arguments().iter() {
+                collect(argument, assignments);
+            }
+        }
+        Expression::Operation(operation) => {
+            collect(operation.lhs(), assignments);
+            collect(operation.rhs(), assignments);
+        }
+        Expression::Grouping(grouping) => collect(grouping.inner(), assignments),
+        Expression::Identifier(_) | Expression::Number(_) => (),
+    }
+}
+
+/// A number at each of the sorted constants, between each pair of them and beyond the first and last.
+fn representatives(constants: &[f64]) -> Vec<f64> {
+    let (first, last) = match (constants.first(), constants.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return vec![0.0],
+    };
+    let mut reals = vec![first - first.abs().max(1.0)];
+
+    for (i, constant) in constants.iter().enumerate() {
+        if i > 0 {
+            reals.push(constants[i - 1] / 2.0 + constant / 2.0);
+        }
+
+        reals.push(*constant);
+    }
+
+    reals.push(last + last.abs().max(1.0));
+    reals
+}
+
+/// Finds the first combination of arguments (one from each of the given samples) that satisfies the predicate.
+fn find<F>(samples: &[Vec<Sample>], mut predicate: F) -> Option<Vec<Sample>>
+where
+    F: FnMut(&[Sample]) -> bool,
+{
+    if samples.iter().any(Vec::is_empty) {
+        return None;
+    }
+
+    let mut indices = vec![0; samples.len()];
+
+    loop {
+        let arguments: Vec<Sample> = indices
+            .iter()
+            .zip(samples)
+            .map(|(index, sample)| sample[*index])
+            .collect();
+
+        if predicate(arguments.as_slice()) {
+            return Some(arguments);
+        }
+
+        let mut position = 0;
+
+        loop {
+            if position == indices.len() {
+                return None;
+            }
+
+            indices[position] += 1;
+
+            if indices[position] < samples[position].len() {
+                break;
+            }
+
+            indices[position] = 0;
+            position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_declarations(source: &str) -> Vec<WarningKind> {
+        check(&source.parse::<Program>().unwrap())
+            .into_iter()
+            .map(|warning| warning.kind().clone())
+            .filter(|kind| matches!(kind, WarningKind::UnreachableDeclaration { .. }))
+            .collect()
+    }
+
+    fn gaps(source: &str) -> Vec<String> {
+        check(&source.parse::<Program>().unwrap())
+            .iter()
+            .filter(|warning| matches!(warning.kind(), WarningKind::UncoveredNumbers { .. }))
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn unreachable(function: &str, declaration: usize) -> WarningKind {
+        WarningKind::UnreachableDeclaration {
+            function: function.to_string(),
+            declaration,
+        }
+    }
+
+    #[test]
+    fn shadowed_by_catch_all() {
+        let source = r###"
+            @f(@n) = 0
+            @f(@n > 0) = 1
+            @g(@x, @y) = 0
+            @g(@x = 0, @y) = 1
+            @h(@x = 0, @y) = 1
+            @h(@x, @y) = 0
+        "###;
+
+        assert_eq!(
+            unreachable_declarations(source),
+            vec![unreachable("f", 2), unreachable("g", 2)]
+        );
+    }
+
+    #[test]
+    fn shadowed_by_ranges() {
+        let source = r###"
+            @f(@n <= 0) = 0
+            @f(@n >= 0) = 1
+            @f(@n = 0 ~ 1) = 2
+            @f(@n = 5) = 3
+            @g(@n < 1) = 0
+            @g(@n > -1) = 1
+            @g(@n = 5) = 2
+            @h(0 <= @n < 10) = 0
+            @h(1 < @n <= 2) = 1
+            @h(@n < 0) = 2
+            @h(-5 < @n < -1) = 3
+        "###;
+
+        assert_eq!(
+            unreachable_declarations(source),
+            vec![unreachable("f", 4), unreachable("h", 4)]
+        );
+    }
+
+    #[test]
+    fn reachable_by_other_values() {
+        let source = r###"
+            @f(@n < 0) = 0
+            @f(@n >= 0) = 1
+            @f(@n <> 0) = 2
+            @g(@n = x) = 0
+            @g(@n) = 1
+            @k(_ > 3) = 0
+            @k(_ > 3) = 1
+        "###;
+
+        assert_eq!(unreachable_declarations(source), Vec::new());
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let source = r###"
+            @f(@n > 0) = [
+                @g(@x) = x
+                @g(@x = 1) = 1
+                g(n)
+            ]
+            @g(@x = 1) = 1
+        "###;
+
+        assert_eq!(unreachable_declarations(source), vec![unreachable("g", 2)]);
+    }
+
+    #[test]
+    fn numbered_by_declaration() {
+        let source = r###"
+            @f(@n) = 0
+            @f(@x, @y) = 1
+            @f(@n = 1) = 2
+        "###;
+
+        assert_eq!(unreachable_declarations(source), vec![unreachable("f", 3)]);
+        assert_eq!(
+            check(&source.parse::<Program>().unwrap())[0].to_string(),
+            "Declaration 3 of @f on 4:13 can never match, since the declarations before it match all of its arguments."
+        );
+    }
+
+    #[test]
+    fn unreachable_declarations_compile() {
+        let source = "@r(@n) = r(n)\n@r(@n = 0) = 0";
+        let (result, warnings) = crate::compiler::Parser::try_from(source)
+            .unwrap()
+            .parse_with_warnings();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            warnings,
+            vec![Warning::located(
+                unreachable("r", 2),
+                Location::new(2, 1, 14)
+            )]
+        );
+    }
+
+    #[test]
+    fn uncovered_numbers() {
+        let source = r###"
+            @sqrt(@n < 0) = 0
+            @sqrt(@n > 0) = 1
+            @abs(@n < 0) = 0 - n
+            @abs(@n >= 0) = n
+            @round(@n, @r < 0.5) = n
+            @round(@n, @r >= 0.5) = n + 1
+            @apply(@f(@x), @y) = f(y)
+            @id(@x) = x
+            @f(@n > 0) = [
+                @g(@x <= 1) = x
+                g(n)
+            ]
+        "###;
+
+        assert_eq!(
+            gaps(source),
+            vec![
+                "@sqrt on 2:13 does not match every number (e.g., sqrt(0)).",
+                "@f on 10:13 does not match every number (e.g., f(-1)).",
+                "@g on 11:17 does not match every number (e.g., g(2)).",
+            ]
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! Enforces that functions are free of side-effects.
+//! Only procedures (and the top-level of a program) may call other procedures,
+//! while functions and patterns may only call functions.
+//!
+//! Variables (i.e., declarations without parameters) are evaluated where they are declared,
+//! so they may call procedures only when declared inside of a procedure.
+//!
+//! The built-in functions and procedures (e.g., `spawn!`) are declared before the top-level of a program.
+
+use crate::compiler::SemanticError;
+use crate::grammar::*;
+use crate::runtime::builtin_names;
+use std::collections::HashMap;
+use std::mem;
+
+/// What a name is known to refer to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Declared {
+    Function,
+    Procedure,
+    Value,
+}
+
+impl From<&Function> for Declared {
+    fn from(function: &Function) -> Self {
+        if function.is_procedure() {
+            Declared::Procedure
+        } else if function.parameters().is_empty() {
+            Declared::Value
+        } else {
+            Declared::Function
+        }
+    }
+}
+
+/// The kind of code being checked.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Context {
+    Procedure,
+    Function(String),
+    Pattern,
+}
+
+/// The names declared at the top-level of a program, so checking can resume after any top-level expression.
+pub(crate) type Scope = HashMap<String, Declared>;
+
+/// Walks a syntax tree in order of declaration to find calls to procedures from pure code.
+#[derive(Debug, Default)]
+struct PurityChecker {
+    scopes: Vec<HashMap<String, Declared>>,
+    errors: Vec<SemanticError>,
+}
+
+/// Checks the given [`Program`] for violations of purity.
+/// Returns all of the violations found.
+pub fn check(program: &Program) -> Vec<SemanticError> {
+    let mut scope = builtins();
+    let mut errors = Vec::new();
+
+    match program {
+        Program::Expressions(expressions) => {
+            for expression in expressions.iter() {
+                errors.extend(check_top_level(&mut scope, expression));
+            }
+        }
+        Program::Comparisons(comparisons) => {
+            errors.extend(check_top_level(&mut scope, comparisons.lhs()));
+
+            for comparison in comparisons.comparisons().iter() {
+                errors.extend(check_top_level(&mut scope, comparison.rhs()));
+            }
+        }
+    }
+
+    errors
+}
+
+/// The top-level [`Scope`] before any expression, which declares the built-in functions and procedures.
+pub(crate) fn builtins() -> Scope {
+    builtin_names()
+        .map(|(name, procedure)| {
+            let declared = if procedure {
+                Declared::Procedure
+            } else {
+                Declared::Function
+            };
+
+            (name.to_string(), declared)
+        })
+        .collect()
+}
+
+/// Checks a single top-level expression, adding its declarations to the top-level [`Scope`].
+pub(crate) fn check_top_level(scope: &mut Scope, expression: &Expression) -> Vec<SemanticError> {
+    let mut checker = PurityChecker {
+        scopes: vec![mem::take(scope)],
+        errors: Vec::new(),
+    };
+
+    checker.check_expression(expression, &Context::Procedure);
+    *scope = checker.scopes.pop().unwrap_or_default();
+    checker.errors
+}
+
+impl PurityChecker {
+    fn resolve(&self, name: &str) -> Option<Declared> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+    }
+
+    /// Declares a name in the innermost scope.
+    /// Declarations in outer scopes are shadowed rather than merged, so they never conflict.
+    fn declare(&mut self, name: &Name, declared: Declared) {
+        let name = match name.as_str() {
+            Some(name) => name,
+            None => return,
+        };
+        let existing = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.get(name))
+            .copied();
+
+        match (existing, declared) {
+            (Some(Declared::Function), Declared::Procedure)
+            | (Some(Declared::Procedure), Declared::Function) => self
+                .errors
+                .push(SemanticError::ConflictingDeclaration(name.to_string())),
+            _ => (),
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), declared);
+        }
+    }
+
+    fn check_expression(&mut self, expression: &Expression, context: &Context) {
+        match expression {
+            Expression::Assignment(assignment) => self.check_assignment(assignment, context),
+            Expression::Call(call) => self.check_call(call, context),
+            Expression::Operation(operation) => {
+                self.check_expression(operation.lhs(), context);
+                self.check_expression(operation.rhs(), context);
+            }
+            Expression::Grouping(grouping) => self.check_expression(grouping.inner(), context),
+            Expression::Identifier(_) | Expression::Number(_) => (),
+        }
+    }
+
+    fn check_call(&mut self, call: &Call, context: &Context) {
+        self.check_expression(call.callee(), context);
+
+        for argument in call.arguments().iter() {
+            self.check_expression(argument, context);
+        }
+
+        let name = match call.callee() {
+            Expression::Identifier(identifier) => Some(identifier.as_str()),
+            _ => None,
+        };
+
+        match (
+            name.and_then(|name| self.resolve(name)),
+            call.is_procedure(),
+        ) {
+            (Some(Declared::Procedure), false) => self.errors.push(
+                SemanticError::MissingExclamation(name.unwrap_or_default().to_string()),
+            ),
+            (Some(Declared::Function), true) => self.errors.push(SemanticError::NotAProcedure(
+                name.unwrap_or_default().to_string(),
+            )),
+            _ => (),
+        }
+
+        if !call.is_procedure() {
+            return;
+        }
+
+        let procedure = format!("{}!", name.unwrap_or("_"));
+
+        match context {
+            Context::Procedure => (),
+            Context::Function(function) => self.errors.push(SemanticError::ImpureFunction {
+                function: function.clone(),
+                procedure,
+            }),
+            Context::Pattern => self.errors.push(SemanticError::ImpurePattern(procedure)),
+        }
+    }
+
+    fn check_assignment(&mut self, assignment: &Assignment, context: &Context) {
+        let function = assignment.function();
+        let declared = Declared::from(function);
+
+        self.declare(function.name(), declared);
+        self.scopes.push(HashMap::new());
+
+        for parameter in function.parameters() {
+            self.check_pattern(parameter);
+        }
+
+        let body = match declared {
+            Declared::Procedure => Context::Procedure,
+            Declared::Function => Context::Function(match function.name() {
+                Name::Anonymous => "_".to_string(),
+                Name::Identified(identifier) => format!("@{}", identifier.as_str()),
+            }),
+            Declared::Value => context.clone(),
+        };
+
+        for expression in assignment.block().iter() {
+            self.check_expression(expression, &body);
+        }
+
+        self.scopes.pop();
+    }
+
+    /// Declares the name bound by a parameter and checks its constraints.
+    fn check_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Function(function) => {
+                self.declare(function.name(), Declared::from(function.as_ref()));
+                self.check_constraints(function.parameters());
+            }
+            Pattern::Refinement(refinement) => {
+                self.declare(refinement.name(), Declared::Value);
+                self.check_expression(refinement.constraint(), &Context::Pattern);
+            }
+            Pattern::Bounds(bounds) => {
+                self.declare(bounds.name(), Declared::Value);
+                self.check_expression(bounds.left().constraint(), &Context::Pattern);
+                self.check_expression(bounds.right().constraint(), &Context::Pattern);
+            }
+        }
+    }
+
+    /// Checks the constraints of the parameters of a function pattern, which do not bind any names.
+    fn check_constraints(&mut self, parameters: &[Pattern]) {
+        for parameter in parameters {
+            match parameter {
+                Pattern::Function(function) => self.check_constraints(function.parameters()),
+                Pattern::Refinement(refinement) => {
+                    self.check_expression(refinement.constraint(), &Context::Pattern)
+                }
+                Pattern::Bounds(bounds) => {
+                    self.check_expression(bounds.left().constraint(), &Context::Pattern);
+                    self.check_expression(bounds.right().constraint(), &Context::Pattern);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CompilationError;
+
+    fn errors(source: &str) -> Vec<SemanticError> {
+        match source.parse::<Program>() {
+            Ok(_) => Vec::new(),
+            Err(CompilationError::Semantic(errors)) => errors,
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn procedures_call_procedures() {
+        let source = r###"
+            @log!(@x) = x
+            @twice!(@x) = log!(log!(x))
+            @square(@x) = x * x
+
+            twice!(square(2))
+        "###;
+
+        assert_eq!(errors(source), Vec::new());
+    }
+
+    #[test]
+    fn function_calls_procedure() {
+        let source = r###"
+            @log!(@x) = x
+            @f(@x) = [
+                @y = log!(x)
+                y + 1
+            ]
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::ImpureFunction {
+                function: "@f".to_string(),
+                procedure: "log!".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn nested_function_in_procedure() {
+        let source = r###"
+            @log!(@x) = x
+            @p!(@x) = [
+                @g(@y) = log!(y)
+                log!(g(x))
+            ]
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::ImpureFunction {
+                function: "@g".to_string(),
+                procedure: "log!".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn pattern_calls_procedure() {
+        let source = r###"
+            @log!(@x) = x
+            @f(@x > log!(0)) = x
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::ImpurePattern("log!".to_string())]
+        );
+    }
+
+    #[test]
+    fn procedure_parameter() {
+        let source = r###"
+            @apply!(@p!(@x), @y) = p!(y)
+            @map(@f(@x), @y) = f(y)
+            @bad(@p!(@x), @y) = p(y)
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::MissingExclamation("p".to_string())]
+        );
+    }
+
+    #[test]
+    fn calling_convention() {
+        let source = r###"
+            @log!(@x) = x
+            @f(@x) = x
+            log(1)
+            f!(1)
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![
+                SemanticError::MissingExclamation("log".to_string()),
+                SemanticError::NotAProcedure("f".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicting_declaration() {
+        let source = r###"
+            @f(@x = 0) = 0
+            @f!(@x) = x
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::ConflictingDeclaration("f".to_string())]
+        );
+    }
+
+    #[test]
+    fn shadowing_declaration() {
+        let source = r###"
+            @p!(@x) = x
+            @f(@p(@y)) = p(1)
+            @g(@x) = [
+                @p(@y) = y
+                p(x)
+            ]
+        "###;
+
+        assert_eq!(errors(source), Vec::new());
+    }
+
+    #[test]
+    fn builtin_procedures() {
+        let source = r###"
+            @double(@x) = x * 2
+            @start!(@x) = call!(spawn!(double), x)
+            @impure(@x) = send!(self!(), x)
+            @unmarked!(@x) = spawn(double)
+            @round!(@x) = round_to!(x, 2)
+            @call(@x = 0) = 0
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![
+                SemanticError::ImpureFunction {
+                    function: "@impure".to_string(),
+                    procedure: "self!".to_string()
+                },
+                SemanticError::ImpureFunction {
+                    function: "@impure".to_string(),
+                    procedure: "send!".to_string()
+                },
+                SemanticError::MissingExclamation("spawn".to_string()),
+                SemanticError::NotAProcedure("round_to".to_string()),
+                SemanticError::ConflictingDeclaration("call".to_string()),
+            ]
+        );
+    }
+}
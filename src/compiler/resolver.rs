@@ -0,0 +1,213 @@
+//! Resolves identifiers to the [`Slot`] of the variable they refer to at runtime,
+//! so evaluation never has to look up variables by name.
+//!
+//! A new scope is created for every call to a function (including the evaluation of a variable).
+//! Within a scope, slots are allocated in order of declaration:
+//! the name of the function being called, then its parameters, then the declarations in its block.
+//! Declarations that merge with an existing function in the same scope re-use its slot.
+//! Identifiers that are not declared before they are used are left unresolved.
+
+use crate::grammar::lexical::{Identifier, Slot};
+use crate::grammar::*;
+
+/// Walks a syntax tree in order of evaluation to resolve each identifier.
+#[derive(Debug, Default)]
+struct Resolver {
+    scopes: Vec<Vec<Symbol>>,
+}
+
+/// Resolves the identifiers in the given [`Program`].
+/// The globals are the names already declared in the outermost scope, in order of their slots.
+pub fn resolve<I>(program: &mut Program, globals: I)
+where
+    I: IntoIterator<Item = Symbol>,
+{
+    let mut resolver = Resolver {
+        scopes: vec![globals.into_iter().collect()],
+    };
+
+    match program {
+        Program::Expressions(expressions) => {
+            for expression in expressions.iter_mut() {
+                resolver.resolve_expression(expression);
+            }
+        }
+        Program::Comparisons(comparisons) => {
+            resolver.resolve_expression(comparisons.lhs_mut());
+
+            for comparison in comparisons.comparisons_mut().iter_mut() {
+                resolver.resolve_expression(comparison.rhs_mut());
+            }
+        }
+    }
+}
+
+impl Resolver {
+    fn lookup(&self, symbol: Symbol) -> Option<Slot> {
+        self.scopes
+            .iter()
+            .rev()
+            .enumerate()
+            .find_map(|(depth, scope)| {
+                scope
+                    .iter()
+                    .position(|declared| *declared == symbol)
+                    .map(|index| Slot::new(depth, index))
+            })
+    }
+
+    /// Declares a name in the current scope, resolving it to the existing variable with the same name (if any).
+    fn declare(&mut self, name: &mut Name) {
+        let identifier = match name.identifier_mut() {
+            Some(identifier) => identifier,
+            None => return,
+        };
+        let existing = self.lookup(identifier.symbol());
+
+        identifier.resolve(existing);
+
+        match existing {
+            Some(slot) if slot.depth() == 0 => (),
+            _ => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.push(identifier.symbol());
+                }
+            }
+        }
+    }
+
+    fn resolve_identifier(&self, identifier: &mut Identifier) {
+        identifier.resolve(self.lookup(identifier.symbol()));
+    }
+
+    fn resolve_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Assignment(assignment) => self.resolve_assignment(assignment),
+            Expression::Call(call) => {
+                self.resolve_expression(call.callee_mut());
+
+                for argument in call.arguments_mut().iter_mut() {
+                    self.resolve_expression(argument);
+                }
+            }
+            Expression::Operation(operation) => {
+                self.resolve_expression(&mut operation.lhs);
+                self.resolve_expression(&mut operation.rhs);
+            }
+            Expression::Grouping(grouping) => self.resolve_expression(grouping.inner_mut()),
+            Expression::Identifier(identifier) => self.resolve_identifier(identifier),
+            Expression::Number(_) => (),
+        }
+    }
+
+    fn resolve_assignment(&mut self, assignment: &mut Assignment) {
+        let name = assignment.function().name().clone();
+
+        self.scopes.push(match &name {
+            Name::Anonymous => Vec::new(),
+            Name::Identified(identifier) => vec![identifier.symbol()],
+        });
+
+        for parameter in assignment.function_mut().parameters_mut() {
+            self.resolve_pattern(parameter);
+        }
+
+        for expression in assignment.block_mut().iter_mut() {
+            self.resolve_expression(expression);
+        }
+
+        self.scopes.pop();
+        self.declare(assignment.function_mut().name_mut());
+    }
+
+    /// Declares the name bound by a parameter and resolves its constraints.
+    /// The parameters of a function pattern do not bind any names and their constraints are never evaluated.
+    fn resolve_pattern(&mut self, pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Function(function) => self.declare(function.name_mut()),
+            Pattern::Refinement(refinement) => {
+                self.declare(refinement.name_mut());
+                self.resolve_expression(refinement.constraint_mut());
+            }
+            Pattern::Bounds(bounds) => {
+                self.declare(bounds.name_mut());
+                self.resolve_expression(bounds.left_mut().constraint_mut());
+                self.resolve_expression(bounds.right_mut().constraint_mut());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(expression: &Expression) -> Option<Slot> {
+        match expression {
+            Expression::Identifier(identifier) => identifier.slot(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn resolve_scopes() {
+        let mut program: Program = r###"
+            @x = 1
+            @f(@y) = [
+                @z = 2
+                x + y + z + f
+            ]
+            w
+        "###
+        .parse()
+        .unwrap();
+
+        resolve(&mut program, [Symbol::intern("w")]);
+
+        let expressions: Vec<&Expression> = match &program {
+            Program::Expressions(expressions) => expressions.iter().collect(),
+            _ => panic!("Expected expressions."),
+        };
+
+        assert_eq!(slot(expressions[2]), Some(Slot::new(0, 0)));
+
+        let block = match expressions[1] {
+            Expression::Assignment(assignment) => assignment.block(),
+            _ => panic!("Expected an assignment."),
+        };
+        let mut slots = Vec::new();
+        let mut expression = block.tail()[0].clone();
+
+        while let Expression::Operation(operation) = expression {
+            slots.push(slot(operation.rhs()));
+            expression = operation.lhs().clone();
+        }
+
+        slots.push(slot(&expression));
+
+        assert_eq!(
+            slots,
+            vec![
+                Some(Slot::new(0, 0)),
+                Some(Slot::new(0, 2)),
+                Some(Slot::new(0, 1)),
+                Some(Slot::new(1, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unresolved() {
+        let mut program: Program = "f(1)\n@f(@x) = x".parse().unwrap();
+
+        resolve(&mut program, []);
+
+        match &program {
+            Program::Expressions(expressions) => match expressions.head() {
+                Expression::Call(call) => assert_eq!(slot(call.callee()), None),
+                _ => panic!("Expected a call."),
+            },
+            _ => panic!("Expected expressions."),
+        }
+    }
+}
@@ -1,9 +1,10 @@
 //! Performs lexical analysis on Tortuga input and produces a sequence of `Token`s.
 
 use crate::compiler::errors::lexical::ErrorKind;
-use crate::compiler::number::{DECIMAL, MAX_RADIX, NUMBER_REGEX};
+use crate::compiler::number::{DECIMAL, DIGIT_SEPARATOR, MAX_RADIX};
 use crate::compiler::unicode::UnicodeProperties;
 use crate::compiler::{Input, Kind, LexicalError, Token};
+use crate::runtime::Number;
 use std::str::Chars;
 
 type LexicalResult<'a> = Result<Token<'a>, LexicalError>;
@@ -49,6 +50,7 @@ impl<'a> Iterator for Scanner<'a> {
                 '@' => self.new_token(Kind::At),
                 '!' => self.new_token(Kind::Exclamation),
                 '|' => self.new_token(Kind::VerticalPipe),
+                ';' if self.input.next_if_eq(';').is_some() => self.scan_doc_comment(),
                 ';' => {
                     self.skip_comment();
                     continue;
@@ -83,6 +85,11 @@ impl<'a> Scanner<'a> {
         while self.input.next_unless_eq('\n').is_some() {}
     }
 
+    fn scan_doc_comment(&mut self) -> LexicalResult<'a> {
+        self.skip_comment();
+        self.new_token(Kind::DocComment)
+    }
+
     fn scan_less_than(&mut self) -> LexicalResult<'a> {
         let kind = if self.input.next_if_eq('=').is_some() {
             Kind::LessThanOrEqualTo
@@ -107,14 +114,9 @@ impl<'a> Scanner<'a> {
 
     fn scan_fractional_number(&mut self) -> LexicalResult<'a> {
         self.scan_digits(DECIMAL);
+        self.scan_exponent(DECIMAL);
 
-        let number = self.lexeme();
-
-        if NUMBER_REGEX.is_match(number) {
-            self.new_token(Kind::Number)
-        } else {
-            self.new_error(ErrorKind::Number)
-        }
+        self.new_number()
     }
 
     fn scan_number(&mut self) -> LexicalResult<'a> {
@@ -131,17 +133,38 @@ impl<'a> Scanner<'a> {
             self.scan_digits(base);
         }
 
-        let number = self.lexeme();
+        self.scan_exponent(base);
 
-        if NUMBER_REGEX.is_match(number) {
-            self.new_token(Kind::Number)
-        } else {
-            self.new_error(ErrorKind::Number)
+        self.new_number()
+    }
+
+    fn new_number(&mut self) -> LexicalResult<'a> {
+        match self.lexeme().parse::<Number>() {
+            Ok(_) => self.new_token(Kind::Number),
+            Err(error) => self.new_error(ErrorKind::Number(*error.kind())),
         }
     }
 
     fn scan_digits(&mut self, radix: u32) {
-        while self.input.next_digit(radix).is_some() {}
+        while self.input.next_digit(radix).is_some() || self.scan_separator(radix) {}
+    }
+
+    /// Digits may be separated into groups by an underscore, but only when another digit follows it.
+    /// Otherwise, the underscore is a token of its own.
+    fn scan_separator(&mut self, radix: u32) -> bool {
+        let digit_follows = matches!(self.input.peek_second(), Some(c) if c.is_digit(radix));
+
+        digit_follows && self.input.next_if_eq(DIGIT_SEPARATOR).is_some()
+    }
+
+    /// The exponent marker (`*^`) is never valid syntax between two tokens, so it unambiguously continues a number.
+    fn scan_exponent(&mut self, radix: u32) {
+        if self.input.peek() == Some('*') && self.input.peek_second() == Some('^') {
+            self.input.next();
+            self.input.next();
+            self.input.next_if_eq('-');
+            self.scan_digits(radix);
+        }
     }
 
     fn scan_identifier(&mut self) -> LexicalResult<'a> {
@@ -168,7 +191,7 @@ impl<'a> Scanner<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compiler::{Lexeme, Location};
+    use crate::compiler::{Lexeme, Location, NumberErrorKind};
 
     fn validate(kind: Kind) {
         let input = kind.to_string();
@@ -299,18 +322,24 @@ mod tests {
         validate_number("30#0.5");
         validate_number("36#10000.5002");
         validate_number("32#7.002");
-        validate_number("37#1.0");
-        validate_number("2#4.0");
+
+        validate_number("6.02214076*^23");
+        validate_number("1*^-9");
+        validate_number(".5*^0");
+        validate_number("16#F.8*^A");
+        validate_number("1_000_000");
+        validate_number("2#1010_0101.1_1");
+        validate_number("1_0.2_5*^1_0");
     }
 
-    fn invalidate_number(number: &str) {
+    fn invalidate_number(number: &str, kind: NumberErrorKind) {
         let mut scanner: Scanner<'_> = number.into();
 
         assert_eq!(
             scanner.next(),
             Some(Err(LexicalError::new(
                 Lexeme::new(Location::default(), number),
-                ErrorKind::Number
+                ErrorKind::Number(kind)
             )))
         );
         assert_eq!(scanner.next(), None);
@@ -318,13 +347,60 @@ mod tests {
 
     #[test]
     fn scan_invalid_number() {
-        invalidate_number(".");
-        invalidate_number("20#.");
-        invalidate_number("008#1.0");
-        invalidate_number("0008");
-        invalidate_number(".100");
-        invalidate_number("2#.100");
-        invalidate_number("300#1");
+        invalidate_number(".", NumberErrorKind::EmptyFraction);
+        invalidate_number("20#.", NumberErrorKind::EmptyFraction);
+        invalidate_number("008#1.0", NumberErrorKind::LeadingZeros);
+        invalidate_number("0008", NumberErrorKind::LeadingZeros);
+        invalidate_number(".100", NumberErrorKind::TrailingZeros);
+        invalidate_number("2#.100", NumberErrorKind::TrailingZeros);
+        invalidate_number("300#1", NumberErrorKind::UnsupportedRadix(300));
+        invalidate_number("37#1.0", NumberErrorKind::UnsupportedRadix(37));
+        invalidate_number(
+            "2#4.0",
+            NumberErrorKind::InvalidDigit {
+                digit: '4',
+                radix: 2,
+            },
+        );
+        invalidate_number("1*^", NumberErrorKind::MissingDigits);
+        invalidate_number("1*^-", NumberErrorKind::MissingDigits);
+        invalidate_number("1*^01", NumberErrorKind::LeadingZeros);
+        invalidate_number("1_6#1", NumberErrorKind::MisplacedSeparator);
+    }
+
+    #[test]
+    fn invalid_number_suggestion() {
+        let error = Scanner::from("0008").next().unwrap().unwrap_err();
+
+        assert_eq!(error.suggestion().as_deref(), Some("8"));
+        assert_eq!(
+            error.to_string(),
+            "Encountered a NUMBER error during lexical analysis on 1:1; numbers cannot have leading zeros. Did you mean `8`?"
+        );
+    }
+
+    #[test]
+    fn number_followed_by_operator() {
+        let kinds: Vec<Kind> = Scanner::from("2*3 ^ 4*^1_x 1_ _2")
+            .map(|token| *token.unwrap().kind())
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Number,
+                Kind::Star,
+                Kind::Number,
+                Kind::Caret,
+                Kind::Number,
+                Kind::Underscore,
+                Kind::Identifier,
+                Kind::Number,
+                Kind::Underscore,
+                Kind::Underscore,
+                Kind::Number
+            ]
+        );
     }
 
     #[test]
@@ -362,7 +438,7 @@ mod tests {
             scanner.next(),
             Some(Err(LexicalError::new(
                 Lexeme::new("#", "."),
-                ErrorKind::Number
+                ErrorKind::Number(NumberErrorKind::EmptyFraction)
             )))
         );
         assert_eq!(scanner.next(), None);
@@ -375,6 +451,28 @@ mod tests {
         assert_forty_two(input);
     }
 
+    #[test]
+    fn scan_doc_comment() {
+        let input = ";; Hello, world!\n42";
+        let mut scanner: Scanner<'_> = input.into();
+
+        assert_eq!(
+            scanner.next(),
+            Some(Ok(Token::new(
+                Lexeme::new(Location::default(), ";; Hello, world!"),
+                Kind::DocComment
+            )))
+        );
+        assert_eq!(
+            scanner.next(),
+            Some(Ok(Token::new(
+                Lexeme::new(";; Hello, world!\n", "42"),
+                Kind::Number
+            )))
+        );
+        assert_eq!(scanner.next(), None);
+    }
+
     fn assert_forty_two(input: &str) {
         let mut scanner: Scanner<'_> = input.into();
 
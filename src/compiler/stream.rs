@@ -1 +1,287 @@
+//! Performs lexical analysis on Tortuga input read incrementally from a stream.
+//!
+//! Blank space ends every token except comments, which end at a new line.
+//! So the input is decoded in chunks and scanned up to the last blank space outside of a comment,
+//! keeping only the incomplete token at the end of the input in memory.
 
+use crate::compiler::errors::StreamError;
+use crate::compiler::unicode::UnicodeProperties;
+use crate::compiler::{Lexeme, Location, OwnedToken, Scanner};
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read};
+use std::str;
+
+/// The default number of bytes to read from the stream at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+type StreamResult = Result<OwnedToken, StreamError>;
+
+/// A lexical analyzer over UTF-8 encoded input from a [`Read`]er.
+/// Only the input after the last complete token is kept in memory.
+///
+/// # Examples
+/// ```rust
+/// use tortuga::compiler::StreamScanner;
+/// use tortuga::Kind;
+///
+/// let kinds: Vec<Kind> = StreamScanner::new("2 + 2".as_bytes())
+///     .map(|token| *token.unwrap().kind())
+///     .collect();
+///
+/// assert_eq!(kinds, vec![Kind::Number, Kind::Plus, Kind::Number]);
+/// ```
+#[derive(Debug)]
+pub struct StreamScanner<R: Read> {
+    reader: R,
+    chunk: Vec<u8>,
+    undecoded: Vec<u8>,
+    pending: String,
+    searched: usize,
+    comment: bool,
+    start: Location,
+    results: VecDeque<StreamResult>,
+    done: bool,
+}
+
+impl<R: Read> StreamScanner<R> {
+    /// Creates a new [`StreamScanner`] over the given [`Read`]er.
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, CHUNK_SIZE)
+    }
+
+    /// Creates a new [`StreamScanner`] that reads at most `size` bytes at a time.
+    pub fn with_chunk_size(reader: R, size: usize) -> Self {
+        StreamScanner {
+            reader,
+            chunk: vec![0; size.max(1)],
+            undecoded: Vec::new(),
+            pending: String::new(),
+            searched: 0,
+            comment: false,
+            start: Location::default(),
+            results: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Reads the next chunk of input and scans any complete tokens.
+    fn fill(&mut self) {
+        let length = match self.reader.read(self.chunk.as_mut_slice()) {
+            Ok(0) => return self.finish(),
+            Ok(length) => length,
+            Err(error) if error.kind() == ErrorKind::Interrupted => return,
+            Err(error) => {
+                self.results.push_back(Err(error.into()));
+                self.done = true;
+                return;
+            }
+        };
+
+        self.undecoded.extend_from_slice(&self.chunk[..length]);
+
+        let (valid, invalid) = match str::from_utf8(self.undecoded.as_slice()) {
+            Ok(text) => (text.len(), false),
+            // A code point split across chunks is completed by the next read.
+            Err(error) => (error.valid_up_to(), error.error_len().is_some()),
+        };
+
+        let decoded = str::from_utf8(&self.undecoded[..valid]).unwrap_or_default();
+
+        self.pending.push_str(decoded);
+        self.undecoded.drain(..valid);
+
+        if invalid {
+            self.scan(self.pending.len());
+            self.results
+                .push_back(Err(StreamError::Encoding(self.start)));
+            self.done = true;
+        } else {
+            let end = self.boundary();
+
+            if end > 0 {
+                self.scan(end);
+            }
+        }
+    }
+
+    /// The length of the pending input up to and including its last blank space outside of a comment.
+    /// No token continues past that blank space, so the input before it can be scanned on its own.
+    /// Only the input added since the last search is examined, since the input before it has no such blank space.
+    fn boundary(&mut self) -> usize {
+        let mut boundary = 0;
+
+        for (index, c) in self.pending[self.searched..].char_indices() {
+            let index = self.searched + index;
+
+            match c {
+                '\n' => {
+                    self.comment = false;
+                    boundary = index + 1;
+                }
+                ';' => self.comment = true,
+                c if !self.comment && c.is_pattern_white_space() => boundary = index + c.len_utf8(),
+                _ => (),
+            }
+        }
+
+        self.searched = self.pending.len();
+
+        boundary
+    }
+
+    /// Scans the remaining input at the end of the stream.
+    fn finish(&mut self) {
+        self.scan(self.pending.len());
+
+        if !self.undecoded.is_empty() {
+            self.results
+                .push_back(Err(StreamError::Encoding(self.start)));
+        }
+
+        self.done = true;
+    }
+
+    /// Scans the first `end` bytes of the pending input.
+    fn scan(&mut self, end: usize) {
+        let excerpt: String = self.pending.drain(..end).collect();
+        let start = self.start;
+
+        self.searched = self.searched.saturating_sub(end);
+
+        self.results
+            .extend(Scanner::from(excerpt.as_str()).map(|result| match result {
+                Ok(token) => {
+                    let location = token.lexeme().start().relative_to(start);
+
+                    Ok(OwnedToken::new(
+                        &Lexeme::new(location, token.as_str()),
+                        *token.kind(),
+                    ))
+                }
+                Err(error) => Err(error.relative_to(start).into()),
+            }));
+
+        self.start = self.start + excerpt.as_str();
+    }
+}
+
+impl<R: Read> Iterator for StreamScanner<R> {
+    type Item = StreamResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.results.pop_front() {
+                return Some(result);
+            }
+
+            if self.done {
+                return None;
+            }
+
+            self.fill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::errors::lexical::ErrorKind;
+    use crate::compiler::{Kind, LexicalError};
+
+    const SOURCE: &str = "@π = 3.14 ; ≈ pi\n@é(@x) = [\n  x ^ 2 ~ 0.1\n  π * x ]\n\né(2) <> 4 $ 7";
+
+    fn expected() -> Vec<Result<OwnedToken, LexicalError>> {
+        Scanner::from(SOURCE)
+            .map(|result| result.map(OwnedToken::from))
+            .collect()
+    }
+
+    fn scan(chunk: usize) -> Vec<Result<OwnedToken, LexicalError>> {
+        StreamScanner::with_chunk_size(SOURCE.as_bytes(), chunk)
+            .map(|result| match result {
+                Ok(token) => Ok(token),
+                Err(StreamError::Lexical(error)) => Err(error),
+                Err(error) => panic!("Unexpected error: {error}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_as_scanner() {
+        let expected = expected();
+
+        assert!(expected.iter().any(Result::is_err));
+
+        for chunk in [1, 2, 3, 5, 7, 16, CHUNK_SIZE] {
+            assert_eq!(scan(chunk), expected, "chunk size {chunk}");
+        }
+    }
+
+    #[test]
+    fn single_line() {
+        let mut scanner = StreamScanner::with_chunk_size("1 + 22 ; 3 + 4\n5".as_bytes(), 4);
+
+        assert_eq!(*scanner.next().unwrap().unwrap().kind(), Kind::Number);
+        assert_eq!(scanner.pending, "");
+        assert_eq!(*scanner.next().unwrap().unwrap().kind(), Kind::Plus);
+        assert_eq!(*scanner.next().unwrap().unwrap().kind(), Kind::Number);
+        assert_eq!(scanner.pending, ";");
+        assert_eq!(scanner.searched, 1);
+        assert!(scanner.comment);
+        assert_eq!(
+            scanner.next().unwrap().unwrap(),
+            OwnedToken::new(&Lexeme::new(Location::new(2, 1, 15), "5"), Kind::Number)
+        );
+        assert!(scanner.next().is_none());
+    }
+
+    #[test]
+    fn split_code_point() {
+        let mut scanner = StreamScanner::with_chunk_size("1\n é".as_bytes(), 4);
+
+        assert_eq!(
+            scanner.next().unwrap().unwrap(),
+            OwnedToken::new(&Lexeme::new(Location::default(), "1"), Kind::Number)
+        );
+        assert_eq!(
+            scanner.next().unwrap().unwrap(),
+            OwnedToken::new(&Lexeme::new(Location::new(2, 2, 3), "é"), Kind::Identifier)
+        );
+        assert!(scanner.next().is_none());
+    }
+
+    #[test]
+    fn invalid_encoding() {
+        let mut scanner = StreamScanner::with_chunk_size(&b"1 \xFF 2"[..], 1);
+
+        assert!(matches!(scanner.next(), Some(Ok(_))));
+        assert!(matches!(
+            scanner.next(),
+            Some(Err(StreamError::Encoding(location))) if location == Location::new(1, 3, 2)
+        ));
+        assert!(scanner.next().is_none());
+    }
+
+    #[test]
+    fn truncated_code_point() {
+        let mut scanner = StreamScanner::new(&"é".as_bytes()[..1]);
+
+        assert!(matches!(
+            scanner.next(),
+            Some(Err(StreamError::Encoding(location))) if location == Location::default()
+        ));
+        assert!(scanner.next().is_none());
+    }
+
+    #[test]
+    fn invalid_character() {
+        let mut scanner = StreamScanner::with_chunk_size("\n\n  $".as_bytes(), 2);
+
+        assert!(matches!(
+            scanner.next(),
+            Some(Err(StreamError::Lexical(error)))
+                if error == LexicalError::new(Lexeme::new(Location::new(3, 3, 4), "$"), ErrorKind::Invalid)
+        ));
+    }
+}
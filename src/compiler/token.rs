@@ -1,17 +1,22 @@
 //! Lexical [`Token`]s for the Tortuga Programming Language.
 
 use crate::compiler::{Lexeme, Location};
+use crate::grammar::lexical::Identifier;
+use crate::grammar::Symbol;
 use std::fmt::{self, Display, Formatter, Write};
 
 /// A lexical token is a pair of a [`Lexeme`] and a [`Kind`].
+/// Identifiers are normalized and interned when the token is created.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Token<'a> {
     lexeme: Lexeme<'a>,
     kind: Kind,
+    symbol: Option<Symbol>,
 }
 
 /// A lexical token is a pair of a [`Lexeme`] and a [`Kind`].
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OwnedToken {
     start: Location,
     lexeme: String,
@@ -62,20 +67,57 @@ impl Display for Token<'_> {
     }
 }
 
+/// Serializes a [`Token`] as its [`Kind`], lexeme and start [`Location`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for Token<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut token = serializer.serialize_struct("Token", 3)?;
+
+        token.serialize_field("kind", &self.kind)?;
+        token.serialize_field("lexeme", self.as_str())?;
+        token.serialize_field("start", self.lexeme.start())?;
+        token.end()
+    }
+}
+
 impl<'a> Token<'a> {
     /// Creates a new instance of a [`Token`] with the given [`Lexeme`] and [`Kind`].
     pub fn new<L: Into<Lexeme<'a>>, K: Into<Kind>>(lexeme: L, kind: K) -> Self {
+        let lexeme = lexeme.into();
+        let kind = kind.into();
+        let symbol = match kind {
+            Kind::Identifier => Some(Identifier::new(lexeme.as_str()).symbol()),
+            _ => None,
+        };
+
         Token {
-            lexeme: lexeme.into(),
-            kind: kind.into(),
+            lexeme,
+            kind,
+            symbol,
+        }
+    }
+
+    /// Converts this [`Token`] into one that owns its lexeme.
+    pub fn into_owned_token(self) -> Token<'static> {
+        Token {
+            lexeme: self.lexeme.to_owned(),
+            kind: self.kind,
+            symbol: self.symbol,
         }
     }
 
     /// The actual text this [`Token`] represents in the input.
-    pub fn lexeme(&self) -> &Lexeme {
+    pub fn lexeme(&self) -> &Lexeme<'_> {
         &self.lexeme
     }
 
+    /// Mutable version of [`Self::lexeme`].
+    pub(crate) fn lexeme_mut(&mut self) -> &mut Lexeme<'a> {
+        &mut self.lexeme
+    }
+
     /// This [`Token`]'s variant.
     pub fn kind(&self) -> &Kind {
         &self.kind
@@ -85,13 +127,21 @@ impl<'a> Token<'a> {
     pub fn as_str(&self) -> &str {
         self.lexeme.as_str()
     }
+
+    /// The interned [`Symbol`] of an identifier [`Token`], in the same normalized form as an [`Identifier`].
+    pub fn symbol(&self) -> Option<Symbol> {
+        self.symbol.clone()
+    }
 }
 
 /// The variants of the [`Token`]s and their associated attributes.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Kind {
     Number,
     Identifier,
+    /// ;; documents the following assignment.
+    DocComment,
 
     // Punctuation
     /// +
@@ -145,6 +195,7 @@ impl Display for Kind {
         match self {
             Kind::Number => f.write_str("NUMBER"),
             Kind::Identifier => f.write_str("IDENTIFIER"),
+            Kind::DocComment => f.write_str("DOC_COMMENT"),
             Kind::Plus => f.write_char('+'),
             Kind::Minus => f.write_char('-'),
             Kind::Star => f.write_char('*'),
@@ -186,5 +237,37 @@ mod tests {
 
         assert_eq!(token.lexeme(), &Lexeme::new(Location::default(), lexeme));
         assert_eq!(token.kind(), &kind);
+        assert_eq!(token.symbol(), None);
+    }
+
+    #[test]
+    fn identifier() {
+        let token = Token::new("abc", Kind::Identifier);
+
+        assert_eq!(token.symbol(), Some(Symbol::intern("abc")));
+    }
+
+    #[test]
+    fn normalized_identifier() {
+        let combining = Token::new("cafe\u{0301}", Kind::Identifier);
+        let precomposed = Token::new("caf\u{00E9}", Kind::Identifier);
+
+        assert_eq!(combining.as_str(), "cafe\u{0301}");
+        assert_eq!(combining.symbol(), precomposed.symbol());
+        assert_eq!(combining.symbol(), Some(Symbol::intern("caf\u{00E9}")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_token() {
+        let token = Token::new(
+            Lexeme::new(Location::new(2, 3, 10), "abc"),
+            Kind::Identifier,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&token).unwrap(),
+            r#"{"kind":"Identifier","lexeme":"abc","start":{"offset":10,"line":2,"column":3}}"#
+        );
     }
 }
@@ -0,0 +1,515 @@
+//! Infers the types of expressions to find operations that can never succeed, before a program runs.
+//!
+//! Inference is conservative: names whose types cannot be known ahead of time (e.g., parameters)
+//! have an [`Type::Unknown`] type and never cause an error.
+//! Any operation that still fails at runtime is reported by the interpreter instead.
+
+use crate::compiler::SemanticError;
+use crate::grammar::*;
+use crate::runtime::Value;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+
+/// The type of a value, as inferred from the syntax tree.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Type {
+    /// The type could not be inferred.
+    #[default]
+    Unknown,
+    Unit,
+    Boolean,
+    Number,
+    Tolerance,
+    /// A reference to a function (or procedure) declared with the given numbers of parameters.
+    Function {
+        arities: Vec<usize>,
+        result: Box<Type>,
+    },
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Unknown => f.write_str("Unknown"),
+            Type::Unit => f.write_str("Unit"),
+            Type::Boolean => f.write_str("Boolean"),
+            Type::Number => f.write_str("Number"),
+            Type::Tolerance => f.write_str("Tolerance"),
+            Type::Function { arities, .. } => {
+                f.write_str("Function of arity ")?;
+
+                for (index, arity) in arities.iter().enumerate() {
+                    match index {
+                        0 => (),
+                        _ if index + 1 == arities.len() => f.write_str(" or ")?,
+                        _ => f.write_str(", ")?,
+                    }
+
+                    write!(f, "{arity}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Type {
+    /// Creates the type of a function with a single declaration.
+    fn function(arity: usize, result: Type) -> Self {
+        Type::Function {
+            arities: vec![arity],
+            result: Box::new(result),
+        }
+    }
+
+    /// The type of a value that may be either of the given types.
+    fn join(self, other: Type) -> Self {
+        if self == other {
+            self
+        } else {
+            Type::Unknown
+        }
+    }
+
+    /// Whether a value of this type is a tolerance, or [`None`] if it is unknown.
+    /// Errors for types that are neither a number nor a tolerance.
+    fn as_tolerance(&self) -> Result<Option<bool>, ()> {
+        match self {
+            Type::Unknown => Ok(None),
+            Type::Number => Ok(Some(false)),
+            Type::Tolerance => Ok(Some(true)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&Value> for Type {
+    /// The type of a runtime value; functions implemented by the runtime have an unknown arity.
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Unit => Type::Unit,
+            Value::Boolean(_) => Type::Boolean,
+            Value::Number(_) => Type::Number,
+            Value::Tolerance(_) => Type::Tolerance,
+            Value::FunctionReference(reference) => match reference.function().arities() {
+                arities if arities.is_empty() => Type::Unknown,
+                arities => Type::Function {
+                    arities,
+                    result: Box::new(Type::Unknown),
+                },
+            },
+            _ => Type::Unknown,
+        }
+    }
+}
+
+/// Infers the result type of applying the operator to operands of the given types.
+fn operate(operator: Operator, lhs: &Type, rhs: &Type) -> Result<Type, ()> {
+    let (lhs, rhs) = (lhs.as_tolerance()?, rhs.as_tolerance()?);
+
+    match operator {
+        Operator::Modulo | Operator::Tolerance => {
+            if lhs == Some(true) || rhs == Some(true) {
+                Err(())
+            } else if operator == Operator::Modulo {
+                Ok(Type::Number)
+            } else {
+                Ok(Type::Tolerance)
+            }
+        }
+        Operator::Multiply | Operator::Divide | Operator::Exponent
+            if lhs == Some(true) && rhs == Some(true) =>
+        {
+            Err(())
+        }
+        _ => Ok(match (lhs, rhs) {
+            (Some(true), _) | (_, Some(true)) => Type::Tolerance,
+            (Some(false), Some(false)) => Type::Number,
+            _ => Type::Unknown,
+        }),
+    }
+}
+
+/// The types of the names declared at the top-level of a program, so checking can resume after any top-level expression.
+pub(crate) type Scope = HashMap<String, Type>;
+
+/// Walks a syntax tree in order of declaration to infer the type of every expression.
+#[derive(Debug, Default)]
+struct TypeChecker {
+    scopes: Vec<HashMap<String, Type>>,
+    errors: Vec<SemanticError>,
+}
+
+/// Checks the given [`Program`] for operations on values of the wrong type.
+/// Returns all of the mismatches found.
+pub fn check(program: &Program) -> Vec<SemanticError> {
+    let mut checker = TypeChecker {
+        scopes: vec![HashMap::new()],
+        errors: Vec::new(),
+    };
+
+    checker.check_program(program);
+    checker.errors
+}
+
+/// Checks a single top-level expression, adding its declarations to the top-level [`Scope`].
+pub(crate) fn check_top_level(scope: &mut Scope, expression: &Expression) -> Vec<SemanticError> {
+    let mut checker = TypeChecker {
+        scopes: vec![mem::take(scope)],
+        errors: Vec::new(),
+    };
+
+    checker.check_expression(expression);
+    *scope = checker.scopes.pop().unwrap_or_default();
+    checker.errors
+}
+
+/// Infers the type of the value of the given [`Program`], where the given names are already declared with the given types.
+pub fn infer<'a, I>(program: &Program, names: I) -> Type
+where
+    I: IntoIterator<Item = (&'a str, Type)>,
+{
+    let mut checker = TypeChecker {
+        scopes: vec![names
+            .into_iter()
+            .map(|(name, kind)| (name.to_string(), kind))
+            .collect()],
+        errors: Vec::new(),
+    };
+
+    checker.check_program(program)
+}
+
+impl TypeChecker {
+    fn resolve(&self, name: &str) -> Option<&Type> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn declare(&mut self, name: &Name, kind: Type) {
+        if let (Some(name), Some(scope)) = (name.as_str(), self.scopes.last_mut()) {
+            scope.insert(name.to_string(), kind);
+        }
+    }
+
+    fn check_program(&mut self, program: &Program) -> Type {
+        match program {
+            Program::Expressions(expressions) => self.check_block(expressions),
+            Program::Comparisons(comparisons) => {
+                self.check_expression(comparisons.lhs());
+
+                for comparison in comparisons.comparisons().iter() {
+                    self.check_expression(comparison.rhs());
+                }
+
+                Type::Boolean
+            }
+        }
+    }
+
+    /// The type of a block is the type of its last expression.
+    fn check_block(&mut self, block: &List<Expression>) -> Type {
+        let mut kind = Type::Unknown;
+
+        for expression in block.iter() {
+            kind = self.check_expression(expression);
+        }
+
+        kind
+    }
+
+    fn check_expression(&mut self, expression: &Expression) -> Type {
+        match expression {
+            Expression::Assignment(assignment) => self.check_assignment(assignment),
+            Expression::Call(call) => self.check_call(call),
+            Expression::Operation(operation) => self.check_operation(operation),
+            Expression::Grouping(grouping) => self.check_expression(grouping.inner()),
+            Expression::Identifier(identifier) => self
+                .resolve(identifier.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            Expression::Number(_) => Type::Number,
+        }
+    }
+
+    fn check_operation(&mut self, operation: &Operation) -> Type {
+        let lhs = self.check_expression(operation.lhs());
+        let rhs = self.check_expression(operation.rhs());
+
+        match operate(*operation.operator(), &lhs, &rhs) {
+            Ok(kind) => kind,
+            Err(()) => {
+                self.errors.push(SemanticError::MismatchedTypes {
+                    location: operation.location(),
+                    operator: *operation.operator(),
+                    lhs,
+                    rhs,
+                });
+
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_call(&mut self, call: &Call) -> Type {
+        let callee = self.check_expression(call.callee());
+
+        for argument in call.arguments().iter() {
+            self.check_expression(argument);
+        }
+
+        let location = call.location();
+        let arguments = call.arguments().len();
+
+        match callee {
+            Type::Unknown => Type::Unknown,
+            Type::Function { ref arities, .. } if !arities.contains(&arguments) => {
+                self.errors.push(SemanticError::WrongNumberOfArguments {
+                    location,
+                    function: callee,
+                    arguments,
+                });
+
+                Type::Unknown
+            }
+            Type::Function { result, .. } => *result,
+            found => {
+                self.errors
+                    .push(SemanticError::NotAFunction { location, found });
+
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_assignment(&mut self, assignment: &Assignment) -> Type {
+        let function = assignment.function();
+        let parameters = function.parameters();
+
+        if parameters.is_empty() {
+            self.scopes.push(HashMap::new());
+
+            let kind = self.check_block(assignment.block());
+
+            self.scopes.pop();
+            self.declare(function.name(), kind.clone());
+
+            return kind;
+        }
+
+        let existing = function
+            .name()
+            .as_str()
+            .and_then(|name| self.resolve(name))
+            .cloned();
+
+        // Later declarations may add arities, so recursive calls are not checked.
+        self.declare(function.name(), Type::Unknown);
+        self.scopes.push(HashMap::new());
+
+        for parameter in parameters {
+            self.check_pattern(parameter);
+        }
+
+        let result = self.check_block(assignment.block());
+
+        self.scopes.pop();
+
+        let arity = parameters.len();
+        let kind = match existing {
+            None => Type::function(arity, result),
+            Some(Type::Function {
+                mut arities,
+                result: previous,
+            }) => {
+                if !arities.contains(&arity) {
+                    arities.push(arity);
+                    arities.sort_unstable();
+                }
+
+                Type::Function {
+                    arities,
+                    result: Box::new(previous.join(result)),
+                }
+            }
+            // Values cannot be merged with functions.
+            Some(_) => Type::Unknown,
+        };
+
+        self.declare(function.name(), kind.clone());
+
+        kind
+    }
+
+    /// Declares the name bound by a parameter and checks its constraints.
+    fn check_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Function(function) => {
+                let kind = match function.parameters().len() {
+                    0 => Type::Unknown,
+                    arity => Type::function(arity, Type::Unknown),
+                };
+
+                self.declare(function.name(), kind);
+                self.check_constraints(function.parameters());
+            }
+            Pattern::Refinement(refinement) => {
+                self.check_expression(refinement.constraint());
+                self.declare(refinement.name(), Type::Unknown);
+            }
+            Pattern::Bounds(bounds) => {
+                self.check_expression(bounds.left().constraint());
+                self.check_expression(bounds.right().constraint());
+                self.declare(bounds.name(), Type::Unknown);
+            }
+        }
+    }
+
+    /// Checks the constraints of the parameters of a function pattern, which do not bind any names.
+    fn check_constraints(&mut self, parameters: &[Pattern]) {
+        for parameter in parameters {
+            match parameter {
+                Pattern::Function(function) => self.check_constraints(function.parameters()),
+                Pattern::Refinement(refinement) => {
+                    self.check_expression(refinement.constraint());
+                }
+                Pattern::Bounds(bounds) => {
+                    self.check_expression(bounds.left().constraint());
+                    self.check_expression(bounds.right().constraint());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Location;
+    use crate::CompilationError;
+
+    fn errors(source: &str) -> Vec<SemanticError> {
+        match source.parse::<Program>() {
+            Ok(_) => Vec::new(),
+            Err(CompilationError::Semantic(errors)) => errors,
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn well_typed() {
+        let source = r###"
+            @x = 2 ~ 0.1
+            @f(@a, @b) = (a^2 + b^2)^.5
+            @g(@a) = @h(@x, @y) = a + x * y
+            @m = 7 % 2
+
+            x * f(3, 4) + g(1)(3, 4) - m
+        "###;
+
+        assert_eq!(errors(source), Vec::new());
+    }
+
+    #[test]
+    fn mismatched_operands() {
+        let source = "@t = 2 ~ 0.1\n@f(@x) = x\nt * t + f";
+
+        assert_eq!(
+            errors(source),
+            vec![
+                SemanticError::MismatchedTypes {
+                    location: Location::from("@t = 2 ~ 0.1\n@f(@x) = x\nt "),
+                    operator: Operator::Multiply,
+                    lhs: Type::Tolerance,
+                    rhs: Type::Tolerance,
+                },
+                SemanticError::MismatchedTypes {
+                    location: Location::from("@t = 2 ~ 0.1\n@f(@x) = x\nt * t "),
+                    operator: Operator::Add,
+                    lhs: Type::Unknown,
+                    rhs: Type::function(1, Type::Unknown),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn tolerance_of_tolerance() {
+        let source = "(1 ~ 2) ~ 3 + (4 % (5 ~ 6))";
+
+        assert_eq!(
+            errors(source)
+                .iter()
+                .map(SemanticError::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "Operator % cannot be applied to operands of type Number and Tolerance on 1:18.",
+                "Operator ~ cannot be applied to operands of type Tolerance and Unknown on 1:9.",
+            ]
+        );
+    }
+
+    #[test]
+    fn calls() {
+        let source = r###"
+            @f(@x) = x
+            @f(@x, @y) = x + y
+            @n = 42
+
+            f(1, 2, 3) + n(1)
+        "###;
+
+        assert_eq!(
+            errors(source)
+                .iter()
+                .map(SemanticError::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "A Function of arity 1 or 2 cannot be called with 3 argument(s) on 6:14.",
+                "Only functions may be called, but found a Number on 6:27.",
+            ]
+        );
+    }
+
+    #[test]
+    fn parameters_are_unknown() {
+        let source = r###"
+            @apply(@f(@x), @y) = f(y) + y
+            @bad(@f(@x)) = f(1, 2)
+            @factorial(@n <= 1) = 1
+            @factorial(@n > 1) = n * factorial(n - 1)
+        "###;
+
+        assert_eq!(
+            errors(source),
+            vec![SemanticError::WrongNumberOfArguments {
+                location: Location::from(
+                    "\n            @apply(@f(@x), @y) = f(y) + y\n            @bad(@f(@x)) = f"
+                ),
+                function: Type::function(1, Type::Unknown),
+                arguments: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn infer_with_names() {
+        let program: Program = "@y = x + 1\ny ~ 0.1".parse().unwrap();
+
+        assert_eq!(infer(&program, []), Type::Tolerance);
+        assert_eq!(
+            infer(
+                &"f(x)".parse().unwrap(),
+                [("f", Type::function(1, Type::Number))]
+            ),
+            Type::Number
+        );
+        assert_eq!(
+            infer(&"x".parse().unwrap(), [("x", Type::from(&Value::from(2)))]),
+            Type::Number
+        );
+        assert_eq!(infer(&"1 < 2".parse().unwrap(), []), Type::Boolean);
+    }
+}
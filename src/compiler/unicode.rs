@@ -1,5 +1,6 @@
 //! Character extensions to query for Unicode properties useful for the Tortuga compiler.
 
+use unicode_security::GeneralSecurityProfile;
 use unicode_xid::UnicodeXID;
 
 /// Character extension to test for Unicode properties.
@@ -20,6 +21,12 @@ pub trait UnicodeProperties {
     ///
     /// See <https://util.unicode.org/UnicodeJsps/list-unicodeset.jsp?a=%5B%3AXID_Continue%3A%5D%0D%0A%0D%0A%0D%0A&abb=on&g=&i=>
     fn is_xid_continue(&self) -> bool;
+
+    /// Tests whether this is a Unicode character with the `Identifier_Status` of `Allowed`,
+    /// according to the General Security Profile for identifiers.
+    ///
+    /// See <https://www.unicode.org/reports/tr39/#General_Security_Profile>
+    fn is_identifier_allowed(&self) -> bool;
 }
 
 const PATTERN_WHITE_SPACE: [char; 11] = [
@@ -39,6 +46,10 @@ impl UnicodeProperties for char {
     fn is_xid_continue(&self) -> bool {
         UnicodeXID::is_xid_continue(*self)
     }
+
+    fn is_identifier_allowed(&self) -> bool {
+        GeneralSecurityProfile::identifier_allowed(*self)
+    }
 }
 
 #[cfg(test)]
@@ -63,4 +74,11 @@ mod tests {
         assert!('_'.is_xid_continue());
         assert!(!'\n'.is_xid_continue());
     }
+
+    #[test]
+    fn identifier_allowed() {
+        assert!('_'.is_identifier_allowed());
+        assert!('\u{00E9}'.is_identifier_allowed());
+        assert!(!'\u{01C9}'.is_identifier_allowed());
+    }
 }
@@ -0,0 +1,408 @@
+//! Compiler warnings flag code that is valid, but likely to change meaning or to fail at runtime:
+//! uses of deprecated functionality to be removed in a future major version,
+//! declarations that can never match and functions that do not match every number.
+//! Each warning has a stable code, so that tools can refer to it.
+//! Codes `W00xx` are for deprecated functionality, while codes `W01xx` are found by analyzing the patterns of declarations.
+//!
+//! Syntax is deprecated by adding it to [`DEPRECATED_SYNTAX`], which is checked for every token.
+//! Built-in functions and procedures are deprecated in [`builtins`](crate::runtime::builtins),
+//! and every call to them is checked unless a parameter or nested declaration shadows the built-in.
+
+use crate::compiler::{Kind, Location, Token};
+use crate::grammar::*;
+use crate::runtime::deprecated_builtins;
+use std::fmt::{self, Display, Formatter};
+
+/// Deprecated syntax: the kind of token, a test of its text and the [`Deprecation`].
+type Syntax = (Kind, fn(&str) -> bool, Deprecation);
+
+/// The deprecated syntax, checked for every token. No syntax is deprecated yet.
+const DEPRECATED_SYNTAX: [Syntax; 0] = [];
+
+/// Marks functionality (e.g., syntax or a built-in function) as deprecated.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Deprecation {
+    code: &'static str,
+    subject: &'static str,
+    since: &'static str,
+    removal: &'static str,
+    suggestion: &'static str,
+}
+
+impl Deprecation {
+    /// Creates a new [`Deprecation`] with a stable code (e.g., `W0001`) for the given subject (e.g., `The built-in function @f`).
+    /// The functionality is deprecated since one version and will be removed in another,
+    /// and the suggestion explains how to replace it.
+    pub const fn new(
+        code: &'static str,
+        subject: &'static str,
+        since: &'static str,
+        removal: &'static str,
+        suggestion: &'static str,
+    ) -> Self {
+        Deprecation {
+            code,
+            subject,
+            since,
+            removal,
+            suggestion,
+        }
+    }
+
+    /// The stable code used to refer to this [`Deprecation`].
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// The version in which the functionality was deprecated.
+    pub fn since(&self) -> &'static str {
+        self.since
+    }
+
+    /// The version in which the functionality will be removed.
+    pub fn removal(&self) -> &'static str {
+        self.removal
+    }
+}
+
+/// The reason code is flagged.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum WarningKind {
+    /// The code uses deprecated functionality.
+    Deprecated(Deprecation),
+    /// A declaration of a function can never match, since the declarations before it match all of its arguments.
+    /// Declarations are numbered from 1 in order of their declaration.
+    UnreachableDeclaration {
+        function: String,
+        declaration: usize,
+    },
+    /// None of the declarations of a function match the given numbers.
+    UncoveredNumbers {
+        function: String,
+        arguments: Vec<f64>,
+    },
+}
+
+/// Code flagged by the compiler in the source code.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Warning {
+    kind: WarningKind,
+    location: Option<Location>,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            WarningKind::Deprecated(deprecation) => {
+                write!(
+                    f,
+                    "{} is deprecated since version {} and will be removed in version {}",
+                    deprecation.subject, deprecation.since, deprecation.removal,
+                )?;
+
+                if let Some(location) = self.location {
+                    write!(f, " on {location}")?;
+                }
+
+                write!(f, ". {}", deprecation.suggestion)
+            }
+            WarningKind::UnreachableDeclaration {
+                function,
+                declaration,
+            } => {
+                write!(f, "Declaration {declaration} of @{function}")?;
+
+                if let Some(location) = self.location {
+                    write!(f, " on {location}")?;
+                }
+
+                write!(
+                    f,
+                    " can never match, since the declarations before it match all of its arguments."
+                )
+            }
+            WarningKind::UncoveredNumbers {
+                function,
+                arguments,
+            } => {
+                let arguments: Vec<String> = arguments.iter().map(f64::to_string).collect();
+
+                write!(f, "@{function}")?;
+
+                if let Some(location) = self.location {
+                    write!(f, " on {location}")?;
+                }
+
+                write!(
+                    f,
+                    " does not match every number (e.g., {function}({})).",
+                    arguments.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl Warning {
+    /// Creates a new [`Warning`] for a use of deprecated functionality at the given [`Location`].
+    pub fn new(deprecation: Deprecation, location: Location) -> Self {
+        Warning {
+            kind: WarningKind::Deprecated(deprecation),
+            location: Some(location),
+        }
+    }
+
+    /// Creates a new [`Warning`] of the given kind at the given [`Location`].
+    pub fn located(kind: WarningKind, location: Location) -> Self {
+        Warning {
+            kind,
+            location: Some(location),
+        }
+    }
+
+    /// The reason the code was flagged.
+    pub fn kind(&self) -> &WarningKind {
+        &self.kind
+    }
+
+    /// The deprecated functionality used, if any.
+    pub fn deprecation(&self) -> Option<&Deprecation> {
+        match &self.kind {
+            WarningKind::Deprecated(deprecation) => Some(deprecation),
+            _ => None,
+        }
+    }
+
+    /// The stable code used to refer to the reason the code was flagged.
+    pub fn code(&self) -> &'static str {
+        match &self.kind {
+            WarningKind::Deprecated(deprecation) => deprecation.code,
+            WarningKind::UnreachableDeclaration { .. } => "W0101",
+            WarningKind::UncoveredNumbers { .. } => "W0102",
+        }
+    }
+
+    /// The start [`Location`] in the input of the flagged code, if known.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Mutable version of [`Self::location`].
+    pub(crate) fn location_mut(&mut self) -> Option<&mut Location> {
+        self.location.as_mut()
+    }
+}
+
+/// Checks a single token for deprecated syntax.
+pub fn check_token(token: &Token<'_>) -> Option<Warning> {
+    check_syntax(token, &DEPRECATED_SYNTAX)
+}
+
+fn check_syntax(token: &Token<'_>, syntax: &[Syntax]) -> Option<Warning> {
+    syntax
+        .iter()
+        .find(|(kind, matches, _)| kind == token.kind() && matches(token.as_str()))
+        .map(|(_, _, deprecation)| Warning::new(*deprecation, *token.lexeme().start()))
+}
+
+/// Checks a [`Program`] for calls to deprecated built-in functions and procedures.
+pub(crate) fn check_calls(program: &Program) -> Vec<Warning> {
+    let mut checker = CallChecker {
+        deprecated: deprecated_builtins(),
+        scopes: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    checker.check_program(program);
+    checker.warnings
+}
+
+/// Checks a single top-level expression for calls to deprecated built-in functions and procedures.
+pub(crate) fn check_top_level_calls(expression: &Expression) -> Vec<Warning> {
+    let mut checker = CallChecker {
+        deprecated: deprecated_builtins(),
+        scopes: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    checker.check_expression(expression);
+    checker.warnings
+}
+
+/// Walks a syntax tree to find calls to deprecated built-ins that are not shadowed.
+struct CallChecker<'a> {
+    deprecated: &'a [(&'a str, Deprecation)],
+    /// The names declared by the parameters and nested declarations of each enclosing function.
+    scopes: Vec<Vec<&'a str>>,
+    warnings: Vec<Warning>,
+}
+
+impl<'a> CallChecker<'a> {
+    fn check_program(&mut self, program: &'a Program) {
+        match program {
+            Program::Expressions(expressions) => {
+                for expression in expressions.iter() {
+                    self.check_expression(expression);
+                }
+            }
+            Program::Comparisons(comparisons) => {
+                self.check_expression(comparisons.lhs());
+
+                for comparison in comparisons.comparisons().iter() {
+                    self.check_expression(comparison.rhs());
+                }
+            }
+        }
+    }
+
+    fn check_expression(&mut self, expression: &'a Expression) {
+        match expression {
+            Expression::Assignment(assignment) => self.check_assignment(assignment),
+            Expression::Call(call) => self.check_call(call),
+            Expression::Operation(operation) => {
+                self.check_expression(operation.lhs());
+                self.check_expression(operation.rhs());
+            }
+            Expression::Grouping(grouping) => self.check_expression(grouping.inner()),
+            Expression::Identifier(_) | Expression::Number(_) => (),
+        }
+    }
+
+    fn check_call(&mut self, call: &'a Call) {
+        if let Expression::Identifier(identifier) = call.callee() {
+            let name = identifier.as_str();
+            let shadowed = self.scopes.iter().any(|scope| scope.contains(&name));
+            let deprecation = self.deprecated.iter().find(|(n, _)| *n == name);
+
+            if let (false, Some((_, deprecation))) = (shadowed, deprecation) {
+                self.warnings
+                    .push(Warning::new(*deprecation, call.location()));
+            }
+        }
+
+        self.check_expression(call.callee());
+
+        for argument in call.arguments().iter() {
+            self.check_expression(argument);
+        }
+    }
+
+    fn check_assignment(&mut self, assignment: &'a Assignment) {
+        let parameters = assignment.function().parameters();
+        let mut scope: Vec<&str> = parameters
+            .iter()
+            .filter_map(|parameter| parameter.name().as_str())
+            .collect();
+
+        scope.extend(
+            assignment
+                .block()
+                .iter()
+                .filter_map(|expression| match expression {
+                    Expression::Assignment(nested) => nested.function().name().as_str(),
+                    _ => None,
+                }),
+        );
+
+        self.check_patterns(parameters);
+        self.scopes.push(scope);
+
+        for expression in assignment.block().iter() {
+            self.check_expression(expression);
+        }
+
+        self.scopes.pop();
+    }
+
+    /// Checks the constraints of the given patterns.
+    fn check_patterns(&mut self, patterns: &'a [Pattern]) {
+        for pattern in patterns {
+            match pattern {
+                Pattern::Function(function) => self.check_patterns(function.parameters()),
+                Pattern::Refinement(refinement) => self.check_expression(refinement.constraint()),
+                Pattern::Bounds(bounds) => {
+                    self.check_expression(bounds.left().constraint());
+                    self.check_expression(bounds.right().constraint());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+
+    const DEPRECATED: Deprecation = Deprecation::new(
+        "W0000",
+        "The built-in function @old",
+        "0.5.0",
+        "1.0.0",
+        "Use @new instead.",
+    );
+
+    fn calls(source: &str) -> Vec<Warning> {
+        let program = source.parse::<Program>().unwrap();
+        let mut checker = CallChecker {
+            deprecated: &[("old", DEPRECATED)],
+            scopes: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        checker.check_program(&program);
+        checker.warnings
+    }
+
+    #[test]
+    fn deprecated_builtin() {
+        let source = "@f(@x) = old(x)\nold(1) + new(1)";
+
+        assert_eq!(
+            calls(source),
+            vec![
+                Warning::new(DEPRECATED, Location::from("@f(@x) = old")),
+                Warning::new(DEPRECATED, Location::from("@f(@x) = old(x)\nold"))
+            ]
+        );
+        assert_eq!(
+            calls(source)[0].to_string(),
+            "The built-in function @old is deprecated since version 0.5.0 and will be removed in version 1.0.0 on 1:13. Use @new instead."
+        );
+    }
+
+    #[test]
+    fn shadowed_builtin() {
+        let source = r###"
+            @f(@old(@x)) = old(1)
+            @g(@x) = [
+                @old(@y) = y
+                old(x)
+            ]
+            @h(@x > old(0)) = x
+        "###;
+
+        assert_eq!(calls(source).len(), 1);
+    }
+
+    #[test]
+    fn deprecated_syntax() {
+        let syntax: [Syntax; 1] = [(Kind::Number, |number| number.ends_with('.'), DEPRECATED)];
+        let warnings: Vec<Warning> = Scanner::from("-1. 2")
+            .flatten()
+            .filter_map(|token| check_syntax(&token, &syntax))
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![Warning::new(DEPRECATED, Location::from("-"))]
+        );
+        assert_eq!(
+            check_token(&Scanner::from("1.").next().unwrap().unwrap()),
+            None
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! Pretty print Tortuga [`Program`]s and errors.
 
-use crate::compiler::{Lexeme, Token};
+use crate::compiler::{CompilationError, Lexeme, LexicalErrorKind, Lint, Token, Warning};
 use crate::grammar::*;
+use crate::testing::Outcome;
 use crate::{runtime, Kind, LexicalError, SyntacticalError};
 use colored::*;
 use std::fmt::Display;
@@ -35,7 +36,7 @@ fn print_token_to<W: Write>(token: Token<'_>, mut write: W) -> io::Result<()> {
             "@".yellow().bold(),
             start.red()
         )?,
-        Kind::Identifier => writeln!(
+        Kind::Identifier | Kind::DocComment => writeln!(
             write,
             "[{}] \"{}\" {} {}",
             kind.green().bold(),
@@ -102,9 +103,15 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
                 self.print_error_prefix("EOF")?;
                 writeln!(self.std_err, "{error}")
             }
-            SyntacticalError::Multiple => {
+            SyntacticalError::Multiple(ref errors) => {
                 self.print_error_prefix("Multiple")?;
-                writeln!(self.std_err, "{error}")
+                writeln!(self.std_err, "{error}")?;
+
+                for error in errors.iter().cloned() {
+                    self.print_syntactical_error(error)?;
+                }
+
+                Ok(())
             }
             SyntacticalError::NoMatch(token) => {
                 self.print_error_prefix("NoMatch")?;
@@ -124,6 +131,21 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
         }
     }
 
+    /// Prints a [`CompilationError`] to this [`PrettyPrinter`]'s `std_err` [`Write`]r.
+    pub fn print_compilation_error(&mut self, error: CompilationError) -> io::Result<()> {
+        match error {
+            CompilationError::Syntactical(error) => self.print_syntactical_error(error),
+            CompilationError::Semantic(errors) => {
+                for error in errors {
+                    self.print_error_prefix("Semantic")?;
+                    writeln!(self.std_err, "{error}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
     /// Prints a [`LexicalError`] to this [`PrettyPrinter`]'s `std_err` [`Write`]r.
     pub fn print_lexical_error(&mut self, error: LexicalError) -> io::Result<()> {
         let kind = error.kind();
@@ -132,13 +154,76 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
 
         self.print_error_prefix(kind)?;
 
-        writeln!(
+        write!(
             self.std_err,
             "\"{}\" {} {}",
             lexeme.blue(),
             "@".yellow().bold(),
             start.red()
-        )
+        )?;
+
+        if let LexicalErrorKind::Number(reason) = kind {
+            write!(self.std_err, " ({reason})")?;
+        }
+
+        match error.suggestion() {
+            Some(suggestion) => writeln!(self.std_err, " Did you mean `{}`?", suggestion.green()),
+            None => writeln!(self.std_err),
+        }
+    }
+
+    /// Prints a [`Warning`] to this [`PrettyPrinter`]'s `std_err` [`Write`]r.
+    pub fn print_warning(&mut self, warning: &Warning) -> io::Result<()> {
+        write!(
+            self.std_err,
+            "[{}|{}] ",
+            "WARNING".yellow().bold(),
+            warning.code().green().bold()
+        )?;
+
+        writeln!(self.std_err, "{warning}")
+    }
+
+    /// Prints a [`Lint`] to this [`PrettyPrinter`]'s `std_err` [`Write`]r.
+    pub fn print_lint(&mut self, lint: &Lint) -> io::Result<()> {
+        write!(
+            self.std_err,
+            "[{}|{}] ",
+            "LINT".cyan().bold(),
+            lint.code().green().bold()
+        )?;
+
+        writeln!(self.std_err, "{lint}")
+    }
+
+    /// Prints the [`Outcome`] of a named test assertion to this [`PrettyPrinter`]'s `std_out` [`Write`]r.
+    /// A failed assertion is followed by the comparison that does not hold, with its evaluated left and right -hand sides.
+    pub fn print_test_outcome(&mut self, name: &str, outcome: &Outcome) -> io::Result<()> {
+        write!(self.std_out, "test {name} ... ")?;
+
+        match outcome {
+            Outcome::Passed => writeln!(self.std_out, "{}", "ok".green()),
+            Outcome::Failed(lhs, comparator, rhs) => {
+                writeln!(self.std_out, "{}", "FAILED".red().bold())?;
+                writeln!(
+                    self.std_out,
+                    "    {lhs} {comparator} {rhs} does not hold (left: {}, right: {})",
+                    lhs.to_string().blue(),
+                    rhs.to_string().blue()
+                )
+            }
+            Outcome::Error(error) => {
+                writeln!(self.std_out, "{}", "ERROR".red().bold())?;
+                writeln!(self.std_out, "    {error}")
+            }
+        }
+    }
+
+    /// Prints a [`Warning`] that was denied (i.e., treated as an error) to this [`PrettyPrinter`]'s `std_err` [`Write`]r.
+    pub fn print_denied_warning(&mut self, warning: &Warning) -> io::Result<()> {
+        self.print_error_prefix(warning.code())?;
+
+        writeln!(self.std_err, "{warning}")
     }
 
     fn print_error_prefix<T: ToString>(&mut self, kind: T) -> io::Result<()> {
@@ -204,13 +289,35 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
     }
 
     fn print_assignment(&mut self, assignment: &Assignment) -> io::Result<()> {
+        for line in assignment.doc().unwrap_or_default().lines() {
+            if line.is_empty() {
+                writeln!(self.std_out, ";;")?;
+            } else {
+                writeln!(self.std_out, ";; {line}")?;
+            }
+
+            if self.nesting > 0 {
+                self.print_nesting(' ')?;
+            }
+        }
+
         self.print_function(assignment.function())?;
         write!(self.std_out, " = ")?;
         self.print_block(assignment.block())
     }
 
+    /// Prints the signature of a [`Function`] (i.e., its name and parameters) to this [`PrettyPrinter`]'s `std_out` [`Write`]r.
+    pub fn print_signature(&mut self, function: &Function) -> io::Result<()> {
+        self.print_function(function)
+    }
+
     fn print_function(&mut self, function: &Function) -> io::Result<()> {
         self.print_name(function.name())?;
+
+        if function.is_procedure() {
+            write!(self.std_out, "!")?;
+        }
+
         self.print_parameters(function.parameters())?;
 
         Ok(())
@@ -303,17 +410,7 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
     fn print_operation(&mut self, operation: &Operation) -> io::Result<()> {
         self.print_expression(operation.lhs())?;
 
-        let operator = match operation.operator() {
-            Operator::Add => "+",
-            Operator::Subtract => "-",
-            Operator::Multiply => "*",
-            Operator::Divide => "/",
-            Operator::Exponent => "^",
-            Operator::Modulo => "%",
-            Operator::Tolerance => "~",
-        };
-
-        write!(self.std_out, " {operator} ")?;
+        write!(self.std_out, " {} ", operation.operator())?;
 
         self.print_expression(operation.rhs())
     }
@@ -333,16 +430,22 @@ impl<StdOut: Write, StdErr: Write> PrettyPrinter<StdOut, StdErr> {
 
     fn print_call(&mut self, call: &Call) -> io::Result<()> {
         self.print_expression(call.callee())?;
+
+        if call.is_procedure() {
+            write!(self.std_out, "!")?;
+        }
+
         self.print_arguments(call.arguments())
     }
 
     fn print_arguments(&mut self, arguments: &Arguments) -> io::Result<()> {
         write!(self.std_out, "(")?;
 
-        self.print_expression(arguments.head())?;
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 {
+                write!(self.std_out, ", ")?;
+            }
 
-        for argument in arguments.tail() {
-            write!(self.std_out, ", ")?;
             self.print_expression(argument)?;
         }
 
@@ -0,0 +1,289 @@
+//! Generate reference documentation for Tortuga modules from their documentation comments.
+//!
+//! A module is a single source file.
+//! Its functions are the named assignments at the top-level of the module, in the order they are first declared.
+//! Each declaration of a function is listed by its signature, followed by the documentation comments (`;;`) before it.
+
+use crate::grammar::syntax::{Assignment, Expression};
+use crate::{PrettyPrinter, Program};
+use std::fmt::Write;
+use std::io;
+
+/// The documentation of a single module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Module {
+    name: String,
+    functions: Vec<Function>,
+}
+
+/// A function with all of its declarations in a module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Function {
+    name: String,
+    declarations: Vec<Declaration>,
+}
+
+/// A single pattern-matched declaration of a function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Declaration {
+    signature: String,
+    doc: Option<String>,
+}
+
+impl Module {
+    /// Collects the documentation of the functions declared at the top-level of the given [`Program`].
+    pub fn new<S: Into<String>>(name: S, program: &Program) -> Self {
+        let mut functions: Vec<Function> = Vec::new();
+
+        let expressions = match program {
+            Program::Expressions(expressions) => expressions,
+            Program::Comparisons(_) => {
+                return Module {
+                    name: name.into(),
+                    functions,
+                }
+            }
+        };
+
+        for expression in expressions.iter() {
+            let assignment = match expression {
+                Expression::Assignment(assignment) => assignment.as_ref(),
+                _ => continue,
+            };
+
+            let name = match assignment.function().name().as_str() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let declaration = Declaration {
+                signature: signature(assignment),
+                doc: assignment.doc().map(str::to_string),
+            };
+
+            match functions.iter_mut().find(|function| function.name == name) {
+                Some(function) => function.declarations.push(declaration),
+                None => functions.push(Function {
+                    name: name.to_string(),
+                    declarations: vec![declaration],
+                }),
+            }
+        }
+
+        Module {
+            name: name.into(),
+            functions,
+        }
+    }
+
+    /// The name of this [`Module`].
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Renders this [`Module`] as a Markdown page (e.g., for an mdBook).
+    pub fn to_markdown(&self) -> String {
+        let mut page = format!("# {}\n", self.name);
+
+        for function in self.functions.iter() {
+            let _ = write!(page, "\n## {}\n", function.name);
+
+            for declaration in function.declarations.iter() {
+                let _ = write!(page, "\n```\n{}\n```\n", declaration.signature);
+
+                if let Some(doc) = declaration.doc.as_deref() {
+                    let _ = write!(page, "\n{}\n", doc.trim());
+                }
+            }
+        }
+
+        page
+    }
+
+    /// Renders this [`Module`] as a static HTML page.
+    pub fn to_html(&self) -> String {
+        let name = escape(self.name.as_str());
+        let mut body = format!("<h1>{name}</h1>\n");
+
+        for function in self.functions.iter() {
+            let function_name = escape(function.name.as_str());
+
+            let _ = write!(
+                body,
+                "<section id=\"{function_name}\">\n<h2>{function_name}</h2>\n"
+            );
+
+            for declaration in function.declarations.iter() {
+                let _ = writeln!(
+                    body,
+                    "<pre><code>{}</code></pre>",
+                    escape(declaration.signature.as_str())
+                );
+
+                for paragraph in paragraphs(declaration.doc.as_deref().unwrap_or_default()) {
+                    let _ = writeln!(body, "<p>{}</p>", inline_code(paragraph.as_str()));
+                }
+            }
+
+            body.push_str("</section>\n");
+        }
+
+        html_page(name.as_str(), body.as_str())
+    }
+}
+
+/// Renders a Markdown page that links to the pages of the given modules.
+pub fn markdown_index(modules: &[Module]) -> String {
+    let mut page = String::from("# Modules\n\n");
+
+    for module in modules {
+        let _ = writeln!(page, "- [{0}]({0}.md)", module.name);
+    }
+
+    page
+}
+
+/// Renders a static HTML page that links to the pages of the given modules.
+pub fn html_index(modules: &[Module]) -> String {
+    let mut body = String::from("<h1>Modules</h1>\n<ul>\n");
+
+    for module in modules {
+        let name = escape(module.name.as_str());
+
+        let _ = writeln!(body, "<li><a href=\"{name}.html\">{name}</a></li>");
+    }
+
+    body.push_str("</ul>\n");
+
+    html_page("Modules", body.as_str())
+}
+
+/// The signature of the function declared by the given [`Assignment`], as it is written in the source.
+fn signature(assignment: &Assignment) -> String {
+    let mut signature = Vec::new();
+    let mut printer = PrettyPrinter::new(&mut signature, io::sink());
+
+    match printer.print_signature(assignment.function()) {
+        Ok(()) => String::from_utf8_lossy(signature.as_slice()).into_owned(),
+        Err(_) => assignment.function().name().to_string(),
+    }
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n{body}</body>\n</html>\n")
+}
+
+/// Splits documentation into paragraphs at blank lines.
+fn paragraphs(doc: &str) -> Vec<String> {
+    doc.split("\n\n")
+        .map(|paragraph| paragraph.trim().to_string())
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+/// Escapes text and formats the spans between backticks as code.
+fn inline_code(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(index, span)| {
+            if index % 2 == 1 {
+                format!("<code>{}</code>", escape(span))
+            } else {
+                escape(span)
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r###"
+;; Rounds a number to the nearest integer; halves round up.
+@round(@n) = round(n, n % 1)
+@round(@n, @remainder >= 0.5) = 1 + n - (n % 1)
+@round(@n, @remainder < 0.5) = n - (n % 1)
+
+;; The product of all positive integers up to and including `n`.
+@factorial(@n = 0) = 1
+;; Rounds `n` to an integer first, so non-integer inputs terminate.
+@factorial(@n > 0) = [
+    @i = round(n)
+    i * factorial(i - 1)
+]
+
+factorial(9)
+"###;
+
+    fn factorial() -> Module {
+        let program: Program = SOURCE.parse().unwrap();
+
+        Module::new("factorial", &program)
+    }
+
+    #[test]
+    fn functions_in_declaration_order() {
+        let module = factorial();
+        let names: Vec<&str> = module
+            .functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["round", "factorial"]);
+        assert_eq!(module.functions[0].declarations.len(), 3);
+        assert_eq!(
+            module.functions[1].declarations[0],
+            Declaration {
+                signature: String::from("@factorial(@n = 0)"),
+                doc: Some(String::from(
+                    "The product of all positive integers up to and including `n`."
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let markdown = factorial().to_markdown();
+
+        assert!(markdown.starts_with("# factorial\n\n## round\n\n```\n@round(@n)\n```\n"));
+        assert!(markdown.contains("```\n@round(@n, @remainder >= 0.5)\n```\n\n```"));
+        assert!(markdown.contains("## factorial\n\n```\n@factorial(@n = 0)\n```\n\nThe product"));
+    }
+
+    #[test]
+    fn html() {
+        let html = factorial().to_html();
+
+        assert!(html.contains("<pre><code>@round(@n, @remainder &gt;= 0.5)</code></pre>"));
+        assert!(html.contains(
+            "<p>The product of all positive integers up to and including <code>n</code>.</p>"
+        ));
+    }
+
+    #[test]
+    fn index() {
+        let modules = [factorial()];
+
+        assert!(markdown_index(&modules).contains("- [factorial](factorial.md)"));
+        assert!(html_index(&modules).contains("<li><a href=\"factorial.html\">factorial</a></li>"));
+    }
+}
@@ -1,23 +1,114 @@
 //! The lexical grammar rules for Tortuga.
 
+use crate::grammar::Symbol;
+use std::cmp::Ordering;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
 /// The name of a function or constant.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Identifier(String);
+/// Names are normalized to Unicode Normalization Form C (NFC),
+/// so canonically equivalent spellings (e.g., with a precomposed or a combining accent) are the same identifier.
+/// Once resolved, an identifier also knows the [`Slot`] of the variable it refers to.
+/// Resolution does not affect equality.
+#[derive(Clone, Debug)]
+pub struct Identifier {
+    symbol: Symbol,
+    slot: Option<Slot>,
+}
+
+/// The location of a variable at runtime.
+/// The depth is the number of enclosing scopes between the use of a variable and its declaration.
+/// The index is the position of the variable in the scope it was declared in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Slot {
+    depth: usize,
+    index: usize,
+}
+
+impl Slot {
+    /// Creates a new instance of a [`Slot`].
+    pub fn new(depth: usize, index: usize) -> Self {
+        Slot { depth, index }
+    }
+
+    /// The number of enclosing scopes between the use of a variable and its declaration.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The position of the variable in the scope it was declared in.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
 
 impl Identifier {
-    /// Creates a new instance of an [`Identifier`].
+    /// Creates a new instance of an [`Identifier`] with the NFC normalized form of the given lexeme.
     pub fn new(lexeme: &str) -> Self {
-        Identifier(lexeme.to_string())
+        match is_nfc_quick(lexeme.chars()) {
+            IsNormalized::Yes => Symbol::intern(lexeme).into(),
+            _ => Symbol::intern(lexeme.nfc().collect::<String>().as_str()).into(),
+        }
     }
 
     /// The [`str`] representation of this [`Identifier`].
     pub fn as_str(&self) -> &str {
-        self.0.as_str()
+        self.symbol.as_str()
+    }
+
+    /// The interned [`Symbol`] of this [`Identifier`].
+    pub fn symbol(&self) -> Symbol {
+        self.symbol.clone()
+    }
+
+    /// The [`Slot`] this [`Identifier`] was resolved to, if any.
+    /// For declarations, the slot is that of the existing variable with the same name.
+    pub fn slot(&self) -> Option<Slot> {
+        self.slot
+    }
+
+    /// Resolves this [`Identifier`] to the given [`Slot`].
+    pub(crate) fn resolve(&mut self, slot: Option<Slot>) {
+        self.slot = slot;
+    }
+}
+
+impl From<Symbol> for Identifier {
+    fn from(symbol: Symbol) -> Self {
+        Identifier { symbol, slot: None }
+    }
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl Eq for Identifier {}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.symbol.cmp(&other.symbol)
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Serializes an [`Identifier`] as its normalized name; the [`Slot`] is an implementation detail of the runtime.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Identifier {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
     }
 }
 
 /// A numerical literal.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Number(String);
 
 impl Number {
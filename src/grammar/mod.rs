@@ -1,6 +1,8 @@
 //! The Syntax Tree for the tortuga grammar.
 
 pub mod lexical;
+mod symbol;
 pub mod syntax;
 
+pub use symbol::Symbol;
 pub use syntax::*;
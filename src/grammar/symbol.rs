@@ -0,0 +1,151 @@
+//! Interned identifiers shared by the scanner, parser and runtime.
+//! Each distinct identifier is stored once while any [`Symbol`] refers to it,
+//! so identifiers can be compared and hashed as pointers.
+
+use lazy_static::lazy_static;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    static ref INTERNER: RwLock<Interner> = RwLock::new(Interner::default());
+}
+
+/// The table of all interned strings.
+/// Strings no longer referred to by any [`Symbol`] are removed whenever the table doubles in size.
+#[derive(Debug, Default)]
+struct Interner {
+    strings: HashSet<Arc<str>>,
+    capacity: usize,
+}
+
+impl Interner {
+    /// Removes the strings that only this [`Interner`] refers to.
+    /// No other [`Symbol`] can be created for them, since interning requires the table's lock.
+    fn sweep(&mut self) {
+        self.strings.retain(|string| Arc::strong_count(string) > 1);
+        self.capacity = (2 * self.strings.len()).max(64);
+    }
+}
+
+/// An interned identifier.
+/// Symbols are equal if and only if their strings are equal.
+#[derive(Clone)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    /// Gets the [`Symbol`] for the given string, interning it if necessary.
+    pub fn intern(string: &str) -> Self {
+        if let Some(symbol) = INTERNER
+            .read()
+            .expect("Symbol interner is poisoned.")
+            .strings
+            .get(string)
+        {
+            return Symbol(symbol.clone());
+        }
+
+        let mut interner = INTERNER.write().expect("Symbol interner is poisoned.");
+
+        if let Some(symbol) = interner.strings.get(string) {
+            return Symbol(symbol.clone());
+        }
+
+        if interner.strings.len() >= interner.capacity {
+            interner.sweep();
+        }
+
+        let symbol: Arc<str> = Arc::from(string);
+
+        interner.strings.insert(symbol.clone());
+
+        Symbol(symbol)
+    }
+
+    /// The string this [`Symbol`] was interned from.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).cast::<u8>().hash(state)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(string: &str) -> Self {
+        Symbol::intern(string)
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            Ordering::Equal
+        } else {
+            self.as_str().cmp(other.as_str())
+        }
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern() {
+        let a = Symbol::intern("interned");
+        let b = Symbol::intern(String::from("interned").as_str());
+
+        assert_eq!(a, b);
+        assert_ne!(a, Symbol::intern("other"));
+        assert_eq!(a.as_str(), "interned");
+        assert!(Symbol::intern("a") < Symbol::intern("b"));
+    }
+
+    #[test]
+    fn sweep() {
+        let kept = Symbol::intern("kept");
+
+        for index in 0..4096 {
+            Symbol::intern(format!("dropped{index}").as_str());
+        }
+
+        let interner = INTERNER.read().unwrap();
+
+        assert!(!interner.strings.contains("dropped0"));
+        assert!(interner.strings.contains("kept"));
+        drop(interner);
+        assert_eq!(kept, Symbol::intern("kept"));
+    }
+}
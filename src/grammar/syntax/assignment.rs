@@ -1,19 +1,66 @@
 //! Grammar rules for function declarations and pattern matching.
 
+use crate::compiler::Location;
 use crate::grammar::lexical::Identifier;
 use crate::grammar::syntax::{Comparator, Expression, List};
+use crate::grammar::Symbol;
 use std::fmt::{self, Display, Formatter, Write};
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// The declaration of a function.
+/// Equality ignores the [`Location`] of the declaration.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Assignment {
     function: Function,
     block: Block,
+    doc: Option<String>,
+    location: Location,
 }
 
+impl PartialEq for Assignment {
+    fn eq(&self, other: &Self) -> bool {
+        self.function == other.function && self.block == other.block && self.doc == other.doc
+    }
+}
+
+impl Eq for Assignment {}
+
 impl Assignment {
     /// Creates a new `assignment` grammar rule.
     pub fn new(function: Function, block: Block) -> Self {
-        Assignment { function, block }
+        Assignment {
+            function,
+            block,
+            doc: None,
+            location: Location::default(),
+        }
+    }
+
+    /// Sets the [`Location`] of the start of the declaration in the source code.
+    pub(crate) fn at(mut self, location: Location) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// The [`Location`] of the start of the declaration in the source code.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Mutable version of [`Self::location`].
+    pub(crate) fn location_mut(&mut self) -> &mut Location {
+        &mut self.location
+    }
+
+    /// Documents this `Assignment` with the text of the documentation comments before it.
+    pub fn with_doc(mut self, doc: String) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+
+    /// The text of the documentation comments before this `Assignment`, if any.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
     }
 
     /// Get the `function` defined by this `Assignment`.
@@ -25,11 +72,22 @@ impl Assignment {
     pub fn block(&self) -> &Block {
         &self.block
     }
+
+    /// Mutable version of [`Self::function`].
+    pub(crate) fn function_mut(&mut self) -> &mut Function {
+        &mut self.function
+    }
+
+    /// Mutable version of [`Self::block`].
+    pub(crate) fn block_mut(&mut self) -> &mut Block {
+        &mut self.block
+    }
 }
 
 pub type Block = List<Expression>;
 
 #[derive(Clone, Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Pattern {
     Function(Box<Function>),
     Refinement(Box<Refinement>),
@@ -39,7 +97,9 @@ pub enum Pattern {
 impl PartialEq for Pattern {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Pattern::Function(a), Pattern::Function(b)) => a.parameters == b.parameters,
+            (Pattern::Function(a), Pattern::Function(b)) => {
+                a.procedure == b.procedure && a.parameters == b.parameters
+            }
             (Pattern::Refinement(a), Pattern::Refinement(b)) => {
                 a.comparator == b.comparator && a.constraint == b.constraint
             }
@@ -78,10 +138,14 @@ impl From<Bounds> for Pattern {
     }
 }
 
+/// The signature of a function or procedure.
+/// Functions are free of side-effects, while procedures may call other procedures.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Function {
     name: Name,
     parameters: Vec<Pattern>,
+    procedure: bool,
 }
 
 pub type Parameters = Vec<Pattern>;
@@ -89,7 +153,20 @@ pub type Parameters = Vec<Pattern>;
 impl Function {
     /// Create a new instance of a `Function`.
     pub fn new(name: Name, parameters: Vec<Pattern>) -> Self {
-        Function { name, parameters }
+        Function {
+            name,
+            parameters,
+            procedure: false,
+        }
+    }
+
+    /// Create a new instance of a procedure `Function`.
+    pub fn procedure(name: Name, parameters: Vec<Pattern>) -> Self {
+        Function {
+            name,
+            parameters,
+            procedure: true,
+        }
     }
 
     /// The `Name` of this `Function`.
@@ -97,13 +174,29 @@ impl Function {
         &self.name
     }
 
+    /// Tests whether this `Function` is a procedure (i.e., may have side-effects).
+    pub fn is_procedure(&self) -> bool {
+        self.procedure
+    }
+
     /// The `Parameters` necessary to invoke this `Function`.
     pub fn parameters(&self) -> &[Pattern] {
         self.parameters.as_ref()
     }
+
+    /// Mutable version of [`Self::name`].
+    pub(crate) fn name_mut(&mut self) -> &mut Name {
+        &mut self.name
+    }
+
+    /// Mutable version of [`Self::parameters`].
+    pub(crate) fn parameters_mut(&mut self) -> &mut [Pattern] {
+        self.parameters.as_mut()
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Name {
     Anonymous,
     Identified(Identifier),
@@ -126,6 +219,22 @@ impl Name {
             Name::Identified(identifier) => Some(identifier.as_str()),
         }
     }
+
+    /// The interned [`Symbol`] of an identified [`Name`].
+    pub fn as_symbol(&self) -> Option<Symbol> {
+        match self {
+            Name::Anonymous => None,
+            Name::Identified(identifier) => Some(identifier.symbol()),
+        }
+    }
+
+    /// The [`Identifier`] of an identified [`Name`], for resolution.
+    pub(crate) fn identifier_mut(&mut self) -> Option<&mut Identifier> {
+        match self {
+            Name::Anonymous => None,
+            Name::Identified(identifier) => Some(identifier),
+        }
+    }
 }
 
 impl From<Identifier> for Name {
@@ -135,6 +244,7 @@ impl From<Identifier> for Name {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Bounds {
     left: Bound,
     name: Name,
@@ -161,10 +271,26 @@ impl Bounds {
     pub fn right(&self) -> &Bound {
         &self.right
     }
+
+    /// Mutable version of [`Self::name`].
+    pub(crate) fn name_mut(&mut self) -> &mut Name {
+        &mut self.name
+    }
+
+    /// Mutable version of [`Self::left`].
+    pub(crate) fn left_mut(&mut self) -> &mut Bound {
+        &mut self.left
+    }
+
+    /// Mutable version of [`Self::right`].
+    pub(crate) fn right_mut(&mut self) -> &mut Bound {
+        &mut self.right
+    }
 }
 
 /// The singular bound on a `range` pattern.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Bound {
     constraint: Expression,
     inequality: Inequality,
@@ -188,9 +314,15 @@ impl Bound {
     pub fn inequality(&self) -> &Inequality {
         &self.inequality
     }
+
+    /// Mutable version of [`Self::constraint`].
+    pub(crate) fn constraint_mut(&mut self) -> &mut Expression {
+        &mut self.constraint
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Inequality {
     LessThan,
     LessThanOrEqualTo,
@@ -210,6 +342,7 @@ impl fmt::Display for Inequality {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Refinement {
     name: Name,
     comparator: Comparator,
@@ -240,4 +373,14 @@ impl Refinement {
     pub fn constraint(&self) -> &Expression {
         &self.constraint
     }
+
+    /// Mutable version of [`Self::name`].
+    pub(crate) fn name_mut(&mut self) -> &mut Name {
+        &mut self.name
+    }
+
+    /// Mutable version of [`Self::constraint`].
+    pub(crate) fn constraint_mut(&mut self) -> &mut Expression {
+        &mut self.constraint
+    }
 }
@@ -3,6 +3,7 @@ use std::fmt::{self, Write};
 
 /// A pair of a comparison operator and the right-hand side expression to compare against.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Comparison(Comparator, Expression);
 
 impl Comparison {
@@ -20,9 +21,15 @@ impl Comparison {
     pub fn rhs(&self) -> &Expression {
         &self.1
     }
+
+    /// Mutable version of [`Self::rhs`].
+    pub(crate) fn rhs_mut(&mut self) -> &mut Expression {
+        &mut self.1
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Comparisons {
     lhs: Expression,
     comparison: List<Comparison>,
@@ -43,10 +50,21 @@ impl Comparisons {
     pub fn comparisons(&self) -> &List<Comparison> {
         &self.comparison
     }
+
+    /// Mutable version of [`Self::lhs`].
+    pub(crate) fn lhs_mut(&mut self) -> &mut Expression {
+        &mut self.lhs
+    }
+
+    /// Mutable version of [`Self::comparisons`].
+    pub(crate) fn comparisons_mut(&mut self) -> &mut List<Comparison> {
+        &mut self.comparison
+    }
 }
 
 /// Comparison operators.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Comparator {
     LessThan,
     LessThanOrEqualTo,
@@ -4,13 +4,17 @@
 //! Some grammars for languages do not directly encode the precedence relationships and specify that elsewhere.
 //! Here, we use a separate rule for each precedence level to make it explicit.
 
+use crate::compiler::Location;
 use crate::grammar::lexical;
 use crate::grammar::lexical::Identifier;
 use crate::grammar::syntax::{Assignment, List};
+use std::cmp::Ordering;
+use std::fmt::{self, Write};
 
 pub type Expressions = List<Expression>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expression {
     Number(Number),
     Identifier(Identifier),
@@ -56,16 +60,41 @@ impl From<Assignment> for Expression {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// A binary operation.
+/// Equality ignores the [`Location`] of the operator.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Operation {
     pub lhs: Expression,
     pub operator: Operator,
     pub rhs: Expression,
+    location: Location,
 }
 
 impl Operation {
     pub fn new(lhs: Expression, operator: Operator, rhs: Expression) -> Self {
-        Operation { lhs, operator, rhs }
+        Operation {
+            lhs,
+            operator,
+            rhs,
+            location: Location::default(),
+        }
+    }
+
+    /// Sets the [`Location`] of the operator in the source code.
+    pub(crate) fn at(mut self, location: Location) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// The [`Location`] of the operator in the source code.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Mutable version of [`Self::location`].
+    pub(crate) fn location_mut(&mut self) -> &mut Location {
+        &mut self.location
     }
 
     pub fn lhs(&self) -> &Expression {
@@ -81,7 +110,16 @@ impl Operation {
     }
 }
 
+impl PartialEq for Operation {
+    fn eq(&self, other: &Self) -> bool {
+        self.operator == other.operator && self.lhs == other.lhs && self.rhs == other.rhs
+    }
+}
+
+impl Eq for Operation {}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Operator {
     Add,
     Subtract,
@@ -92,16 +130,50 @@ pub enum Operator {
     Tolerance,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+            Operator::Exponent => '^',
+            Operator::Modulo => '%',
+            Operator::Tolerance => '~',
+        })
+    }
+}
+
+/// A numerical literal.
+/// The value of the literal may be parsed ahead of time (e.g., by an optimization pass), so it is not parsed on every evaluation.
+/// Equality and ordering ignore the parsed value.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Number {
     number: lexical::Number,
     negative: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    value: Option<f64>,
 }
 
 impl Number {
     /// Creates a new instance of a `number` grammar rule.
     pub fn new(negative: bool, number: lexical::Number) -> Self {
-        Number { number, negative }
+        Number {
+            number,
+            negative,
+            value: None,
+        }
+    }
+
+    /// The value of this `Number`, if it has been parsed ahead of time.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// Records the parsed value of this `Number` (including its sign).
+    pub(crate) fn set_value(&mut self, value: f64) {
+        self.value = Some(value);
     }
 
     /// Tests whether this `Number` represents a negative value.
@@ -115,16 +187,93 @@ impl Number {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+impl From<f64> for Number {
+    /// Creates a literal with an already parsed value.
+    /// The literal is written in decimal with the shortest representation that parses back to the same value.
+    fn from(value: f64) -> Self {
+        let number = lexical::Number::new(value.abs().to_string().as_str());
+
+        Number {
+            number,
+            negative: value.is_sign_negative(),
+            value: Some(value),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.negative == other.negative && self.number == other.number
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.number
+            .cmp(&other.number)
+            .then(self.negative.cmp(&other.negative))
+    }
+}
+
+/// A call to a function or procedure.
+/// Equality ignores the [`Location`] of the call.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Call {
     callee: Expression,
     arguments: Arguments,
+    procedure: bool,
+    location: Location,
 }
 
 impl Call {
     /// Creates a new instance of a `Call` grammar rule.
     pub fn new(callee: Expression, arguments: Arguments) -> Self {
-        Call { callee, arguments }
+        Call {
+            callee,
+            arguments,
+            procedure: false,
+            location: Location::default(),
+        }
+    }
+
+    /// Creates a new instance of a `Call` grammar rule that invokes a procedure.
+    pub fn procedure(callee: Expression, arguments: Arguments) -> Self {
+        Call {
+            callee,
+            arguments,
+            procedure: true,
+            location: Location::default(),
+        }
+    }
+
+    /// Sets the [`Location`] of the arguments in the source code.
+    pub(crate) fn at(mut self, location: Location) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// The [`Location`] of the arguments in the source code.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Mutable version of [`Self::location`].
+    pub(crate) fn location_mut(&mut self) -> &mut Location {
+        &mut self.location
+    }
+
+    /// Tests whether this [`Call`] invokes a procedure.
+    pub fn is_procedure(&self) -> bool {
+        self.procedure
     }
 
     /// The callee of the function to [`Call`].
@@ -136,11 +285,34 @@ impl Call {
     pub fn arguments(&self) -> &Arguments {
         &self.arguments
     }
+
+    /// Mutable version of [`Self::callee`].
+    pub(crate) fn callee_mut(&mut self) -> &mut Expression {
+        &mut self.callee
+    }
+
+    /// Mutable version of [`Self::arguments`].
+    pub(crate) fn arguments_mut(&mut self) -> &mut Arguments {
+        &mut self.arguments
+    }
 }
 
-pub type Arguments = List<Expression>;
+/// The arguments of a [`Call`].
+/// Only procedures may be called without arguments.
+pub type Arguments = Vec<Expression>;
+
+impl PartialEq for Call {
+    fn eq(&self, other: &Self) -> bool {
+        self.procedure == other.procedure
+            && self.callee == other.callee
+            && self.arguments == other.arguments
+    }
+}
+
+impl Eq for Call {}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Grouping(Expression);
 
 impl From<Expression> for Grouping {
@@ -154,4 +326,9 @@ impl Grouping {
     pub fn inner(&self) -> &Expression {
         &self.0
     }
+
+    /// Mutable version of [`Self::inner`].
+    pub(crate) fn inner_mut(&mut self) -> &mut Expression {
+        &mut self.0
+    }
 }
@@ -50,6 +50,11 @@ where
     pub fn iter(&self) -> impl Iterator<Item = &Type> {
         Some(&self.0).into_iter().chain(self.1.as_slice())
     }
+
+    /// Mutable version of [`Self::iter`].
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Type> {
+        Some(&mut self.0).into_iter().chain(self.1.as_mut_slice())
+    }
 }
 
 impl<Type> IntoIterator for List<Type, Type>
@@ -63,3 +68,25 @@ where
         Some(self.0).into_iter().chain(self.1)
     }
 }
+
+/// Serializes a [`List`] as a flat sequence of its head followed by its tail.
+#[cfg(feature = "serde")]
+impl<Head, Tail> serde::Serialize for List<Head, Tail>
+where
+    Head: Clone + Debug + Eq + PartialEq + serde::Serialize,
+    Tail: Clone + Debug + Eq + PartialEq + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut sequence = serializer.serialize_seq(Some(self.len()))?;
+
+        sequence.serialize_element(self.head())?;
+
+        for element in self.tail() {
+            sequence.serialize_element(element)?;
+        }
+
+        sequence.end()
+    }
+}
@@ -5,12 +5,18 @@ pub mod compiler;
 
 #[cfg(feature = "cli")]
 pub mod display;
+#[cfg(feature = "cli")]
+pub mod documentation;
 
 pub mod grammar;
+pub mod library;
 pub mod runtime;
+pub mod testing;
 
 pub use about::*;
-pub use compiler::{Kind, LexicalError, ParseNumberError, Parser, Scanner, SyntacticalError};
+pub use compiler::{
+    CompilationError, Kind, LexicalError, ParseNumberError, Parser, Scanner, SyntacticalError,
+};
 
 #[cfg(feature = "cli")]
 pub use display::PrettyPrinter;
@@ -0,0 +1,379 @@
+//! A logging process that Tortuga programs send structured log messages to.
+//! Functions cannot have side-effects, so logging is done by sending a message instead.
+//!
+//! Messages are tuples of a level, a message key and zero or more values: `(level, key, values...)`.
+//! Levels are numbered from most to least severe: `1` (error), `2` (warn), `3` (info), `4` (debug) and `5` (trace).
+//! Each message is forwarded to [`tracing`] along with the sending process and source location,
+//! and written to the configured [`Sink`].
+//!
+//! Tortuga code gets the address of the logging process with the `logger!()` procedure (e.g., `send!(logger!(), 3, 404, x)`).
+//! The process registered as [`NAME`] is the logger; when none is registered, a logger that writes to [`Sink::Stderr`] is spawned and registered.
+
+use crate::compiler::Location;
+use crate::runtime::process::procedures;
+use crate::runtime::process::{Address, Context, Process};
+use crate::runtime::Tuple;
+use crate::{RuntimeError, Value};
+use std::any::type_name;
+use std::fmt::{self, Write as _};
+#[cfg(feature = "cli")]
+use std::fs::OpenOptions;
+use std::io::{stderr, Write};
+#[cfg(feature = "cli")]
+use std::{io, path::Path};
+use tracing::Level;
+
+/// The name the logging process for Tortuga code is registered under.
+pub const NAME: &str = "logger";
+
+/// The destination log messages are written to, in addition to [`tracing`].
+pub enum Sink {
+    /// Writes human-readable lines to standard error.
+    Stderr,
+    /// Writes one JSON object per line to the given writer.
+    #[cfg(feature = "cli")]
+    JsonLines(Box<dyn Write + Send>),
+    /// Only forwards messages to [`tracing`].
+    None,
+}
+
+#[cfg(feature = "cli")]
+impl Sink {
+    /// Creates a [`Sink::JsonLines`] that appends to the file at the given path.
+    pub fn json_lines_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Sink::JsonLines(Box::new(file)))
+    }
+}
+
+impl fmt::Debug for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sink::Stderr => f.write_str("Stderr"),
+            #[cfg(feature = "cli")]
+            Sink::JsonLines(_) => f.write_str("JsonLines"),
+            Sink::None => f.write_str("None"),
+        }
+    }
+}
+
+/// A process that forwards structured log messages to [`tracing`] and a [`Sink`].
+#[derive(Debug)]
+pub struct LoggingProcess {
+    sink: Sink,
+    max_level: Level,
+}
+
+impl LoggingProcess {
+    /// Creates a new [`LoggingProcess`] that writes messages up to [`Level::INFO`] to the given [`Sink`].
+    pub fn new(sink: Sink) -> Self {
+        LoggingProcess {
+            sink,
+            max_level: Level::INFO,
+        }
+    }
+
+    /// Sets the least severe [`Level`] of messages to log.
+    pub fn with_max_level(mut self, max_level: Level) -> Self {
+        self.max_level = max_level;
+        self
+    }
+}
+
+/// A single structured log message.
+#[cfg_attr(feature = "cli", derive(serde::Serialize))]
+struct Record<'a> {
+    #[cfg_attr(feature = "cli", serde(serialize_with = "serialize_level"))]
+    level: Level,
+    process: Option<Address>,
+    location: Option<Location>,
+    key: String,
+    values: &'a [Value],
+}
+
+impl Process for LoggingProcess {
+    fn receive(&mut self, context: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        let fields = match &message {
+            Value::Tuple(tuple) if tuple.len() >= 2 => tuple.as_slice(),
+            _ => {
+                return Err(RuntimeError::UnexpectedType(
+                    message.clone(),
+                    type_name::<Tuple>().to_string(),
+                ))
+            }
+        };
+
+        let record = Record {
+            level: to_level(&fields[0])?,
+            process: context.origin().sender(),
+            location: context.origin().location(),
+            key: to_key(&fields[1]),
+            values: &fields[2..],
+        };
+
+        if record.level > self.max_level {
+            return Ok(Value::Unit);
+        }
+
+        forward(&record);
+
+        let result = match &mut self.sink {
+            Sink::Stderr => writeln!(stderr(), "{}", text_line(&record)),
+            #[cfg(feature = "cli")]
+            Sink::JsonLines(writer) => serde_json::to_writer(&mut *writer, &record)
+                .map_err(io::Error::from)
+                .and_then(|_| writeln!(writer))
+                .and_then(|_| writer.flush()),
+            Sink::None => Ok(()),
+        };
+
+        result.map_err(|error| RuntimeError::Logging(error.to_string()))?;
+
+        Ok(Value::Unit)
+    }
+}
+
+/// The built-in `logger!()` procedure.
+pub(crate) fn logger(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    if !arguments.is_empty() {
+        return None;
+    }
+
+    Some(procedures::current("@logger!").map(|current| {
+        let system = current.system();
+        let address = system.lookup(NAME).unwrap_or_else(|| {
+            let address = system.spawn(LoggingProcess::new(Sink::Stderr));
+
+            system.register(NAME, address);
+            address
+        });
+
+        Value::from(address)
+    }))
+}
+
+fn to_level(value: &Value) -> Result<Level, RuntimeError> {
+    let level = match value {
+        Value::Number(number) if number.to_f64().fract() == 0.0 => match number.to_f64() as i64 {
+            1 => Some(Level::ERROR),
+            2 => Some(Level::WARN),
+            3 => Some(Level::INFO),
+            4 => Some(Level::DEBUG),
+            5 => Some(Level::TRACE),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    level.ok_or_else(|| {
+        RuntimeError::UnexpectedType(value.clone(), type_name::<Level>().to_string())
+    })
+}
+
+fn to_key(value: &Value) -> String {
+    match value {
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes.as_bytes()) {
+            Ok(key) => key.to_string(),
+            Err(_) => bytes.to_string(),
+        },
+        value => value.to_string(),
+    }
+}
+
+fn describe(record: &Record<'_>) -> (String, String) {
+    let process = record
+        .process
+        .map(|address| address.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let location = record
+        .location
+        .map(|location| location.to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    (process, location)
+}
+
+#[cfg(feature = "cli")]
+fn serialize_level<S: serde::Serializer>(level: &Level, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(level)
+}
+
+fn forward(record: &Record<'_>) {
+    let (process, location) = describe(record);
+    let values = Tuple::from(record.values).to_string();
+    let key = record.key.as_str();
+
+    match record.level {
+        Level::ERROR => tracing::error!(%process, %location, %values, "{key}"),
+        Level::WARN => tracing::warn!(%process, %location, %values, "{key}"),
+        Level::INFO => tracing::info!(%process, %location, %values, "{key}"),
+        Level::DEBUG => tracing::debug!(%process, %location, %values, "{key}"),
+        Level::TRACE => tracing::trace!(%process, %location, %values, "{key}"),
+    }
+}
+
+fn text_line(record: &Record<'_>) -> String {
+    let (process, location) = describe(record);
+    let mut line = format!(
+        "[{}] {} @ {} {}",
+        record.level, process, location, record.key
+    );
+
+    for value in record.values {
+        write!(line, " {}", value).ok();
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::process::System;
+    #[cfg(feature = "cli")]
+    use crate::{runtime::process::Origin, Interpreter};
+    #[cfg(feature = "cli")]
+    use std::sync::{Arc, Mutex};
+
+    #[cfg(feature = "cli")]
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "cli")]
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn message(fields: Vec<Value>) -> Value {
+        Value::from(fields)
+    }
+
+    #[cfg(feature = "cli")]
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn json_lines() {
+        let buffer = Buffer::default();
+        let system = System::new(7);
+        let logger = system.spawn(LoggingProcess::new(Sink::JsonLines(Box::new(
+            buffer.clone(),
+        ))));
+        let sender = system
+            .spawn(move |context: &mut Context, message: Value| context.call(logger, message));
+
+        let message = Value::from(vec![
+            3.into(),
+            Value::from(b"request \"served\"".as_slice()),
+            1.5.into(),
+            Value::from(vec![true.into(), Value::Unit]),
+        ]);
+
+        assert_eq!(system.call(sender, message), Ok(Value::Unit));
+        assert_eq!(
+            buffer.contents(),
+            format!(
+                "{{\"level\":\"INFO\",\"process\":{{\"node\":{},\"id\":{}}},\"location\":null,\"key\":\"request \\\"served\\\"\",\"values\":[{{\"Number\":1.5}},{{\"Tuple\":[{{\"Boolean\":true}},\"Unit\"]}}]}}\n",
+                sender.node(),
+                sender.id()
+            )
+        );
+
+        system.shutdown();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn filter_by_level() {
+        let buffer = Buffer::default();
+        let system = System::default();
+        let logger = system.spawn(
+            LoggingProcess::new(Sink::JsonLines(Box::new(buffer.clone())))
+                .with_max_level(Level::WARN),
+        );
+
+        assert_eq!(
+            system.call(logger, message(vec![4.into(), 1.into()])),
+            Ok(Value::Unit)
+        );
+        assert_eq!(buffer.contents(), "");
+
+        system.shutdown();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn location() {
+        let buffer = Buffer::default();
+        let system = System::default();
+        let logger = system.spawn(LoggingProcess::new(Sink::JsonLines(Box::new(
+            buffer.clone(),
+        ))));
+
+        system
+            .call_from(
+                logger,
+                message(vec![1.into(), 7.into()]),
+                Origin::new(None, Some(Location::new(3, 5, 20))),
+            )
+            .unwrap();
+
+        assert_eq!(
+            buffer.contents(),
+            "{\"level\":\"ERROR\",\"process\":null,\"location\":{\"offset\":20,\"line\":3,\"column\":5},\"key\":\"7\",\"values\":[]}\n"
+        );
+
+        system.shutdown();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn logger_procedure() {
+        let buffer = Buffer::default();
+        let mut interpreter = Interpreter::default();
+        let system = interpreter.system().clone();
+        let logger = system.spawn(LoggingProcess::new(Sink::JsonLines(Box::new(
+            buffer.clone(),
+        ))));
+
+        system.register(NAME, logger);
+
+        assert_eq!(
+            interpreter.run("call!(logger!(), 2, 7, 1)".parse().unwrap()),
+            Ok(Value::Unit)
+        );
+        assert_eq!(
+            buffer.contents(),
+            "{\"level\":\"WARN\",\"process\":null,\"location\":{\"offset\":4,\"line\":1,\"column\":5},\"key\":\"7\",\"values\":[{\"Number\":1.0}]}\n"
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn invalid_level() {
+        let system = System::default();
+        let logger = system.spawn(LoggingProcess::new(Sink::None));
+
+        assert_eq!(
+            system.call(logger, message(vec![9.into(), 1.into()])),
+            Err(RuntimeError::UnexpectedType(
+                9.into(),
+                type_name::<Level>().to_string()
+            ))
+        );
+
+        system.shutdown();
+    }
+}
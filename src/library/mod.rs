@@ -0,0 +1,5 @@
+//! The Tortuga standard library.
+//! Services that need side-effects are provided as processes that Tortuga programs send messages to.
+
+pub mod logging;
+pub mod storage;
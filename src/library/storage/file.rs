@@ -0,0 +1,392 @@
+//! A durable storage backend backed by an append-only log file.
+//!
+//! Each change to the store is appended to the log as a single record:
+//!
+//! | Field        | Size             | Description                                    |
+//! |:-------------|:-----------------|:-----------------------------------------------|
+//! | Tag          | 1 byte           | `1` for a put, `2` for a delete.               |
+//! | Key length   | 4 bytes          | Big-endian length of the key.                  |
+//! | Block length | 4 bytes          | Big-endian length of the block.                |
+//! | Key          | Key length       | The key being changed.                         |
+//! | Block        | Block length     | The block stored under the key (empty on a delete). |
+//! | Checksum     | 4 bytes          | Big-endian CRC-32 of all the preceding fields. |
+//!
+//! A crash part-way through an append leaves an incomplete or corrupt record at the end of the log.
+//! On recovery, the log is replayed up to the last valid record and truncated there.
+//! An append that fails part-way is truncated the same way before the next append, so no acknowledged record follows it.
+
+use crate::library::storage::{Backend, Entry, MemoryBackend, StorageError};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const PUT: u8 = 1;
+const DELETE: u8 = 2;
+const HEADER_LENGTH: usize = 9;
+const CHECKSUM_LENGTH: usize = 4;
+
+/// A [`Backend`] that persists every change to an append-only log before acknowledging it.
+/// The latest block for each key is also kept in memory to serve reads.
+#[derive(Debug)]
+pub struct FileBackend {
+    path: PathBuf,
+    log: File,
+    index: MemoryBackend,
+    length: u64,
+    torn: bool,
+}
+
+impl FileBackend {
+    /// Opens (or creates) the log at the given path and replays it to recover the store.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut contents = Vec::new();
+        log.read_to_end(&mut contents)?;
+
+        let mut index = MemoryBackend::default();
+        let valid = replay(&contents, &mut index)?;
+
+        if valid < contents.len() {
+            warn!(
+                "Discarding {} bytes of incomplete records at the end of {}.",
+                contents.len() - valid,
+                path.display()
+            );
+
+            log.set_len(valid as u64)?;
+            log.sync_all()?;
+        }
+
+        Ok(FileBackend {
+            path,
+            log,
+            index,
+            length: valid as u64,
+            torn: false,
+        })
+    }
+
+    /// The path of the log file for this [`FileBackend`].
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Rewrites the log to contain a single record per key, discarding overwritten and deleted blocks.
+    /// The compacted log is written to a temporary file next to the log (e.g., `store.log.compact`),
+    /// which atomically replaces the existing log once both the file and the rename are durable.
+    pub fn compact(&mut self) -> Result<(), StorageError> {
+        let mut temporary = self.path.clone().into_os_string();
+
+        temporary.push(".compact");
+
+        let temporary = PathBuf::from(temporary);
+        let mut compacted = File::create(&temporary)?;
+        let mut length = 0;
+
+        for (key, block) in self.index.scan(Bound::Unbounded, Bound::Unbounded)? {
+            let record = encode(PUT, &key, &block)?;
+
+            compacted.write_all(&record)?;
+            length += record.len() as u64;
+        }
+
+        compacted.sync_all()?;
+        fs::rename(&temporary, &self.path)?;
+        sync_directory(&self.path)?;
+
+        self.log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        self.length = length;
+        self.torn = false;
+
+        Ok(())
+    }
+
+    /// Appends a record to the log.
+    /// On failure, the log is truncated back to its last valid record.
+    fn append(&mut self, tag: u8, key: &[u8], block: &[u8]) -> Result<(), StorageError> {
+        let record = encode(tag, key, block)?;
+
+        if self.torn {
+            self.truncate()?;
+        }
+
+        if let Err(error) = self
+            .log
+            .write_all(&record)
+            .and_then(|_| self.log.sync_data())
+        {
+            self.torn = true;
+
+            if let Err(truncation) = self.truncate() {
+                warn!(
+                    "Unable to discard a failed append to {}: {truncation}.",
+                    self.path.display()
+                );
+            }
+
+            return Err(error.into());
+        }
+
+        self.length += record.len() as u64;
+
+        Ok(())
+    }
+
+    /// Discards any bytes after the last valid record in the log.
+    fn truncate(&mut self) -> Result<(), StorageError> {
+        self.log.set_len(self.length)?;
+        self.log.sync_all()?;
+        self.torn = false;
+
+        Ok(())
+    }
+}
+
+impl Backend for FileBackend {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        self.index.get(key)
+    }
+
+    fn put(&mut self, key: &[u8], block: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        self.append(PUT, key, block)?;
+        self.index.put(key, block)
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        if self.index.get(key)?.is_none() {
+            return Ok(None);
+        }
+
+        self.append(DELETE, key, &[])?;
+        self.index.delete(key)
+    }
+
+    fn scan(&self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Result<Vec<Entry>, StorageError> {
+        self.index.scan(start, end)
+    }
+}
+
+/// Flushes the directory containing the given path, so that a rename into it survives a crash.
+#[cfg(unix)]
+fn sync_directory(path: &Path) -> Result<(), StorageError> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    File::open(directory)?.sync_all()?;
+
+    Ok(())
+}
+
+/// Directories cannot be opened as files on other platforms, where renames are flushed by the file system.
+#[cfg(not(unix))]
+fn sync_directory(_: &Path) -> Result<(), StorageError> {
+    Ok(())
+}
+
+fn encode(tag: u8, key: &[u8], block: &[u8]) -> Result<Vec<u8>, StorageError> {
+    let key_length =
+        u32::try_from(key.len()).map_err(|_| StorageError::RecordTooLarge(key.len() as u64))?;
+    let block_length =
+        u32::try_from(block.len()).map_err(|_| StorageError::RecordTooLarge(block.len() as u64))?;
+
+    let mut record = Vec::with_capacity(HEADER_LENGTH + key.len() + block.len() + CHECKSUM_LENGTH);
+
+    record.push(tag);
+    record.extend_from_slice(&key_length.to_be_bytes());
+    record.extend_from_slice(&block_length.to_be_bytes());
+    record.extend_from_slice(key);
+    record.extend_from_slice(block);
+    record.extend_from_slice(&crc32fast::hash(&record).to_be_bytes());
+
+    Ok(record)
+}
+
+/// Applies each valid record in the log to the index.
+/// Returns the length of the prefix of the log made up of valid records.
+fn replay(log: &[u8], index: &mut MemoryBackend) -> Result<usize, StorageError> {
+    let mut offset = 0;
+
+    while let Some((tag, key, block, length)) = decode(&log[offset..]) {
+        match tag {
+            PUT => index.put(key, block)?,
+            DELETE => index.delete(key)?,
+            _ => break,
+        };
+
+        offset += length;
+    }
+
+    Ok(offset)
+}
+
+/// Decodes the record at the start of the given bytes.
+/// Returns [`None`] if the record is incomplete or its checksum does not match.
+fn decode(bytes: &[u8]) -> Option<(u8, &[u8], &[u8], usize)> {
+    let header = bytes.get(..HEADER_LENGTH)?;
+    let key_length = u32::from_be_bytes(header[1..5].try_into().ok()?) as usize;
+    let block_length = u32::from_be_bytes(header[5..9].try_into().ok()?) as usize;
+
+    let body_end = HEADER_LENGTH
+        .checked_add(key_length)?
+        .checked_add(block_length)?;
+    let length = body_end.checked_add(CHECKSUM_LENGTH)?;
+
+    let record = bytes.get(..length)?;
+    let checksum = u32::from_be_bytes(record[body_end..].try_into().ok()?);
+
+    if crc32fast::hash(&record[..body_end]) != checksum {
+        return None;
+    }
+
+    let key = &record[HEADER_LENGTH..HEADER_LENGTH + key_length];
+    let block = &record[HEADER_LENGTH + key_length..body_end];
+
+    Some((header[0], key, block, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reopen() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("store.log");
+
+        {
+            let mut backend = FileBackend::open(&path).unwrap();
+
+            backend.put(b"a", b"1").unwrap();
+            backend.put(b"b", b"2").unwrap();
+            backend.put(b"a", b"3").unwrap();
+            backend.delete(b"b").unwrap();
+        }
+
+        let backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(backend.get(b"a").unwrap(), Some(b"3".to_vec()));
+        assert_eq!(backend.get(b"b").unwrap(), None);
+    }
+
+    #[test]
+    fn recover_from_torn_write() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("store.log");
+
+        {
+            let mut backend = FileBackend::open(&path).unwrap();
+
+            backend.put(b"a", b"1").unwrap();
+        }
+
+        let valid = fs::metadata(&path).unwrap().len();
+        let torn = encode(PUT, b"b", b"2").unwrap();
+
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&torn[..torn.len() - 1])
+            .unwrap();
+
+        let mut backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().len(), valid);
+        assert_eq!(backend.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(backend.get(b"b").unwrap(), None);
+
+        backend.put(b"c", b"3").unwrap();
+
+        let backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(backend.get(b"c").unwrap(), Some(b"3".to_vec()));
+    }
+
+    #[test]
+    fn discard_failed_append() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("store.log");
+        let mut backend = FileBackend::open(&path).unwrap();
+
+        backend.put(b"a", b"1").unwrap();
+
+        let torn = encode(PUT, b"b", b"2").unwrap();
+
+        backend.log.write_all(&torn[..torn.len() - 1]).unwrap();
+        backend.torn = true;
+        backend.put(b"c", b"3").unwrap();
+
+        let backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(backend.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(backend.get(b"b").unwrap(), None);
+        assert_eq!(backend.get(b"c").unwrap(), Some(b"3".to_vec()));
+    }
+
+    #[test]
+    fn recover_from_corrupt_record() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("store.log");
+
+        {
+            let mut backend = FileBackend::open(&path).unwrap();
+
+            backend.put(b"a", b"1").unwrap();
+            backend.put(b"b", b"2").unwrap();
+        }
+
+        let mut contents = fs::read(&path).unwrap();
+        let last = contents.len() - CHECKSUM_LENGTH - 1;
+
+        contents[last] ^= 0xFF;
+        fs::write(&path, contents).unwrap();
+
+        let backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(backend.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(backend.get(b"b").unwrap(), None);
+    }
+
+    #[test]
+    fn compact() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("store.compact");
+        let mut backend = FileBackend::open(&path).unwrap();
+
+        for _ in 0..10 {
+            backend.put(b"a", b"1").unwrap();
+        }
+
+        let before = fs::metadata(&path).unwrap().len();
+
+        backend.compact().unwrap();
+        backend.put(b"b", b"2").unwrap();
+
+        assert!(fs::metadata(&path).unwrap().len() < before);
+        assert!(!directory.path().join("store.compact.compact").exists());
+
+        let backend = FileBackend::open(&path).unwrap();
+
+        assert_eq!(
+            backend.scan(Bound::Unbounded, Bound::Unbounded).unwrap(),
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec())
+            ]
+        );
+    }
+}
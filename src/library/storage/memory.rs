@@ -0,0 +1,112 @@
+//! A volatile storage backend kept entirely in memory.
+
+use crate::library::storage::{Backend, Entry, StorageError};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// A [`Backend`] that loses all of its entries when dropped.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Backend for MemoryBackend {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.entries.get(key).cloned())
+    }
+
+    fn put(&mut self, key: &[u8], block: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.entries.insert(key.to_vec(), block.to_vec()))
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.entries.remove(key))
+    }
+
+    fn scan(&self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Result<Vec<Entry>, StorageError> {
+        if is_empty(start, end) {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .entries
+            .range::<[u8], _>((start, end))
+            .map(|(key, block)| (key.clone(), block.clone()))
+            .collect())
+    }
+}
+
+/// Tests whether no key can lie in the given range.
+/// [`BTreeMap::range`] panics on such ranges instead of returning no entries.
+fn is_empty(start: Bound<&[u8]>, end: Bound<&[u8]>) -> bool {
+    match (start, end) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start >= end,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_get_delete() {
+        let mut backend = MemoryBackend::default();
+
+        assert_eq!(backend.put(b"a", b"1").unwrap(), None);
+        assert_eq!(backend.put(b"a", b"2").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(backend.get(b"a").unwrap(), Some(b"2".to_vec()));
+        assert_eq!(backend.delete(b"a").unwrap(), Some(b"2".to_vec()));
+        assert_eq!(backend.get(b"a").unwrap(), None);
+    }
+
+    #[test]
+    fn scan_range() {
+        let mut backend = MemoryBackend::default();
+
+        for key in [b"a", b"b", b"c", b"d"] {
+            backend.put(key, key).unwrap();
+        }
+
+        let keys: Vec<Vec<u8>> = backend
+            .scan(Bound::Included(b"b"), Bound::Excluded(b"d"))
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+
+        assert_eq!(keys, vec![b"b".to_vec(), b"c".to_vec()]);
+    }
+
+    #[test]
+    fn scan_empty_range() {
+        let mut backend = MemoryBackend::default();
+
+        for key in [b"a", b"b", b"c"] {
+            backend.put(key, key).unwrap();
+        }
+
+        assert!(backend
+            .scan(Bound::Included(b"c"), Bound::Included(b"a"))
+            .unwrap()
+            .is_empty());
+        assert!(backend
+            .scan(Bound::Excluded(b"b"), Bound::Excluded(b"b"))
+            .unwrap()
+            .is_empty());
+        assert!(backend
+            .scan(Bound::Included(b"b"), Bound::Excluded(b"b"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            backend
+                .scan(Bound::Included(b"b"), Bound::Included(b"b"))
+                .unwrap(),
+            vec![(b"b".to_vec(), b"b".to_vec())]
+        );
+    }
+}
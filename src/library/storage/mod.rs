@@ -0,0 +1,99 @@
+//! A key-value block store for processes that need durable state.
+//! Keys and blocks are arbitrary byte strings, ordered lexicographically by key.
+//! The store is exposed to Tortuga programs as a [`StorageProcess`] with a pluggable [`Backend`].
+//!
+//! Tortuga code gets the address of the store with the `storage!()` procedure.
+//! The process registered as [`NAME`] is the store; when none is registered, an in-memory store is spawned and registered.
+//! To give Tortuga code a durable store, spawn a [`StorageProcess`] with a [`FileBackend`] and register it as [`NAME`].
+
+mod file;
+mod memory;
+mod process;
+
+pub use file::FileBackend;
+pub use memory::MemoryBackend;
+pub use process::{Operation, StorageProcess};
+
+use crate::runtime::process::procedures;
+use crate::{RuntimeError, Value};
+use std::ops::Bound;
+
+/// The name the store for Tortuga code is registered under.
+pub const NAME: &str = "storage";
+
+/// A key and its corresponding block.
+pub type Entry = (Vec<u8>, Vec<u8>);
+
+/// An error that occurred while reading from or writing to a storage [`Backend`].
+#[derive(thiserror::Error, Debug)]
+pub enum StorageError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(
+        "A key or block of {0} bytes exceeds the maximum of {} bytes.",
+        u32::MAX
+    )]
+    RecordTooLarge(u64),
+}
+
+impl From<StorageError> for RuntimeError {
+    fn from(error: StorageError) -> Self {
+        RuntimeError::Storage(error.to_string())
+    }
+}
+
+/// The storage medium for a key-value block store.
+pub trait Backend: Send + 'static {
+    /// The block stored under the given key, if any.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Stores a block under the given key.
+    /// Returns the block previously stored under the key, if any.
+    fn put(&mut self, key: &[u8], block: &[u8]) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Removes the block stored under the given key.
+    /// Returns the removed block, if any.
+    fn delete(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// All entries with keys in the given range, in ascending order by key.
+    fn scan(&self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Result<Vec<Entry>, StorageError>;
+}
+
+/// The built-in `storage!()` procedure.
+pub(crate) fn storage(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    if !arguments.is_empty() {
+        return None;
+    }
+
+    Some(procedures::current("@storage!").map(|current| {
+        let system = current.system();
+        let address = system.lookup(NAME).unwrap_or_else(|| {
+            let address = system.spawn(StorageProcess::new(MemoryBackend::default()));
+
+            system.register(NAME, address);
+            address
+        });
+
+        Value::from(address)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+
+    #[test]
+    fn storage_procedure() {
+        let source = r###"
+            @store = storage!()
+            call!(store, 2, 1, 42)
+            call!(store, 1, 1)
+        "###;
+
+        assert_eq!(
+            Interpreter::build_then_run(source),
+            Ok(Value::from(42f64.to_be_bytes().as_slice()))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Exposes a storage [`Backend`] to Tortuga programs as a process.
+
+use crate::library::storage::Backend;
+use crate::runtime::process::{Context, Process};
+use crate::runtime::{ByteString, Tuple};
+use crate::{RuntimeError, Value};
+use std::any::type_name;
+use std::convert::TryFrom;
+use std::ops::Bound;
+
+/// The operations supported by a [`StorageProcess`].
+/// Messages are tuples whose first field is the number of the operation, followed by its arguments:
+///
+/// | Operation | Message                 | Reply                                              |
+/// |:----------|:------------------------|:---------------------------------------------------|
+/// | Get       | `(1, key)`              | The stored block, or `{}` if the key is not stored.  |
+/// | Put       | `(2, key, block)`       | The previously stored block, or `{}`.                |
+/// | Delete    | `(3, key)`              | The deleted block, or `{}`.                          |
+/// | Scan      | `(4, start, end)`       | A tuple of `(key, block)` tuples with `start <= key < end`. |
+///
+/// Keys and blocks are byte strings. Numbers are also accepted and stored in network byte order.
+/// A `{}` start or end leaves that side of a scan unbounded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Get = 1,
+    Put = 2,
+    Delete = 3,
+    Scan = 4,
+}
+
+impl TryFrom<&Value> for Operation {
+    type Error = RuntimeError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let operation = match value {
+            Value::Number(number) if number.to_f64().fract() == 0.0 => match number.to_f64() as i64
+            {
+                1 => Some(Operation::Get),
+                2 => Some(Operation::Put),
+                3 => Some(Operation::Delete),
+                4 => Some(Operation::Scan),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        operation.ok_or_else(|| {
+            RuntimeError::UnexpectedType(value.clone(), type_name::<Self>().to_string())
+        })
+    }
+}
+
+impl From<Operation> for Value {
+    fn from(operation: Operation) -> Self {
+        Value::from(operation as i32)
+    }
+}
+
+/// A process that serves get, put, delete and scan messages from a storage [`Backend`].
+#[derive(Debug)]
+pub struct StorageProcess<B: Backend> {
+    backend: B,
+}
+
+impl<B: Backend> StorageProcess<B> {
+    /// Creates a new [`StorageProcess`] for the given [`Backend`].
+    pub fn new(backend: B) -> Self {
+        StorageProcess { backend }
+    }
+}
+
+impl<B: Backend> Process for StorageProcess<B> {
+    fn receive(&mut self, _: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        let fields = match &message {
+            Value::Tuple(tuple) => tuple.as_slice(),
+            _ => {
+                return Err(RuntimeError::UnexpectedType(
+                    message.clone(),
+                    type_name::<Tuple>().to_string(),
+                ))
+            }
+        };
+
+        let argument = |index: usize| fields.get(index).unwrap_or(&Value::Unit);
+        let operation = Operation::try_from(argument(0))?;
+
+        let reply = match operation {
+            Operation::Get => self.backend.get(&to_bytes(argument(1))?)?,
+            Operation::Put => self
+                .backend
+                .put(&to_bytes(argument(1))?, &to_bytes(argument(2))?)?,
+            Operation::Delete => self.backend.delete(&to_bytes(argument(1))?)?,
+            Operation::Scan => {
+                let start = to_bound(argument(1), Bound::Included)?;
+                let end = to_bound(argument(2), Bound::Excluded)?;
+                let entries = self.backend.scan(
+                    start.as_ref().map(Vec::as_slice),
+                    end.as_ref().map(Vec::as_slice),
+                )?;
+
+                return Ok(entries
+                    .into_iter()
+                    .map(|(key, block)| Value::from(vec![Value::from(key), Value::from(block)]))
+                    .collect::<Tuple>()
+                    .into());
+            }
+        };
+
+        Ok(reply.map(Value::from).unwrap_or_default())
+    }
+}
+
+fn to_bytes(value: &Value) -> Result<Vec<u8>, RuntimeError> {
+    match value {
+        Value::Bytes(bytes) => Ok(bytes.as_bytes().to_vec()),
+        Value::Number(number) => Ok(number.to_f64().to_be_bytes().to_vec()),
+        _ => Err(RuntimeError::UnexpectedType(
+            value.clone(),
+            type_name::<ByteString>().to_string(),
+        )),
+    }
+}
+
+fn to_bound(
+    value: &Value,
+    bound: fn(Vec<u8>) -> Bound<Vec<u8>>,
+) -> Result<Bound<Vec<u8>>, RuntimeError> {
+    match value {
+        Value::Unit => Ok(Bound::Unbounded),
+        value => to_bytes(value).map(bound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::storage::MemoryBackend;
+    use crate::runtime::process::System;
+
+    fn message(fields: Vec<Value>) -> Value {
+        Value::from(fields)
+    }
+
+    #[test]
+    fn get_put_delete_scan() {
+        let system = System::default();
+        let storage = system.spawn(StorageProcess::new(MemoryBackend::default()));
+        let key = Value::from(b"key".as_slice());
+
+        assert_eq!(
+            system.call(
+                storage,
+                message(vec![Operation::Put.into(), key.clone(), 42.into()])
+            ),
+            Ok(Value::Unit)
+        );
+        assert_eq!(
+            system.call(storage, message(vec![Operation::Get.into(), key.clone()])),
+            Ok(Value::from(42f64.to_be_bytes().to_vec()))
+        );
+        assert_eq!(
+            system.call(
+                storage,
+                message(vec![Operation::Scan.into(), Value::Unit, Value::Unit])
+            ),
+            Ok(Value::from(vec![Value::from(vec![
+                key.clone(),
+                Value::from(42f64.to_be_bytes().to_vec())
+            ])]))
+        );
+        assert_eq!(
+            system.call(
+                storage,
+                message(vec![Operation::Delete.into(), key.clone()])
+            ),
+            Ok(Value::from(42f64.to_be_bytes().to_vec()))
+        );
+        assert_eq!(
+            system.call(storage, message(vec![Operation::Get.into(), key])),
+            Ok(Value::Unit)
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn invalid_operation() {
+        let system = System::default();
+        let storage = system.spawn(StorageProcess::new(MemoryBackend::default()));
+
+        assert_eq!(
+            system.call(storage, message(vec![7.into()])),
+            Err(RuntimeError::UnexpectedType(
+                7.into(),
+                type_name::<Operation>().to_string()
+            ))
+        );
+        let storage = system.spawn(StorageProcess::new(MemoryBackend::default()));
+
+        assert_eq!(
+            system.call(storage, message(vec![1.5.into()])),
+            Err(RuntimeError::UnexpectedType(
+                1.5.into(),
+                type_name::<Operation>().to_string()
+            ))
+        );
+
+        system.shutdown();
+    }
+}
@@ -0,0 +1,173 @@
+//! Functions that the runtime provides to every Tortuga program.
+//!
+//! Tortuga has no text values, so numbers are not formatted within a program.
+//! Instead, the rounding functions round a number to the value of its literal when formatted
+//! with a given number of fractional digits in a given radix (10 by default):
+//!
+//! - `round_to(x, digits[, radix])` rounds to the nearest value; ties round to an even last digit.
+//! - `floor_to(x, digits[, radix])` rounds towards negative infinity.
+//! - `ceiling_to(x, digits[, radix])` rounds towards positive infinity.
+//! - `truncate_to(x, digits[, radix])` rounds towards zero.
+//!
+//! The number of digits and the radix must be natural numbers, and the radix must be between 2 and 36.
+//!
+//! The built-in procedures spawn processes and send messages to them:
+//!
+//! - `spawn!`, `send!`, `call!`, `self!`, `link!` and `supervise!` are described in [`process`](crate::runtime::process).
+//! - `storage!()` is the address of the key-value block store (see [`storage`](crate::library::storage)).
+//! - `logger!()` is the address of the logging process (see [`logging`](crate::library::logging)).
+
+use crate::compiler::Deprecation;
+use crate::library::{logging, storage};
+use crate::runtime::process::procedures;
+use crate::runtime::{Builtin, Environment, Function, Number, NumberFormat, Rounding};
+use crate::{RuntimeError, Value};
+
+/// The built-in functions, in the order their slots are defined.
+const BUILTINS: [(&str, Builtin); 4] = [
+    ("round_to", round_to),
+    ("floor_to", floor_to),
+    ("ceiling_to", ceiling_to),
+    ("truncate_to", truncate_to),
+];
+
+/// The built-in procedures, in the order their slots are defined after the built-in functions.
+const PROCEDURES: [(&str, Builtin); 8] = [
+    ("spawn", procedures::spawn),
+    ("send", procedures::send),
+    ("call", procedures::call),
+    ("self", procedures::address),
+    ("link", procedures::link),
+    ("supervise", procedures::supervise),
+    ("storage", storage::storage),
+    ("logger", logging::logger),
+];
+
+/// The deprecated built-in functions and procedures, along with their [`Deprecation`].
+/// The compiler warns about every call to them (e.g., `("round_to", Deprecation::new("W0001", "The built-in function @round_to", ...))`).
+/// No built-in is deprecated yet.
+const DEPRECATED: [(&str, Deprecation); 0] = [];
+
+/// The deprecated built-in functions and procedures, along with their [`Deprecation`].
+pub(crate) fn deprecated() -> &'static [(&'static str, Deprecation)] {
+    &DEPRECATED
+}
+
+/// The names of the built-in functions and procedures, along with whether each one is a procedure.
+pub(crate) fn names() -> impl Iterator<Item = (&'static str, bool)> {
+    let functions = BUILTINS.iter().map(|(name, _)| (*name, false));
+    let procedures = PROCEDURES.iter().map(|(name, _)| (*name, true));
+
+    functions.chain(procedures)
+}
+
+/// Defines all of the built-in functions and procedures in the given (root) [`Environment`].
+pub(crate) fn define_all(environment: &mut Environment) {
+    for (name, builtin) in BUILTINS {
+        environment.define_builtin(Function::builtin(name, builtin));
+    }
+
+    for (name, builtin) in PROCEDURES {
+        environment.define_builtin(Function::builtin_procedure(name, builtin));
+    }
+}
+
+fn round_to(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    round(arguments, Rounding::HalfEven)
+}
+
+fn floor_to(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    round(arguments, Rounding::Floor)
+}
+
+fn ceiling_to(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    round(arguments, Rounding::Ceiling)
+}
+
+fn truncate_to(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    round(arguments, Rounding::Down)
+}
+
+/// Rounds a number to a number of fractional digits in an optional radix.
+fn round(arguments: &[Value], rounding: Rounding) -> Option<Result<Value, RuntimeError>> {
+    let (number, digits, radix) = match arguments {
+        [Value::Number(number), Value::Number(digits)] => (*number, *digits, 10.into()),
+        [Value::Number(number), Value::Number(digits), Value::Number(radix)] => {
+            (*number, *digits, *radix)
+        }
+        _ => return None,
+    };
+
+    let format = NumberFormat::new(natural(radix)?)
+        .ok()?
+        .with_precision(natural(digits)? as usize)
+        .with_rounding(rounding);
+
+    Some(Ok(format.round(number).into()))
+}
+
+fn natural(number: Number) -> Option<u32> {
+    let value = number.to_f64();
+
+    (value >= 0.0 && value.fract() == 0.0 && value <= u32::MAX as f64).then_some(value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+
+    #[test]
+    fn rounding_functions() {
+        assert_eq!(
+            Interpreter::build_then_run("round_to(1.23456, 2)"),
+            Ok(Value::from(1.23))
+        );
+        assert_eq!(
+            Interpreter::build_then_run("round_to(2.5, 0)"),
+            Ok(Value::from(2))
+        );
+        assert_eq!(
+            Interpreter::build_then_run("floor_to(-1.25, 1)"),
+            Ok(Value::from(-1.3))
+        );
+        assert_eq!(
+            Interpreter::build_then_run("ceiling_to(1.21, 1)"),
+            Ok(Value::from(1.3))
+        );
+        assert_eq!(
+            Interpreter::build_then_run("truncate_to(-1.29, 1)"),
+            Ok(Value::from(-1.2))
+        );
+        assert_eq!(
+            Interpreter::build_then_run("round_to(0.3, 3, 2)"),
+            Ok(Value::from(0.25))
+        );
+    }
+
+    #[test]
+    fn rounding_functions_mismatched_arguments() {
+        for source in [
+            "round_to(1.5)",
+            "round_to(1.5, 0.5)",
+            "round_to(1.5, -1)",
+            "round_to(1.5, 1, 37)",
+        ] {
+            assert!(
+                matches!(
+                    Interpreter::build_then_run(source),
+                    Err(RuntimeError::NoMatchingDefinition(name, _)) if name == "@round_to"
+                ),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn declarations_extend_builtins() {
+        assert_eq!(
+            Interpreter::build_then_run("@round_to(@x) = round_to(x, 0)\nround_to(7.5)"),
+            Ok(Value::from(8))
+        );
+    }
+}
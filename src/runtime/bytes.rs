@@ -0,0 +1,80 @@
+//! Representation of byte strings within the Tortuga runtime.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// An immutable, fixed size sequence of bytes.
+/// Byte strings share their contents, so cloning a [`ByteString`] is cheap.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct ByteString(Arc<[u8]>);
+
+impl ByteString {
+    /// The number of bytes in this [`ByteString`].
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Tests whether this [`ByteString`] has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The contents of this [`ByteString`] as a slice.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ByteString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+impl From<Vec<u8>> for ByteString {
+    fn from(bytes: Vec<u8>) -> Self {
+        ByteString(bytes.into())
+    }
+}
+
+impl From<&[u8]> for ByteString {
+    fn from(bytes: &[u8]) -> Self {
+        ByteString(bytes.into())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for ByteString {
+    fn from(bytes: &[u8; N]) -> Self {
+        ByteString(bytes.as_slice().into())
+    }
+}
+
+impl AsRef<[u8]> for ByteString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Display for ByteString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("0x")?;
+
+        for byte in self.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(ByteString::from(&[0x0a, 0xff]).to_string(), "0x0aff");
+        assert_eq!(ByteString::default().to_string(), "0x");
+    }
+}
@@ -1,28 +1,70 @@
 //! A scope used to determine the runtime value of a function.
 
-use crate::runtime::Function;
+use crate::grammar::lexical::{Identifier, Slot};
+use crate::grammar::{Name, Symbol};
+use crate::runtime::{Function, MemoTable};
 use crate::{RuntimeError, Value};
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
 
 /// The variable context for a single lexical scope.
 /// Environments are a tree, the root of the tree has no parent.
-/// Since all variables are immutable and variables are not allowed to shadow each other,
-/// environments start as a clone of their parent.
+/// Since all variables are immutable, parents are shared between their children instead of copied.
+///
+/// Variables are stored in slots in order of declaration and are accessed by their resolved [`Slot`],
+/// so evaluation never looks up a variable by name.
+/// Slots are stored in a persistent vector that shares structure between clones,
+/// so capturing an environment in a function declaration is constant time.
+/// Each call starts a new scope, so calls are independent of the number of variables already defined.
+///
+/// An environment may also hold a [`MemoTable`] of function results that is shared with all of its scopes.
+/// Whether independent sub-expressions are evaluated in parallel is also inherited by all scopes.
 #[derive(Clone, Debug, Default)]
 pub struct Environment {
-    names: HashMap<String, Value>,
-    functions: Vec<Function>,
+    parent: Option<Arc<Environment>>,
+    slots: im::Vector<(Symbol, Value)>,
+    memo: Option<Arc<Mutex<MemoTable>>>,
+    parallel: bool,
 }
 
-/// A reference to a function.
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct FunctionReference(usize);
+/// A shared reference to a function.
+/// References are equal only when they refer to the same function instance.
+#[derive(Clone)]
+pub struct FunctionReference(Arc<Function>);
 
-impl From<usize> for FunctionReference {
-    fn from(index: usize) -> Self {
-        FunctionReference(index)
+impl FunctionReference {
+    /// The [`Function`] this reference refers to.
+    pub fn function(&self) -> &Function {
+        self.0.as_ref()
+    }
+}
+
+impl From<Function> for FunctionReference {
+    fn from(function: Function) -> Self {
+        FunctionReference(Arc::new(function))
+    }
+}
+
+impl PartialEq for FunctionReference {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for FunctionReference {}
+
+impl Hash for FunctionReference {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+impl fmt::Debug for FunctionReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FunctionReference")
+            .field(&self.0.to_string())
+            .finish()
     }
 }
 
@@ -33,90 +75,164 @@ impl fmt::Display for FunctionReference {
 }
 
 impl Environment {
-    /// Get the [`Function`] declarations with the given name.
-    pub fn function(&self, reference: &FunctionReference) -> Result<Function, RuntimeError> {
-        self.functions
-            .get(reference.0)
-            .cloned()
-            .ok_or_else(|| RuntimeError::FunctionNotDefined(reference.to_string()))
+    /// Creates an empty scope whose parent is the given [`Environment`].
+    pub fn scope(parent: &Arc<Environment>) -> Self {
+        Environment {
+            parent: Some(Arc::clone(parent)),
+            slots: im::Vector::new(),
+            memo: parent.memo.clone(),
+            parallel: parent.parallel,
+        }
     }
 
-    /// Get the [`FunctionReference`] with the given name.
-    pub fn value(&self, name: &str) -> Result<Value, RuntimeError> {
-        self.names
-            .get(name)
-            .copied()
-            .ok_or_else(|| RuntimeError::FunctionNotDefined(name.to_string()))
+    /// Evaluates independent sub-expressions in this environment (and its future scopes) in parallel.
+    pub fn with_parallelism(mut self) -> Self {
+        self.parallel = true;
+        self
     }
 
-    pub fn override_function(&mut self, function: Function) -> Result<Value, RuntimeError> {
-        if let Some(name) = function.name() {
-            if let Some(Value::FunctionReference(reference)) = self.names.get(name) {
-                if let Some(slot) = self.functions.get_mut(reference.0) {
-                    *slot = function;
-                    return Ok(Value::from(*reference));
-                }
+    /// Tests whether independent sub-expressions are evaluated in parallel.
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Memoizes the results of calls to functions in this environment (and its future scopes) using the given table.
+    pub fn with_memo_table(mut self, table: MemoTable) -> Self {
+        self.memo = Some(Arc::new(Mutex::new(table)));
+        self
+    }
+
+    /// The table used to memoize function calls, if any.
+    pub fn memo_table(&self) -> Option<&Arc<Mutex<MemoTable>>> {
+        self.memo.as_ref()
+    }
+
+    /// The names of the variables declared in this scope, in order of their slots.
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.slots.iter().map(|(symbol, _)| symbol.clone())
+    }
+
+    /// The names and values of the variables declared in this scope, in order of their slots.
+    pub fn variables(&self) -> impl Iterator<Item = (Symbol, &Value)> + '_ {
+        self.slots
+            .iter()
+            .map(|(symbol, value)| (symbol.clone(), value))
+    }
+
+    /// Finds the [`Value`] in the given [`Slot`].
+    fn get(&self, slot: Slot) -> Option<&Value> {
+        let mut environment = self;
+
+        for _ in 0..slot.depth() {
+            environment = environment.parent.as_deref()?;
+        }
+
+        environment.slots.get(slot.index()).map(|(_, value)| value)
+    }
+
+    /// Get the [`Value`] of the variable the given [`Identifier`] was resolved to.
+    pub fn value(&self, identifier: &Identifier) -> Result<Value, RuntimeError> {
+        identifier
+            .slot()
+            .and_then(|slot| self.get(slot))
+            .cloned()
+            .ok_or_else(|| RuntimeError::FunctionNotDefined(identifier.as_str().to_string()))
+    }
+
+    /// Stores a [`Value`] in the slot of the existing variable with the same name in this scope,
+    /// or in a new slot otherwise.
+    fn bind(&mut self, identifier: &Identifier, value: Value) -> Value {
+        let entry = (identifier.symbol(), value.clone());
+
+        match identifier.slot() {
+            Some(slot) if slot.depth() == 0 && slot.index() < self.slots.len() => {
+                self.slots.set(slot.index(), entry);
             }
+            _ => self.slots.push_back(entry),
         }
 
-        self.define_function(function)
+        value
+    }
+
+    /// Binds the given name to the function being called in a new scope.
+    /// Used to allow a function to call itself using the complete set of its declarations.
+    pub fn override_function(&mut self, name: Symbol, reference: &FunctionReference) {
+        self.slots.push_back((name, Value::from(reference.clone())));
     }
 
     /// Defines a [`Function`] as having a given name.
-    /// Returns the previously defined value as an [`Err`], if any.
-    pub fn define_function(&mut self, function: Function) -> Result<Value, RuntimeError> {
-        if let Some(name) = function.name() {
-            if let Some(Value::FunctionReference(reference)) = self.names.get(name) {
-                let existing = self
-                    .functions
-                    .get_mut(reference.0)
-                    .ok_or_else(|| RuntimeError::FunctionNotDefined(name.to_string()))?;
+    /// Merges the declarations of an existing function with the same name in this scope, if any.
+    /// Functions in outer scopes are shadowed instead, so a parameter or nested declaration never conflicts with them.
+    pub fn define_function(
+        &mut self,
+        name: &Name,
+        function: Function,
+    ) -> Result<Value, RuntimeError> {
+        let identifier = match name {
+            Name::Anonymous => return Ok(FunctionReference::from(function).into()),
+            Name::Identified(identifier) => identifier,
+        };
+
+        match identifier.slot().and_then(|slot| self.get(slot)) {
+            None => Ok(self.bind(identifier, FunctionReference::from(function).into())),
+            Some(Value::FunctionReference(_))
+                if identifier.slot().is_some_and(|slot| slot.depth() > 0) =>
+            {
+                Ok(self.bind(identifier, FunctionReference::from(function).into()))
+            }
+            Some(Value::FunctionReference(reference)) => {
+                let mut existing = reference.function().clone();
 
                 existing.merge(function)?;
 
-                return Ok(Value::from(*reference));
+                Ok(self.bind(identifier, FunctionReference::from(existing).into()))
             }
+            Some(_) => Err(RuntimeError::FunctionAlreadyDefined(format!(
+                "@{}",
+                identifier.as_str()
+            ))),
         }
+    }
 
-        let index = self.functions.len();
-        let value = FunctionReference(index).into();
-
-        self.define_value(function.name(), value)?;
-        self.functions.push(function);
+    /// Defines a built-in [`Function`] in a new slot under its own name.
+    pub(crate) fn define_builtin(&mut self, function: Function) {
+        if let Some(name) = function.name() {
+            let name = Symbol::intern(name);
 
-        Ok(value)
+            self.slots
+                .push_back((name, FunctionReference::from(function).into()));
+        }
     }
 
     /// Defines a variable as having a given [`Value`].
-    /// Returns the previously defined value, if any.
-    pub fn define_value(
-        &mut self,
-        name: Option<&str>,
-        value: Value,
-    ) -> Result<Value, RuntimeError> {
-        match name {
-            Some(name) => match self.names.entry(name.to_string()) {
-                Vacant(entry) => {
-                    entry.insert(value);
-                    Ok(value)
-                }
-                Occupied(_) => Err(RuntimeError::FunctionAlreadyDefined(format!("@{}", name))),
-            },
-            None => Ok(value),
+    /// Variables may not be re-defined.
+    pub fn define_value(&mut self, name: &Name, value: Value) -> Result<Value, RuntimeError> {
+        let identifier = match name {
+            Name::Anonymous => return Ok(value),
+            Name::Identified(identifier) => identifier,
+        };
+
+        match identifier.slot().and_then(|slot| self.get(slot)) {
+            None => Ok(self.bind(identifier, value)),
+            Some(_) => Err(RuntimeError::FunctionAlreadyDefined(format!(
+                "@{}",
+                identifier.as_str()
+            ))),
         }
     }
 
+    /// Defines the given [`Value`] under a new name.
+    /// Functions are renamed to allow them to be merged with existing declarations.
     pub fn define_function_from(
         &mut self,
-        source: &mut Environment,
-        name: Option<&str>,
+        name: &Name,
         value: Value,
     ) -> Result<Value, RuntimeError> {
         match value {
-            Value::FunctionReference(ref reference) => {
-                let mut function = source.function(reference)?;
-                function.set_name(name);
-                self.define_function(function)
+            Value::FunctionReference(reference) => {
+                let mut function = reference.function().clone();
+                function.set_name(name.as_symbol());
+                self.define_function(name, function)
             }
             constant => self.define_value(name, constant),
         }
@@ -1,5 +1,7 @@
 //! Runtime errors.
 
+use crate::compiler::{stringify_errors, CompilationError, SemanticError};
+use crate::runtime::process::Address;
 use crate::Value;
 
 /// An error that may occur while executing a [`Program`].
@@ -7,6 +9,8 @@ use crate::Value;
 pub enum RuntimeError {
     #[error("Encountered a syntax error parsing the given Tortuga input. {0}")]
     Syntax(#[from] crate::SyntacticalError),
+    #[error("Encountered one or more semantic errors in the given Tortuga input. {}", stringify_errors(.0.as_slice()))]
+    Semantic(Vec<SemanticError>),
     #[error(transparent)]
     Number(#[from] crate::ParseNumberError),
     #[error("Function {0} is already defined.")]
@@ -17,6 +21,41 @@ pub enum RuntimeError {
     UnexpectedType(Value, String),
     #[error("No definition found for function {0} with the given arguments: {}.", stringify_arguments(.1.as_slice()))]
     NoMatchingDefinition(String, Vec<Value>),
+    #[error("Procedure {0} must be called with an exclamation mark.")]
+    ProcedureCalledAsFunction(String),
+    #[error("Function {0} is not a procedure and must be called without an exclamation mark.")]
+    NotAProcedure(String),
+    #[error("{0} is declared as both a function and a procedure.")]
+    ConflictingDeclaration(String),
+    #[error("No process is running at address {0}.")]
+    ProcessNotFound(Address),
+    #[error("Procedure {0} may only be called from within a process.")]
+    OutsideProcess(String),
+    #[error("Supervisor {0} exceeded its maximum restart intensity.")]
+    RestartIntensityExceeded(Address),
+    #[error(transparent)]
+    Wire(#[from] crate::runtime::wire::WireError),
+    #[error("Lost the connection to node {0}.")]
+    NodeDisconnected(u64),
+    #[error("Process {0} on a remote node failed. {1}")]
+    Remote(Address, String),
+    #[error("Evaluation was cancelled, since a sub-expression evaluated before it failed.")]
+    Cancelled,
+    #[error("Unable to start the threads for parallel evaluation. {0}")]
+    ThreadPool(String),
+    #[error("Storage failure. {0}")]
+    Storage(String),
+    #[error("Unable to write to the log sink. {0}")]
+    Logging(String),
+}
+
+impl From<CompilationError> for RuntimeError {
+    fn from(error: CompilationError) -> Self {
+        match error {
+            CompilationError::Syntactical(error) => RuntimeError::Syntax(error),
+            CompilationError::Semantic(errors) => RuntimeError::Semantic(errors),
+        }
+    }
 }
 
 fn stringify_arguments(arguments: &[Value]) -> String {
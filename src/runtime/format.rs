@@ -0,0 +1,485 @@
+//! Formats numbers as Tortuga literals in any supported radix.
+//!
+//! Formatted numbers are valid number literals (preceded by a `-` when negative),
+//! so they re-parse to the value they represent.
+//! Without a precision, the shortest literal that re-parses to exactly the same value is used.
+//! With a precision, the number is first rounded to that many fractional digits in the radix of the format.
+//!
+//! Numbers that are very large or very small are written in scientific notation (e.g., `1.5*^-20`).
+//! Non-finite numbers have no literal, so they are written the same way as by [`Display`](std::fmt::Display).
+
+use crate::compiler::NumberErrorKind;
+use crate::runtime::{Number, Tolerance, Tuple, Value};
+
+/// The radix of numbers that are written without a radix prefix.
+const DEFAULT_RADIX: u32 = 10;
+/// The largest radix a number literal may have.
+const MAX_RADIX: u32 = 36;
+/// Scaled digits must be below this limit to fit in the `u128` used when parsing a literal.
+const MAX_SCALED: f64 = 1.7014118346046923e38; // 2^127
+/// Numbers below this magnitude are written in scientific notation.
+const MIN_FIXED: f64 = 9.5367431640625e-7; // 2^-20
+/// Numbers at or above this magnitude are written in scientific notation.
+const MAX_FIXED: f64 = 1.8446744073709552e19; // 2^64
+
+/// How to round a number that has more fractional digits than the precision of a [`NumberFormat`].
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Rounding {
+    /// Round to the nearest number; ties round to the number with an even last digit.
+    #[default]
+    HalfEven,
+    /// Round to the nearest number; ties round away from zero.
+    HalfUp,
+    /// Round towards zero (i.e., truncate).
+    Down,
+    /// Round away from zero.
+    Up,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceiling,
+}
+
+impl Rounding {
+    /// Rounds a non-negative magnitude to an integer.
+    /// The sign of the number the magnitude belongs to determines the direction of [`Rounding::Floor`] and [`Rounding::Ceiling`].
+    fn round(self, magnitude: f64, negative: bool) -> f64 {
+        match (self, negative) {
+            (Rounding::HalfEven, _) => magnitude.round_ties_even(),
+            (Rounding::HalfUp, _) => magnitude.round(),
+            (Rounding::Down, _) | (Rounding::Floor, false) | (Rounding::Ceiling, true) => {
+                magnitude.trunc()
+            }
+            (Rounding::Up, _) | (Rounding::Floor, true) | (Rounding::Ceiling, false) => {
+                magnitude.ceil()
+            }
+        }
+    }
+}
+
+/// Formats [`Number`]s as literals in a given radix.
+///
+/// # Example
+/// ```rust
+/// use tortuga::runtime::{NumberFormat, Rounding};
+///
+/// let format = NumberFormat::new(16).unwrap().with_precision(2);
+///
+/// assert_eq!(format.format(10.75.into()), "16#A.C");
+/// assert_eq!(format.format((-1.0 / 3.0).into()), "-16#0.55");
+/// assert_eq!(format.with_rounding(Rounding::Up).format((1.0 / 3.0).into()), "16#0.56");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NumberFormat {
+    radix: u32,
+    precision: Option<usize>,
+    rounding: Rounding,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            radix: DEFAULT_RADIX,
+            precision: None,
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Creates a new [`NumberFormat`] for the given radix.
+    /// The radix must be between 2 and 36, the same as for number literals.
+    pub fn new(radix: u32) -> Result<Self, NumberErrorKind> {
+        if (2..=MAX_RADIX).contains(&radix) {
+            Ok(NumberFormat {
+                radix,
+                ..Default::default()
+            })
+        } else {
+            Err(NumberErrorKind::UnsupportedRadix(radix))
+        }
+    }
+
+    /// Rounds numbers to at most the given number of fractional digits before formatting them.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Rounds numbers in the given mode when they have more fractional digits than the precision.
+    pub fn with_rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// The radix numbers are formatted in.
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    /// The maximum number of fractional digits, if any.
+    pub fn precision(&self) -> Option<usize> {
+        self.precision
+    }
+
+    /// The mode used to round numbers to the precision.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Rounds the given [`Number`] to the precision of this format.
+    /// The result is the value of the literal that [`NumberFormat::format`] returns for the same number.
+    pub fn round(&self, number: Number) -> Number {
+        let value = number.to_f64();
+
+        if !value.is_finite() {
+            return number;
+        }
+
+        let magnitude = self.literal(value.abs(), value < 0.0);
+        let rounded = parse(magnitude.as_str()).unwrap_or(value.abs());
+
+        Number::from(rounded.copysign(value))
+    }
+
+    /// Formats the given [`Number`] as a literal, preceded by a `-` when the number is negative.
+    pub fn format(&self, number: Number) -> String {
+        let value = number.to_f64();
+
+        if !value.is_finite() {
+            return number.to_string();
+        }
+
+        let literal = self.literal(value.abs(), value < 0.0);
+
+        if value < 0.0 && parse(literal.as_str()) != Some(0.0) {
+            format!("-{literal}")
+        } else {
+            literal
+        }
+    }
+
+    /// Formats the numbers in the given [`Value`], including those in tolerances and tuples.
+    /// All other values are formatted the same way as by [`Display`](std::fmt::Display).
+    pub fn format_value(&self, value: &Value) -> String {
+        match value {
+            Value::Number(number) => self.format(*number),
+            Value::Tolerance(tolerance) => self.format_tolerance(tolerance),
+            Value::Tuple(tuple) => self.format_tuple(tuple),
+            value => value.to_string(),
+        }
+    }
+
+    fn format_tolerance(&self, tolerance: &Tolerance) -> String {
+        format!(
+            "{} ± {}",
+            self.format(tolerance.center()),
+            self.format(tolerance.epsilon())
+        )
+    }
+
+    fn format_tuple(&self, tuple: &Tuple) -> String {
+        let fields: Vec<String> = tuple.iter().map(|field| self.format_value(field)).collect();
+
+        format!("({})", fields.join(", "))
+    }
+
+    /// Formats a non-negative, finite magnitude as a literal.
+    fn literal(&self, magnitude: f64, negative: bool) -> String {
+        if magnitude == 0.0 {
+            return self.prefix(String::from("0"));
+        }
+
+        if let Some(precision) = self.precision {
+            let scale = (self.radix as f64).powi(i32::try_from(precision).unwrap_or(i32::MAX));
+            let scaled = self.rounding.round(magnitude * scale, negative);
+
+            if magnitude < MAX_FIXED && scaled < MAX_SCALED {
+                return self.prefix(self.digits(scaled as u128, precision));
+            }
+        }
+
+        if (MIN_FIXED..MAX_FIXED).contains(&magnitude) {
+            if let Some(literal) = self.shortest(magnitude, 0) {
+                return literal;
+            }
+        }
+
+        self.scientific(magnitude)
+    }
+
+    /// Finds the literal with the fewest fractional digits that re-parses to the given magnitude,
+    /// written with the given exponent.
+    fn shortest(&self, magnitude: f64, exponent: i32) -> Option<String> {
+        let significand = self.significand(magnitude, exponent)?;
+        let radix = self.radix as f64;
+        let mut scale = 1.0;
+
+        for precision in 0.. {
+            let scaled = (significand * scale).round_ties_even();
+
+            if scaled >= MAX_SCALED {
+                break;
+            }
+
+            let literal = self.with_exponent(self.digits(scaled as u128, precision), exponent);
+
+            if parse(literal.as_str()) == Some(magnitude) {
+                return Some(literal);
+            }
+
+            scale *= radix;
+        }
+
+        None
+    }
+
+    /// Writes the given magnitude in scientific notation, with a single integer digit in the significand.
+    /// Falls back to the nearest literal with the most digits when no literal re-parses to exactly the same value.
+    fn scientific(&self, magnitude: f64) -> String {
+        let estimate = self.exponent(magnitude);
+        let exponents = [estimate, estimate + 1, estimate - 1];
+
+        exponents
+            .iter()
+            .find_map(|exponent| self.shortest(magnitude, *exponent))
+            .unwrap_or_else(|| {
+                let significand = self.significand(magnitude, estimate).unwrap_or_default();
+                let precision = (MAX_SCALED.log2() / (self.radix as f64).log2()) as usize - 1;
+                let scaled = significand * (self.radix as f64).powi(precision as i32);
+
+                self.with_exponent(self.digits(scaled as u128, precision), estimate)
+            })
+    }
+
+    /// The exponent that leaves a single integer digit in the significand of the given magnitude.
+    fn exponent(&self, magnitude: f64) -> i32 {
+        let radix = self.radix as f64;
+        let estimate = (magnitude.ln() / radix.ln()).floor() as i32;
+
+        match self.significand(magnitude, estimate) {
+            Some(significand) if significand >= radix => estimate + 1,
+            Some(significand) if significand < 1.0 => estimate - 1,
+            _ => estimate,
+        }
+    }
+
+    /// Divides the given magnitude by the radix raised to the given exponent.
+    /// The division is split in two when the power of the radix is not finite.
+    fn significand(&self, magnitude: f64, exponent: i32) -> Option<f64> {
+        let radix = self.radix as f64;
+        let power = radix.powi(exponent);
+
+        let significand = if power.is_normal() {
+            magnitude / power
+        } else {
+            let half = exponent / 2;
+
+            magnitude / radix.powi(half) / radix.powi(exponent - half)
+        };
+
+        significand.is_normal().then_some(significand)
+    }
+
+    /// Writes the given digits in the radix of this format, with the given number of them after the decimal point.
+    /// Trailing zeros in the fraction are removed, since literals may not have them.
+    fn digits(&self, mut scaled: u128, precision: usize) -> String {
+        let radix = self.radix as u128;
+        let mut digits = Vec::new();
+
+        while scaled > 0 || digits.len() <= precision {
+            let digit = (scaled % radix) as u32;
+
+            digits.push(
+                std::char::from_digit(digit, self.radix)
+                    .unwrap_or_default()
+                    .to_ascii_uppercase(),
+            );
+            scaled /= radix;
+        }
+
+        digits.reverse();
+
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+        let integer: String = integer.iter().collect();
+        let fraction: String = fraction.iter().collect();
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            integer
+        } else {
+            format!("{integer}.{fraction}")
+        }
+    }
+
+    fn with_exponent(&self, significand: String, exponent: i32) -> String {
+        let literal = if exponent == 0 {
+            significand
+        } else {
+            let sign = if exponent < 0 { "-" } else { "" };
+            let digits = self.digits(exponent.unsigned_abs() as u128, 0);
+
+            format!("{significand}*^{sign}{digits}")
+        };
+
+        self.prefix(literal)
+    }
+
+    fn prefix(&self, literal: String) -> String {
+        if self.radix == DEFAULT_RADIX {
+            literal
+        } else {
+            format!("{}#{literal}", self.radix)
+        }
+    }
+}
+
+/// Parses a literal the same way the compiler does.
+fn parse(literal: &str) -> Option<f64> {
+    literal.parse::<Number>().ok().map(Number::to_f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADICES: [u32; 5] = [2, 3, 10, 16, 36];
+
+    fn round_trip(radix: u32, value: f64) {
+        let format = NumberFormat::new(radix).unwrap();
+        let literal = format.format(value.into());
+        let magnitude = literal.trim_start_matches('-');
+
+        assert_eq!(
+            parse(magnitude).map(|parsed| parsed.copysign(value)),
+            Some(value),
+            "{value} formatted in radix {radix} as {literal}"
+        );
+        assert_eq!(literal.starts_with('-'), value < 0.0);
+    }
+
+    #[test]
+    fn format_shortest() {
+        let format = NumberFormat::default();
+
+        assert_eq!(format.format(0.into()), "0");
+        assert_eq!(format.format((-0.0).into()), "0");
+        assert_eq!(format.format(42.into()), "42");
+        assert_eq!(format.format(0.1.into()), "0.1");
+        assert_eq!(format.format((-2.5).into()), "-2.5");
+        assert_eq!(format.format(1e21.into()), "1*^21");
+        assert_eq!(format.format(1.5e-20.into()), "1.5*^-20");
+        assert_eq!(NumberFormat::new(2).unwrap().format(5.into()), "2#101");
+        assert_eq!(
+            NumberFormat::new(16).unwrap().format(255.5.into()),
+            "16#FF.8"
+        );
+        assert_eq!(
+            NumberFormat::new(16).unwrap().format(2f64.powi(-40).into()),
+            "16#1*^-A"
+        );
+    }
+
+    #[test]
+    fn format_round_trip() {
+        let values = [
+            1.0,
+            0.1,
+            0.2 + 0.1,
+            1.0 / 3.0,
+            -2.0 / 7.0,
+            std::f64::consts::PI,
+            std::f64::consts::E * 1e10,
+            123456789.98765432,
+            f64::EPSILON,
+            2f64.powi(63) + 2048.0,
+            6.02214076e23,
+            -1.602176634e-19,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+        ];
+
+        for radix in RADICES {
+            for value in values {
+                round_trip(radix, value);
+            }
+        }
+    }
+
+    #[test]
+    fn format_precision() {
+        let format = NumberFormat::default().with_precision(2);
+
+        assert_eq!(format.format(std::f64::consts::PI.into()), "3.14");
+        assert_eq!(format.format(0.5.into()), "0.5");
+        assert_eq!(format.format(1e-9.into()), "0");
+        assert_eq!(format.format(1e30.into()), "1*^30");
+        assert_eq!(
+            NumberFormat::new(2)
+                .unwrap()
+                .with_precision(3)
+                .format(0.3.into()),
+            "2#0.01"
+        );
+        assert_eq!(
+            NumberFormat::new(3)
+                .unwrap()
+                .with_precision(1)
+                .format((1.0 / 3.0).into()),
+            "3#0.1"
+        );
+    }
+
+    #[test]
+    fn format_rounding() {
+        let cases = [
+            (Rounding::HalfEven, [2.0, -2.0, 3.0, 2.0]),
+            (Rounding::HalfUp, [3.0, -3.0, 3.0, 2.0]),
+            (Rounding::Down, [2.0, -2.0, 2.0, 2.0]),
+            (Rounding::Up, [3.0, -3.0, 3.0, 3.0]),
+            (Rounding::Floor, [2.0, -3.0, 2.0, 2.0]),
+            (Rounding::Ceiling, [3.0, -2.0, 3.0, 3.0]),
+        ];
+
+        for (rounding, expected) in cases {
+            let format = NumberFormat::default()
+                .with_precision(0)
+                .with_rounding(rounding);
+            let actual = [2.5, -2.5, 2.75, 2.25].map(|value| format.round(value.into()).to_f64());
+
+            assert_eq!(actual, expected, "{rounding:?}");
+        }
+    }
+
+    #[test]
+    fn round_matches_format() {
+        for radix in RADICES {
+            for precision in 0..5 {
+                let format = NumberFormat::new(radix).unwrap().with_precision(precision);
+
+                for value in [1.0 / 3.0, -12.34567, 1e-3, 98765.4321] {
+                    let literal = format.format(value.into());
+                    let rounded = format.round(value.into()).to_f64();
+
+                    assert_eq!(
+                        parse(literal.trim_start_matches('-')).map(|parsed| parsed.copysign(value)),
+                        Some(rounded),
+                        "{value} formatted in radix {radix} as {literal}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_radix() {
+        assert_eq!(
+            NumberFormat::new(1),
+            Err(NumberErrorKind::UnsupportedRadix(1))
+        );
+        assert_eq!(
+            NumberFormat::new(37),
+            Err(NumberErrorKind::UnsupportedRadix(37))
+        );
+    }
+}
@@ -1,14 +1,18 @@
 //! Runtime representation of a function.
 
-use crate::grammar::{self, Assignment, Block, Pattern};
-use crate::runtime::interpret::Interpret;
-use crate::runtime::Environment;
+use crate::grammar::lexical::Identifier;
+use crate::grammar::{self, Assignment, Block, Name, Pattern, Symbol};
+use crate::runtime::interpret::{matches, Interpret};
+use crate::runtime::{parallel, Environment, FunctionReference};
 use crate::{RuntimeError, Value};
 use std::fmt::{self, Display, Formatter, Write};
+use std::sync::Arc;
 
 /// A declaration of a [`Function`].
+/// Captures the [`Environment`] it was declared in, which is shared rather than copied.
+/// The name the function was declared with occupies the first slot of each call's scope.
 #[derive(Clone, Debug)]
-pub struct Declaration(Vec<Pattern>, Environment, Block);
+pub struct Declaration(Vec<Pattern>, Arc<Environment>, Block, Option<Symbol>);
 
 impl Declaration {
     /// Create a new [`Declaration`].
@@ -17,112 +21,180 @@ impl Declaration {
 
         Declaration(
             parameters.to_vec(),
-            environment.clone(),
+            Arc::new(environment.clone()),
             assignment.block().clone(),
+            assignment.function().name().as_symbol(),
         )
     }
 
     pub fn call(
         &self,
-        function: &Function,
+        function: &FunctionReference,
         arguments: &[Value],
-        environment: &mut Environment,
-    ) -> Option<Result<CallResult, RuntimeError>> {
+    ) -> Option<Result<Value, RuntimeError>> {
         if !self.0.is_empty() && self.0.len() != arguments.len() {
             return None;
         }
 
-        let mut local_environment = self.1.clone();
+        let mut local_environment = Environment::scope(&self.1);
 
-        if let Err(error) = local_environment.override_function(function.clone()) {
-            return Some(Err(error));
+        if let Some(name) = self.3.clone() {
+            local_environment.override_function(name, function);
         }
 
-        for (parameter, &argument) in self.0.iter().zip(arguments.iter()) {
-            let name = parameter.name().as_str();
-
-            if let Err(error) = local_environment.define_function_from(environment, name, argument)
-            {
-                return Some(Err(error));
+        for (parameter, argument) in self.0.iter().zip(arguments.iter().cloned()) {
+            // Anonymous parameters are not bound to a variable, so there is nothing to match.
+            if let Name::Anonymous = parameter.name() {
+                return None;
             }
 
-            if let Value::Boolean(false) = parameter.execute(&mut local_environment).ok()? {
+            let value = match local_environment.define_function_from(parameter.name(), argument) {
+                Ok(value) => value,
+                Err(error) => return Some(Err(error)),
+            };
+
+            if let Value::Boolean(false) =
+                matches(parameter, &value, &mut local_environment).ok()?
+            {
                 return None;
             }
         }
 
-        Some(
-            self.2
-                .execute(&mut local_environment)
-                .map(|value| CallResult(value, local_environment)),
-        )
+        Some(self.2.execute(&mut local_environment))
     }
 }
 
+/// A function implemented by the runtime instead of in Tortuga.
+/// Returns `None` when the arguments do not match, the same as a [`Declaration`].
+pub type Builtin = fn(&[Value]) -> Option<Result<Value, RuntimeError>>;
+
 /// A runtime function.
 #[derive(Clone, Debug)]
 pub struct Function {
-    name: Option<String>,
-    declarations: Vec<Declaration>,
+    name: Option<Symbol>,
+    builtin: Option<Builtin>,
+    declarations: Vec<Arc<Declaration>>,
+    procedure: bool,
 }
 
-/// The result of correctly invoking a [`Function`].
-pub struct CallResult(Value, Environment);
-
-impl Interpret for CallResult {
-    fn execute(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
-        match self.0 {
-            Value::FunctionReference(reference) => {
-                let mut function = self.1.function(&reference)?;
-
-                function.name.take();
+impl Function {
+    /// Creates a new instance of a runtime [`Function`].
+    pub fn new(assignment: &Assignment, environment: &Environment) -> Self {
+        Function {
+            name: assignment.function().name().as_symbol(),
+            builtin: None,
+            declarations: vec![Arc::new(Declaration::new(assignment, environment))],
+            procedure: assignment.function().is_procedure(),
+        }
+    }
 
-                environment.define_function(function).map(Value::from)
-            }
-            value => Ok(value),
+    /// Creates a new built-in [`Function`] with the given name.
+    /// Declarations merged into a built-in function are only called when the built-in does not match the arguments.
+    pub fn builtin(name: &str, builtin: Builtin) -> Self {
+        Function {
+            name: Some(Symbol::intern(name)),
+            builtin: Some(builtin),
+            declarations: Vec::new(),
+            procedure: false,
         }
     }
-}
 
-impl Function {
-    /// Creates a new instance of a runtime [`Function`].
-    pub fn new(assignment: &Assignment, environment: &Environment) -> Self {
+    /// Creates a new built-in procedure with the given name.
+    pub fn builtin_procedure(name: &str, builtin: Builtin) -> Self {
         Function {
-            name: assignment.function().name().as_str().map(String::from),
-            declarations: vec![Declaration::new(assignment, environment)],
+            procedure: true,
+            ..Function::builtin(name, builtin)
         }
     }
 
+    /// Tests whether this [`Function`] is a procedure (i.e., may have side-effects).
+    pub fn is_procedure(&self) -> bool {
+        self.procedure
+    }
+
     /// The [`Name`] patterns for this [`Function`].
     pub fn name(&self) -> Option<&str> {
-        self.name.as_deref()
+        self.name.as_ref().map(Symbol::as_str)
     }
 
     /// The [`Name`] patterns for this [`Function`].
-    pub fn set_name(&mut self, name: Option<&str>) {
-        self.name = name.map(String::from);
+    pub fn set_name(&mut self, name: Option<Symbol>) {
+        self.name = name;
     }
 
-    /// Calls this [`Function`] with the given arguments.
-    pub fn call(
-        &self,
-        arguments: &[Value],
-        environment: &mut Environment,
-    ) -> Result<CallResult, RuntimeError> {
-        for declaration in self.declarations.as_slice() {
-            if let Some(result) = declaration.call(self, arguments, environment) {
-                return result;
+    /// Tests whether this [`Function`] is implemented by the runtime.
+    pub fn is_builtin(&self) -> bool {
+        self.builtin.is_some()
+    }
+
+    /// The distinct numbers of parameters of this [`Function`]'s declarations, in ascending order.
+    /// Built-in functions are not declared, so their arities are unknown.
+    pub fn arities(&self) -> Vec<usize> {
+        let mut arities: Vec<usize> = self
+            .declarations
+            .iter()
+            .map(|declaration| declaration.0.len())
+            .collect();
+
+        arities.sort_unstable();
+        arities.dedup();
+        arities
+    }
+
+    /// The signatures of this [`Function`]'s declarations, in the order they are tried.
+    pub fn signatures(&self) -> Vec<grammar::Function> {
+        let name = match self.name() {
+            Some(name) => Name::from(Identifier::new(name)),
+            None => Name::Anonymous,
+        };
+
+        self.declarations
+            .iter()
+            .map(|declaration| match self.procedure {
+                true => grammar::Function::procedure(name.clone(), declaration.0.clone()),
+                false => grammar::Function::new(name.clone(), declaration.0.clone()),
+            })
+            .collect()
+    }
+
+    /// Calls the referenced [`Function`] with the given arguments.
+    /// Functions returned from the call are anonymous.
+    pub fn call(reference: &FunctionReference, arguments: &[Value]) -> Result<Value, RuntimeError> {
+        parallel::check_cancelled()?;
+
+        let function = reference.function();
+
+        if let Some(result) = function.builtin.and_then(|builtin| builtin(arguments)) {
+            return result;
+        }
+
+        for declaration in function.declarations.as_slice() {
+            if let Some(result) = declaration.call(reference, arguments) {
+                return match result? {
+                    Value::FunctionReference(returned) if returned.function().name.is_some() => {
+                        let mut anonymous = returned.function().clone();
+
+                        anonymous.name.take();
+
+                        Ok(FunctionReference::from(anonymous).into())
+                    }
+                    value => Ok(value),
+                };
             }
         }
 
         Err(RuntimeError::NoMatchingDefinition(
-            self.to_string(),
+            function.to_string(),
             arguments.to_vec(),
         ))
     }
 
     /// Merges the declarations of the given function into this one.
     pub fn merge(&mut self, other: Function) -> Result<(), RuntimeError> {
+        if self.procedure != other.procedure {
+            return Err(RuntimeError::ConflictingDeclaration(self.to_string()));
+        }
+
         if self == &other {
             return Err(RuntimeError::FunctionAlreadyDefined(self.to_string()));
         }
@@ -149,17 +221,25 @@ impl PartialEq for Function {
 
 impl PartialEq<grammar::Function> for Function {
     fn eq(&self, other: &grammar::Function) -> bool {
-        self.declarations
-            .iter()
-            .any(|declaration| declaration.0.as_slice() == other.parameters())
+        self.procedure == other.is_procedure()
+            && self
+                .declarations
+                .iter()
+                .any(|declaration| declaration.0.as_slice() == other.parameters())
     }
 }
 
 impl Display for Function {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self.name() {
-            Some(name) => write!(f, "@{}", name),
-            None => f.write_char('_'),
+            Some(name) => write!(f, "@{}", name)?,
+            None => f.write_char('_')?,
+        }
+
+        if self.procedure {
+            f.write_char('!')?;
         }
+
+        Ok(())
     }
 }
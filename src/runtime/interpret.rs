@@ -1,10 +1,16 @@
 //! An interpreter used in the CLI prompt.
 
+use crate::compiler::OptimizationLevel;
 use crate::grammar::*;
-use crate::runtime::{Environment, EpsilonOperator, FunctionReference, Value};
-use crate::{runtime, Program, RuntimeError};
+use crate::runtime::process::procedures::{self, Current};
+use crate::runtime::process::System;
+use crate::runtime::{builtins, parallel};
+use crate::runtime::{Environment, EpsilonOperator, FunctionReference, MemoTable, Value};
+use crate::{compiler, runtime, Program, RuntimeError};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::convert::TryFrom;
 use std::ops::Deref;
+use std::sync::Arc;
 
 /// Interprets a Tortuga [`Program`] and returns the [`Value`] by walking the syntax tree.
 ///
@@ -47,15 +53,112 @@ use std::ops::Deref;
 ///
 /// assert_eq!(interpreter.run(program), Ok(true.into()));
 /// ```
-#[derive(Debug, Default)]
+///
+/// ## Processes
+/// ```rust
+/// use tortuga::Interpreter;
+///
+/// let value = Interpreter::build_then_run("@double(@x) = x * 2\n@p = spawn!(double)\ncall!(p, 21)");
+///
+/// assert_eq!(value, Ok(42.into()));
+/// ```
+#[derive(Debug)]
 pub struct Interpreter {
     environment: Environment,
+    pool: Option<Arc<ThreadPool>>,
+    optimization: OptimizationLevel,
+    system: System,
+}
+
+impl Default for Interpreter {
+    /// Creates an interpreter whose environment only defines the built-in functions.
+    fn default() -> Self {
+        let mut environment = Environment::default();
+
+        builtins::define_all(&mut environment);
+
+        Interpreter {
+            environment,
+            pool: None,
+            optimization: OptimizationLevel::default(),
+            system: System::default(),
+        }
+    }
 }
 
+/// The stack size of the threads used for parallel evaluation, to support deeply recursive functions.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
 impl Interpreter {
+    /// Memoizes the results of function calls.
+    /// At most `capacity` results are kept; the least recently used result is evicted first.
+    pub fn with_memoization(mut self, capacity: usize) -> Self {
+        self.environment = self.environment.with_memo_table(MemoTable::new(capacity));
+        self
+    }
+
+    /// Evaluates sufficiently expensive independent sub-expressions in parallel on a pool of threads.
+    /// The results are identical to those of sequential evaluation.
+    /// Fails when the pool of threads cannot be started.
+    pub fn with_parallelism(mut self) -> Result<Self, RuntimeError> {
+        let pool = ThreadPoolBuilder::new()
+            .stack_size(STACK_SIZE)
+            .build()
+            .map_err(|error| RuntimeError::ThreadPool(error.to_string()))?;
+
+        self.environment = self.environment.with_parallelism();
+        self.pool = Some(Arc::new(pool));
+
+        Ok(self)
+    }
+
+    /// Runs the processes spawned by programs in the given [`System`].
+    pub fn with_system(mut self, system: System) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// The [`System`] that runs the processes spawned by programs.
+    pub fn system(&self) -> &System {
+        &self.system
+    }
+
+    /// Optimizes each program at the given level before running it.
+    pub fn with_optimization(mut self, level: OptimizationLevel) -> Self {
+        self.optimization = level;
+        self
+    }
+
+    /// The root [`Environment`] with the variables defined by previous runs.
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
     /// Runs the given [`Program`].
-    pub fn run(&mut self, program: Program) -> Result<Value, RuntimeError> {
-        program.execute(&mut self.environment)
+    /// Resolves the identifiers in the program against the variables already defined by previous runs.
+    ///
+    /// Each run is a transaction: when the program fails, none of the variables it defined are kept,
+    /// so they may be defined again by a later run.
+    /// The side-effects of procedures (e.g., messages sent to processes) are not undone.
+    pub fn run(&mut self, mut program: Program) -> Result<Value, RuntimeError> {
+        compiler::optimize(&mut program, self.optimization);
+        compiler::resolve(&mut program, self.environment.symbols());
+
+        // Environments are persistent, so the snapshot shares its slots instead of copying them.
+        let snapshot = self.environment.clone();
+        let current = Current::System(self.system.clone());
+        let environment = &mut self.environment;
+        let execute = || procedures::enter(current, || program.execute(environment));
+        let result = match self.pool.as_ref() {
+            Some(pool) => pool.install(execute),
+            None => execute(),
+        };
+
+        if result.is_err() {
+            self.environment = snapshot;
+        }
+
+        result
     }
 
     /// Build then execute the given input.
@@ -113,46 +216,73 @@ impl Interpret for Assignment {
         let function = runtime::Function::new(self, environment);
 
         if self.function().parameters().is_empty() {
-            let value = function.call(&[], environment)?.execute(environment)?;
-            environment.define_value(function.name(), value)
+            let value = runtime::Function::call(&function.into(), &[])?;
+            environment.define_value(self.function().name(), value)
         } else {
-            environment.define_function(function)
+            environment.define_function(self.function().name(), function)
         }
     }
 }
 
 impl Interpret for Operation {
     fn execute(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
-        let lhs = self.lhs().execute(environment)?;
-        let rhs = self.rhs().execute(environment)?;
+        let (lhs, rhs) = parallel::join(self.lhs(), self.rhs(), environment)?;
 
         match self.operator() {
-            Operator::Add => Ok(lhs + rhs),
-            Operator::Subtract => Ok(lhs - rhs),
-            Operator::Multiply => Ok(lhs * rhs),
-            Operator::Divide => Ok(lhs / rhs),
-            Operator::Exponent => Ok(lhs ^ rhs),
-            Operator::Modulo => Ok(lhs.abs() % rhs.abs()),
-            Operator::Tolerance => Ok(lhs.epsilon(rhs)),
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide => lhs / rhs,
+            Operator::Exponent => lhs ^ rhs,
+            Operator::Modulo => lhs.abs()? % rhs.abs()?,
+            Operator::Tolerance => lhs.epsilon(rhs),
         }
     }
 }
 
 impl Interpret for Call {
     fn execute(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
-        let mut value = self.callee().execute(environment)?;
+        let value = self.callee().execute(environment)?;
 
         let reference = FunctionReference::try_from(value)?;
-        let function = environment.function(&reference)?;
-        let mut values = Vec::new();
+        let function = reference.function();
+
+        match (function.is_procedure(), self.is_procedure()) {
+            (true, false) => {
+                return Err(RuntimeError::ProcedureCalledAsFunction(
+                    function.to_string(),
+                ))
+            }
+            (false, true) => return Err(RuntimeError::NotAProcedure(function.to_string())),
+            _ => (),
+        }
+
+        let values = parallel::evaluate_all(self.arguments().iter(), environment)?;
+
+        // Procedures may have side-effects, so only calls to functions are memoized.
+        let memo = if function.is_procedure() {
+            None
+        } else {
+            environment.memo_table()
+        };
 
-        for argument in self.arguments().iter() {
-            values.push(argument.execute(environment)?);
+        if let Some(Ok(mut table)) = memo.map(|memo| memo.lock()) {
+            if let Some(value) = table.get(&reference, values.as_slice()) {
+                return Ok(value);
+            }
         }
 
-        value = function
-            .call(values.as_slice(), environment)?
-            .execute(environment)?;
+        let value = if function.is_procedure() {
+            procedures::at(self.location(), || {
+                runtime::Function::call(&reference, values.as_slice())
+            })?
+        } else {
+            runtime::Function::call(&reference, values.as_slice())?
+        };
+
+        if let Some(Ok(mut table)) = memo.map(|memo| memo.lock()) {
+            table.insert(&reference, values.as_slice(), value.clone());
+        }
 
         Ok(value)
     }
@@ -166,6 +296,10 @@ impl Interpret for Grouping {
 
 impl Interpret for Number {
     fn execute(&self, _: &mut Environment) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.value() {
+            return Ok(Value::from(value));
+        }
+
         let mut number = self
             .number()
             .as_str()
@@ -173,7 +307,7 @@ impl Interpret for Number {
             .map(Value::Number)?;
 
         if self.is_negative() {
-            number *= Value::from(-1.0);
+            number = (number * Value::from(-1.0))?;
         }
 
         Ok(number)
@@ -182,45 +316,44 @@ impl Interpret for Number {
 
 impl Interpret for lexical::Identifier {
     fn execute(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
-        environment.value(self.as_str())
+        environment.value(self)
     }
 }
 
-impl Interpret for Pattern {
-    fn execute(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
-        let name = self.name().as_str().unwrap_or_default();
-        let value = environment.value(name)?;
-
-        match self {
-            Pattern::Function(signature) => {
-                if signature.parameters().is_empty() {
-                    return Ok(true.into());
-                }
+/// Tests whether the given [`Value`] bound to a parameter matches the parameter's [`Pattern`].
+pub(crate) fn matches(
+    pattern: &Pattern,
+    value: &Value,
+    environment: &mut Environment,
+) -> Result<Value, RuntimeError> {
+    match pattern {
+        Pattern::Function(signature) => {
+            if signature.parameters().is_empty() {
+                return Ok(true.into());
+            }
 
-                let reference = FunctionReference::try_from(value)?;
-                let function = environment.function(&reference)?;
+            let reference = FunctionReference::try_from(value.clone())?;
 
-                Ok(Value::Boolean(&function == signature.deref()))
-            }
-            Pattern::Refinement(refinement) => Ok(compare(
+            Ok(Value::Boolean(reference.function() == signature.deref()))
+        }
+        Pattern::Refinement(refinement) => Ok(compare(
+            value,
+            refinement.comparator(),
+            &refinement.constraint().execute(environment)?,
+        )),
+        Pattern::Bounds(bounds) => {
+            if let Value::Boolean(false) = compare_inequality(
+                &bounds.left().constraint().execute(environment)?,
+                bounds.left().inequality(),
                 value,
-                refinement.comparator(),
-                refinement.constraint().execute(environment)?,
-            )),
-            Pattern::Bounds(bounds) => {
-                if let Value::Boolean(false) = compare_inequality(
-                    bounds.left().constraint().execute(environment)?,
-                    bounds.left().inequality(),
+            ) {
+                Ok(false.into())
+            } else {
+                Ok(compare_inequality(
                     value,
-                ) {
-                    Ok(false.into())
-                } else {
-                    Ok(compare_inequality(
-                        value,
-                        bounds.right().inequality(),
-                        bounds.right().constraint().execute(environment)?,
-                    ))
-                }
+                    bounds.right().inequality(),
+                    &bounds.right().constraint().execute(environment)?,
+                ))
             }
         }
     }
@@ -232,7 +365,7 @@ impl Interpret for Comparisons {
         let mut comparator = self.comparisons().head().comparator();
         let mut rhs = self.comparisons().head().rhs().execute(environment)?;
 
-        let mut value = compare(lhs, comparator, rhs);
+        let mut value = compare(&lhs, comparator, &rhs);
 
         for comparison in self.comparisons().tail() {
             if value == Value::Boolean(false) {
@@ -243,14 +376,14 @@ impl Interpret for Comparisons {
             comparator = comparison.comparator();
             rhs = comparison.rhs().execute(environment)?;
 
-            value &= compare(lhs, comparator, rhs);
+            value = (value & compare(&lhs, comparator, &rhs))?;
         }
 
         Ok(value)
     }
 }
 
-fn compare(lhs: Value, comparator: &Comparator, rhs: Value) -> Value {
+pub(crate) fn compare(lhs: &Value, comparator: &Comparator, rhs: &Value) -> Value {
     Value::Boolean(match comparator {
         Comparator::LessThan => lhs < rhs,
         Comparator::LessThanOrEqualTo => lhs <= rhs,
@@ -261,7 +394,7 @@ fn compare(lhs: Value, comparator: &Comparator, rhs: Value) -> Value {
     })
 }
 
-fn compare_inequality(lhs: Value, inequality: &Inequality, rhs: Value) -> Value {
+fn compare_inequality(lhs: &Value, inequality: &Inequality, rhs: &Value) -> Value {
     Value::Boolean(match inequality {
         Inequality::LessThan => lhs < rhs,
         Inequality::LessThanOrEqualTo => lhs <= rhs,
@@ -273,6 +406,7 @@ fn compare_inequality(lhs: Value, inequality: &Inequality, rhs: Value) -> Value
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compiler::{Location, SemanticError, Type};
     use crate::runtime::Tolerance;
 
     #[test]
@@ -315,12 +449,20 @@ mod tests {
 
     #[test]
     fn invalid_call() {
-        let source = r###"
-            @x = 42
-            x(7)
-        "###;
         assert_eq!(
-            Interpreter::build_then_run(source),
+            Interpreter::build_then_run("@x = 42\nx(7)"),
+            Err(RuntimeError::Semantic(vec![SemanticError::NotAFunction {
+                location: Location::from("@x = 42\nx"),
+                found: Type::Number
+            }]))
+        );
+
+        let mut interpreter = Interpreter::default();
+
+        interpreter.run("@x = 42".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            interpreter.run("x(7)".parse().unwrap()),
             Err(RuntimeError::UnexpectedType(
                 42.into(),
                 "tortuga::runtime::environment::FunctionReference".to_string()
@@ -346,13 +488,26 @@ mod tests {
 
     #[test]
     fn wrong_number_of_arguments() {
-        let source = r###"
-            @f(_ > 3) = 42
+        assert_eq!(
+            Interpreter::build_then_run("@f(_ > 3) = 42\nf(2, 4)"),
+            Err(RuntimeError::Semantic(vec![
+                SemanticError::WrongNumberOfArguments {
+                    location: Location::from("@f(_ > 3) = 42\nf"),
+                    function: Type::Function {
+                        arities: vec![1],
+                        result: Box::new(Type::Number)
+                    },
+                    arguments: 2
+                }
+            ]))
+        );
+
+        let mut interpreter = Interpreter::default();
+
+        interpreter.run("@f(_ > 3) = 42".parse().unwrap()).unwrap();
 
-            f(2, 4)
-        "###;
         assert_eq!(
-            Interpreter::build_then_run(source),
+            interpreter.run("f(2, 4)".parse().unwrap()),
             Err(RuntimeError::NoMatchingDefinition(
                 "@f".to_string(),
                 vec![2.into(), 4.into()]
@@ -383,7 +538,16 @@ mod tests {
 
         assert_eq!(
             Interpreter::build_then_run(source),
-            Err(RuntimeError::FunctionAlreadyDefined("@f".to_string(),))
+            Err(RuntimeError::FunctionAlreadyDefined("@f".to_string()))
+        );
+
+        let mut interpreter = Interpreter::default();
+
+        interpreter.run("@f(@x) = 1".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            interpreter.run("@f(@y) = 2".parse().unwrap()),
+            Err(RuntimeError::FunctionAlreadyDefined("@f".to_string()))
         );
     }
 
@@ -516,4 +680,273 @@ mod tests {
 
         assert_eq!(Interpreter::build_then_run(source), Ok(4.into()));
     }
+
+    #[test]
+    fn procedure() {
+        let source = r###"
+            @square(@x) = x * x
+            @report!(@x) = square(x) + 1
+            @twice!(@p!(@x), @y) = p!(p!(y))
+
+            twice!(report, 2)
+        "###;
+
+        assert_eq!(Interpreter::build_then_run(source), Ok(26.into()));
+    }
+
+    #[test]
+    fn impure_function() {
+        let source = r###"
+            @report!(@x) = x
+            @f(@x) = report!(x)
+        "###;
+
+        assert!(matches!(
+            Interpreter::build_then_run(source),
+            Err(RuntimeError::Semantic(_))
+        ));
+    }
+
+    #[test]
+    fn procedure_called_as_function() {
+        let source = r###"
+            @report!(@x) = x
+            @f(@g, @x) = g(x)
+
+            f(report, 1)
+        "###;
+
+        assert_eq!(
+            Interpreter::build_then_run(source),
+            Err(RuntimeError::ProcedureCalledAsFunction("@g!".to_string()))
+        );
+    }
+
+    #[test]
+    fn procedure_across_programs() {
+        let mut interpreter = Interpreter::default();
+
+        interpreter
+            .run("@report!(@x) = x".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            interpreter.run("report(1)".parse().unwrap()),
+            Err(RuntimeError::ProcedureCalledAsFunction(
+                "@report!".to_string()
+            ))
+        );
+        assert_eq!(
+            interpreter.run("@report(@x = 0) = 0".parse().unwrap()),
+            Err(RuntimeError::ConflictingDeclaration("@report!".to_string()))
+        );
+        assert_eq!(interpreter.run("report!(1)".parse().unwrap()), Ok(1.into()));
+    }
+
+    #[test]
+    fn shadowed_procedure() {
+        let source = r###"
+            @p!(@x) = x
+            @f(@p(@y)) = p(1)
+            @g(@x) = [
+                @p(@y) = y + 1
+                p(x)
+            ]
+            @id(@y) = y
+
+            f(id) + g(1)
+        "###;
+
+        assert_eq!(Interpreter::build_then_run(source), Ok(3.into()));
+    }
+
+    #[test]
+    fn captured_environment() {
+        let source = r###"
+            @f(@x) = x + y
+            @y = 1
+            f(1)
+        "###;
+
+        assert_eq!(
+            Interpreter::build_then_run(source),
+            Err(RuntimeError::FunctionNotDefined("y".to_string()))
+        );
+    }
+
+    #[test]
+    fn returned_function() {
+        let source = r###"
+            @adder(@x) = [
+                @add(@y) = x + y
+                add
+            ]
+            @inc = adder(1)
+            @dec = adder(-1)
+            inc(dec(5)) + inc(2) * 10
+        "###;
+
+        assert_eq!(Interpreter::build_then_run(source), Ok(35.into()));
+    }
+
+    #[test]
+    fn variables_across_programs() {
+        let mut interpreter = Interpreter::default();
+
+        interpreter
+            .run("@x = 2\n@square(@n) = n * n".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            interpreter.run("square(x) + x".parse().unwrap()),
+            Ok(6.into())
+        );
+        assert_eq!(
+            interpreter.run("@x = 3".parse().unwrap()),
+            Err(RuntimeError::FunctionAlreadyDefined("@x".to_string()))
+        );
+    }
+
+    #[test]
+    fn memoization() {
+        let source = r###"
+            @fibonacci(@n <= 1) = n
+            @fibonacci(@n) = fibonacci(n - 2) + fibonacci(n - 1)
+            fibonacci(30)
+        "###;
+        let mut interpreter = Interpreter::default().with_memoization(64);
+
+        assert_eq!(interpreter.run(source.parse().unwrap()), Ok(832040.into()));
+    }
+
+    #[test]
+    fn memoize_tolerance() {
+        let source = r###"
+            @f(@x = 3) = 1
+            @f(@x) = 2
+            f(3 ~ 1) + f(3.5) * 10 + f(3 ~ 1) * 100
+        "###;
+        let mut interpreter = Interpreter::default().with_memoization(64);
+
+        assert_eq!(interpreter.run(source.parse().unwrap()), Ok(121.into()));
+    }
+
+    #[test]
+    fn parallel() {
+        let sources = [
+            r###"
+                @fibonacci(@n <= 1) = n
+                @fibonacci(@n) = fibonacci(n - 2) + fibonacci(n - 1)
+                fibonacci(15)
+            "###,
+            r###"
+                @factorial(@n <= 1) = 1
+                @factorial(@n > 1) = n * factorial(n - 1)
+                @choose(@n, @k) = factorial(n) / (factorial(k) * factorial(n - k))
+                choose(12, 5) + choose(10, 3)
+            "###,
+            r###"
+                @sum(@n <= 0) = 0
+                @sum(@n) = [
+                    @half = n / 2
+                    n + sum(half - 1) + sum(half - 2)
+                ]
+                sum(20) ~ 0.5
+            "###,
+            r###"
+                @f(@n > 0) = n
+                @g(@n) = f(n) + f(n - 5)
+                g(10) + g(3)
+            "###,
+        ];
+
+        for source in sources {
+            let sequential = Interpreter::default().run(source.parse().unwrap());
+            let parallel = Interpreter::default()
+                .with_parallelism()
+                .unwrap()
+                .run(source.parse().unwrap());
+
+            assert_eq!(sequential, parallel);
+        }
+    }
+
+    #[test]
+    fn parallel_failure() {
+        let definitions = r###"
+            @fibonacci(@n <= 1) = n
+            @fibonacci(@n) = fibonacci(n - 2) + fibonacci(n - 1)
+            @positive(@n > 0) = n
+            @sum(@a, @b, @c) = a + b + c
+        "###;
+        let mut interpreter = Interpreter::default().with_parallelism().unwrap();
+
+        interpreter.run(definitions.parse().unwrap()).unwrap();
+
+        // Evaluating fibonacci(100) would never finish, so it must be cancelled once positive(0) fails.
+        for source in [
+            "positive(0) + fibonacci(100)",
+            "sum(fibonacci(5), positive(0), fibonacci(100))",
+        ] {
+            assert_eq!(
+                interpreter.run(source.parse().unwrap()),
+                Err(RuntimeError::NoMatchingDefinition(
+                    "@positive".to_string(),
+                    vec![0.into()]
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn environment_variables() {
+        let mut interpreter = Interpreter::default();
+        let source = "@x = 2\n@f(@n > 0) = n\n@f(@n, @m) = n + m";
+
+        interpreter.run(source.parse().unwrap()).unwrap();
+
+        let variables: Vec<(String, Value)> = interpreter
+            .environment()
+            .variables()
+            .map(|(symbol, value)| (symbol.to_string(), value.clone()))
+            .filter(|(_, value)| !matches!(value, Value::FunctionReference(reference) if reference.function().is_builtin()))
+            .collect();
+
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0], ("x".to_string(), Value::from(2)));
+
+        match &variables[1] {
+            (name, Value::FunctionReference(reference)) if name == "f" => {
+                let function = reference.function();
+                let signatures: Vec<usize> = function
+                    .signatures()
+                    .iter()
+                    .map(|signature| signature.parameters().len())
+                    .collect();
+
+                assert_eq!(function.arities(), vec![1, 2]);
+                assert_eq!(signatures, vec![1, 2]);
+                assert!(function
+                    .signatures()
+                    .iter()
+                    .all(|signature| signature.name().as_str() == Some("f")));
+            }
+            variable => panic!("Unexpected variable: {variable:?}"),
+        }
+    }
+
+    #[test]
+    fn failed_run_keeps_no_definitions() {
+        let mut interpreter = Interpreter::default();
+
+        interpreter.run("@x = 1".parse().unwrap()).unwrap();
+
+        assert!(interpreter
+            .run("@y = 2\n@z = undefined(y)".parse().unwrap())
+            .is_err());
+        assert_eq!(
+            interpreter.run("@y = x + 2\n@z = y * 2\nz".parse().unwrap()),
+            Ok(Value::from(6))
+        );
+    }
 }
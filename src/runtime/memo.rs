@@ -0,0 +1,197 @@
+//! A bounded table of the results of calls to pure functions.
+//!
+//! Arguments are compared by their exact representation instead of by [`Value`] equality,
+//! because a [`Tolerance`](crate::runtime::Tolerance) is equal to every number it contains (i.e., equality is not transitive).
+//! For example, the result of `f(3 ~ 1)` must not be returned for `f(3.5)`, since the patterns of `f` may tell them apart.
+//! Once the table is full, the least recently used entry is evicted.
+
+use crate::runtime::process::Address;
+use crate::runtime::{ByteString, FunctionReference, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Formatter};
+
+/// The exact representation of an argument.
+/// Numbers are compared bit-wise and functions by identity.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Argument {
+    Unit,
+    Boolean(bool),
+    Number(u64),
+    Tolerance(u64, u64),
+    Function(FunctionReference),
+    Tuple(Vec<Argument>),
+    Bytes(ByteString),
+    Address(Address),
+}
+
+impl From<&Value> for Argument {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Unit => Argument::Unit,
+            Value::Boolean(boolean) => Argument::Boolean(*boolean),
+            Value::Number(number) => Argument::Number(number.to_f64().to_bits()),
+            Value::Tolerance(tolerance) => Argument::Tolerance(
+                tolerance.center().to_f64().to_bits(),
+                tolerance.epsilon().to_f64().to_bits(),
+            ),
+            Value::FunctionReference(reference) => Argument::Function(reference.clone()),
+            Value::Tuple(tuple) => Argument::Tuple(tuple.iter().map(Argument::from).collect()),
+            Value::Bytes(bytes) => Argument::Bytes(bytes.clone()),
+            Value::Address(address) => Argument::Address(*address),
+        }
+    }
+}
+
+/// A call to a function with a specific set of arguments.
+/// Holding on to the function keeps its identity from being re-used while the entry exists.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Key {
+    function: FunctionReference,
+    arguments: Vec<Argument>,
+}
+
+impl Key {
+    fn new(function: &FunctionReference, arguments: &[Value]) -> Self {
+        Key {
+            function: function.clone(),
+            arguments: arguments.iter().map(Argument::from).collect(),
+        }
+    }
+}
+
+/// A memoized result and the last time it was used.
+struct Entry {
+    value: Value,
+    used: u64,
+}
+
+/// A table of the results of previous function calls with a bounded number of entries.
+pub struct MemoTable {
+    capacity: usize,
+    clock: u64,
+    entries: HashMap<Key, Entry>,
+    recency: BTreeMap<u64, Key>,
+}
+
+impl MemoTable {
+    /// Creates an empty [`MemoTable`] that holds at most `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        MemoTable {
+            capacity,
+            clock: 0,
+            entries: HashMap::with_capacity(capacity),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// The maximum number of results held by this table.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of results held by this table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Tests whether this table holds any results.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Gets the result of a previous call to the function with exactly the given arguments.
+    pub fn get(&mut self, function: &FunctionReference, arguments: &[Value]) -> Option<Value> {
+        let used = self.tick();
+        let key = Key::new(function, arguments);
+        let entry = self.entries.get_mut(&key)?;
+
+        if let Some(key) = self.recency.remove(&entry.used) {
+            self.recency.insert(used, key);
+        }
+
+        entry.used = used;
+
+        Some(entry.value.clone())
+    }
+
+    /// Records the result of calling the function with the given arguments.
+    /// Evicts the least recently used result if the table is full.
+    pub fn insert(&mut self, function: &FunctionReference, arguments: &[Value], value: Value) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let used = self.tick();
+        let key = Key::new(function, arguments);
+
+        if let Some(previous) = self.entries.insert(key.clone(), Entry { value, used }) {
+            self.recency.remove(&previous.used);
+        }
+
+        self.recency.insert(used, key);
+
+        while self.entries.len() > self.capacity {
+            match self.recency.pop_first() {
+                Some((_, evicted)) => self.entries.remove(&evicted),
+                None => break,
+            };
+        }
+    }
+}
+
+impl Debug for MemoTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoTable")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Tolerance;
+    use crate::Interpreter;
+
+    fn function() -> FunctionReference {
+        FunctionReference::try_from(Interpreter::build_then_run("@f(@x) = x\nf").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn exact_arguments() {
+        let f = function();
+        let mut table = MemoTable::new(4);
+
+        table.insert(&f, &[Tolerance::new(3, 1).into()], 1.into());
+
+        assert_eq!(table.get(&f, &[3.5.into()]), None);
+        assert_eq!(table.get(&f, &[Tolerance::new(3, 0.5).into()]), None);
+        assert_eq!(
+            table.get(&f, &[Tolerance::new(3, 1).into()]),
+            Some(1.into())
+        );
+        assert_eq!(table.get(&function(), &[Tolerance::new(3, 1).into()]), None);
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let f = function();
+        let mut table = MemoTable::new(2);
+
+        table.insert(&f, &[1.into()], 1.into());
+        table.insert(&f, &[2.into()], 2.into());
+        table.get(&f, &[1.into()]);
+        table.insert(&f, &[3.into()], 3.into());
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(&f, &[1.into()]), Some(1.into()));
+        assert_eq!(table.get(&f, &[2.into()]), None);
+        assert_eq!(table.get(&f, &[3.into()]), Some(3.into()));
+    }
+}
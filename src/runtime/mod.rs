@@ -1,19 +1,33 @@
 //! Numbers, tuples, and other runtime data types necessary for compiling Tortuga programs.
 
+mod builtins;
+mod bytes;
 mod environment;
 mod epsilon;
 mod error;
+mod format;
 mod function;
 mod interpret;
+mod memo;
 mod number;
+mod parallel;
+pub mod process;
 mod tolerance;
+mod tuple;
 mod value;
+pub mod wire;
 
+pub(crate) use builtins::{deprecated as deprecated_builtins, names as builtin_names};
+pub use bytes::ByteString;
 pub use environment::{Environment, FunctionReference};
 pub use epsilon::EpsilonOperator;
 pub use error::RuntimeError;
-pub use function::Function;
+pub use format::{NumberFormat, Rounding};
+pub use function::{Builtin, Function};
 pub use interpret::Interpreter;
+pub(crate) use interpret::{compare, Interpret};
+pub use memo::MemoTable;
 pub use number::Number;
 pub use tolerance::Tolerance;
+pub use tuple::Tuple;
 pub use value::Value;
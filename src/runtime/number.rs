@@ -11,11 +11,29 @@ use std::ops::{
 #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
 pub struct Number(f64);
 
+/// Serializes a finite [`Number`] as a number, and infinity or not-a-number as a string (e.g., `"inf"` or `"NaN"`),
+/// since JSON has no representation for them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Number {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() {
+            serializer.serialize_f64(self.0)
+        } else {
+            serializer.collect_str(&self.0)
+        }
+    }
+}
+
 impl Number {
     /// The absolute value (i.e. positive) of this value.
     pub fn abs(self) -> Number {
         Number(self.0.abs())
     }
+
+    /// The floating-point representation of this value.
+    pub fn to_f64(self) -> f64 {
+        self.0
+    }
 }
 
 impl<I: Into<Number>> EpsilonOperator<I> for Number {
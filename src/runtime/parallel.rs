@@ -0,0 +1,233 @@
+//! Parallel evaluation of independent sub-expressions.
+//!
+//! Functions are pure, so the operands of an operation and the arguments of a call may be evaluated in any order.
+//! Sub-expressions that declare variables or call procedures are never evaluated in parallel,
+//! since later sub-expressions may depend on their effects.
+//! Evaluating a sub-expression on another thread has a fixed overhead,
+//! so only sub-expressions that call a function (directly or indirectly) are considered expensive enough.
+//!
+//! Sequential evaluation stops at the first sub-expression that fails, so the sub-expressions after it
+//! are cancelled at their next function call (e.g., one that would otherwise never terminate).
+
+use crate::grammar::Expression;
+use crate::runtime::interpret::Interpret;
+use crate::runtime::Environment;
+use crate::{RuntimeError, Value};
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+thread_local! {
+    static TASK: RefCell<Option<Arc<Task>>> = const { RefCell::new(None) };
+}
+
+/// The evaluation of one of a group of sub-expressions evaluated in parallel.
+#[derive(Debug)]
+struct Task {
+    /// The index of the first sub-expression in the group that failed.
+    failed: Arc<AtomicUsize>,
+    index: usize,
+    parent: Option<Arc<Task>>,
+}
+
+impl Task {
+    /// Tests whether a sub-expression before this one (or before any of its parents) failed.
+    fn is_cancelled(&self) -> bool {
+        self.failed.load(Ordering::Acquire) < self.index
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
+/// Restores the previous [`Task`] when dropped, even when the evaluation panicked.
+struct Guard(Option<Arc<Task>>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+
+        TASK.with(|task| *task.borrow_mut() = previous);
+    }
+}
+
+/// A group of sub-expressions evaluated in parallel within the current [`Task`], if any.
+struct Group {
+    failed: Arc<AtomicUsize>,
+    parent: Option<Arc<Task>>,
+}
+
+impl Group {
+    fn new() -> Self {
+        Group {
+            failed: Arc::new(AtomicUsize::new(usize::MAX)),
+            parent: TASK.with(|task| task.borrow().clone()),
+        }
+    }
+
+    /// Evaluates the sub-expression at the given index of this group on the current thread.
+    /// A failure cancels the sub-expressions after it.
+    fn evaluate(
+        &self,
+        index: usize,
+        expression: &Expression,
+        environment: &mut Environment,
+    ) -> Result<Value, RuntimeError> {
+        let task = Arc::new(Task {
+            failed: Arc::clone(&self.failed),
+            index,
+            parent: self.parent.clone(),
+        });
+        let _guard = Guard(TASK.with(|current| current.borrow_mut().replace(task)));
+        let result = expression.execute(environment);
+
+        if result.is_err() {
+            self.failed.fetch_min(index, Ordering::AcqRel);
+        }
+
+        result
+    }
+}
+
+/// Fails when the sub-expression being evaluated on this thread was cancelled.
+/// Its result is never used, since a sub-expression before it failed.
+pub(crate) fn check_cancelled() -> Result<(), RuntimeError> {
+    let cancelled = TASK.with(|task| {
+        task.borrow()
+            .as_ref()
+            .is_some_and(|task| task.is_cancelled())
+    });
+
+    if cancelled {
+        Err(RuntimeError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// The estimated cost of calling a function.
+const CALL_COST: usize = 16;
+
+/// The minimum estimated cost of a sub-expression to evaluate it in parallel.
+const THRESHOLD: usize = CALL_COST;
+
+/// Estimates the cost of evaluating an [`Expression`].
+/// Returns [`None`] if the expression is not independent of its siblings.
+fn cost(expression: &Expression) -> Option<usize> {
+    match expression {
+        Expression::Number(_) | Expression::Identifier(_) => Some(1),
+        Expression::Grouping(grouping) => cost(grouping.inner()),
+        Expression::Operation(operation) => {
+            Some(1 + cost(operation.lhs())? + cost(operation.rhs())?)
+        }
+        Expression::Call(call) if call.is_procedure() => None,
+        Expression::Call(call) => call
+            .arguments()
+            .iter()
+            .try_fold(CALL_COST + cost(call.callee())?, |total, argument| {
+                Some(total + cost(argument)?)
+            }),
+        Expression::Assignment(_) => None,
+    }
+}
+
+/// Tests whether all of the given sub-expressions are independent and expensive enough to evaluate in parallel.
+fn worthwhile<'a, I>(expressions: I) -> bool
+where
+    I: IntoIterator<Item = &'a Expression>,
+{
+    let mut count = 0;
+
+    for expression in expressions {
+        match cost(expression) {
+            Some(cost) if cost >= THRESHOLD => count += 1,
+            Some(_) => (),
+            None => return false,
+        }
+    }
+
+    count > 1
+}
+
+/// Evaluates a pair of sub-expressions, in parallel when worthwhile.
+/// Errors are reported in the same order as sequential evaluation.
+pub(crate) fn join(
+    lhs: &Expression,
+    rhs: &Expression,
+    environment: &mut Environment,
+) -> Result<(Value, Value), RuntimeError> {
+    if environment.is_parallel() && worthwhile([lhs, rhs]) {
+        let group = Group::new();
+        let mut local_environment = environment.clone();
+        let (lhs, rhs) = rayon::join(
+            || group.evaluate(0, lhs, environment),
+            || group.evaluate(1, rhs, &mut local_environment),
+        );
+
+        Ok((lhs?, rhs?))
+    } else {
+        Ok((lhs.execute(environment)?, rhs.execute(environment)?))
+    }
+}
+
+/// Evaluates a list of sub-expressions in order, in parallel when worthwhile.
+/// Errors are reported in the same order as sequential evaluation.
+pub(crate) fn evaluate_all<'a, I>(
+    expressions: I,
+    environment: &mut Environment,
+) -> Result<Vec<Value>, RuntimeError>
+where
+    I: IntoIterator<Item = &'a Expression>,
+{
+    let expressions: Vec<&Expression> = expressions.into_iter().collect();
+
+    if environment.is_parallel() && worthwhile(expressions.iter().copied()) {
+        let group = Group::new();
+        let environment: &Environment = environment;
+
+        expressions
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, expression)| group.evaluate(index, expression, &mut environment.clone()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect()
+    } else {
+        expressions
+            .into_iter()
+            .map(|expression| expression.execute(environment))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::Program;
+
+    fn expression(source: &str) -> Expression {
+        match source.parse::<Program>().unwrap() {
+            Program::Expressions(expressions) => expressions.head().clone(),
+            _ => panic!("Expected an expression."),
+        }
+    }
+
+    #[test]
+    fn cheap_expressions() {
+        assert!(!worthwhile([&expression("1 + 2"), &expression("x")]));
+        assert!(!worthwhile([&expression("f(1)"), &expression("x")]));
+    }
+
+    #[test]
+    fn expensive_expressions() {
+        assert!(worthwhile([&expression("f(1)"), &expression("g(x) + 1")]));
+    }
+
+    #[test]
+    fn dependent_expressions() {
+        assert!(!worthwhile([&expression("f(@x = 1)"), &expression("g(x)")]));
+        assert!(!worthwhile([&expression("f!(1)"), &expression("g(2)")]));
+    }
+}
@@ -0,0 +1,56 @@
+//! The address of a process is used to send it messages.
+
+use std::fmt;
+
+/// A unique identifier for a process.
+/// Addresses are made up of the identifier of the node the process runs on
+/// and the identifier of the process within that node's [`System`](crate::runtime::process::System).
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Address {
+    node: u64,
+    id: u64,
+}
+
+impl Address {
+    /// Creates an [`Address`] for the process with the given identifier on the given node.
+    pub fn new(node: u64, id: u64) -> Self {
+        Address { node, id }
+    }
+
+    /// The identifier of the node the process runs on.
+    pub fn node(&self) -> u64 {
+        self.node
+    }
+
+    /// The numerical identifier of this [`Address`] within its node.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl From<u64> for Address {
+    fn from(id: u64) -> Self {
+        Address::new(0, id)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.node {
+            0 => write!(f, "<{}>", self.id),
+            node => write!(f, "<{}.{}>", node, self.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Address::from(42).to_string(), "<42>");
+        assert_eq!(Address::new(7, 42).to_string(), "<7.42>");
+    }
+}
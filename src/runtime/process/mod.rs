@@ -0,0 +1,74 @@
+//! Processes are the building block for concurrency in Tortuga.
+//! A process owns its state and handles the messages sent to it one at a time,
+//! so processes need no synchronization or locking primitives.
+//!
+//! A process that fails with a [`RuntimeError`] exits, and the failure is reported to the processes
+//! linked to it or monitoring it as a [`Signal`]. Supervisors use these signals to restart failed processes.
+//!
+//! Tortuga code spawns and messages processes with built-in procedures (e.g., `spawn!`, `send!` and `call!`).
+//!
+//! Systems on different machines may be connected over TCP to form a cluster of nodes.
+//! Messages to processes on other nodes are sent in the [wire format](crate::runtime::wire).
+
+mod address;
+mod node;
+pub(crate) mod procedures;
+mod signal;
+mod supervisor;
+mod system;
+
+pub use address::Address;
+pub use node::{Listener, NodeError};
+pub use procedures::FunctionProcess;
+pub use signal::{ExitReason, Signal};
+pub use supervisor::{ChildSpecification, Restart, Strategy, Supervisor};
+pub use system::{Context, Origin, System};
+
+use crate::{RuntimeError, Value};
+
+/// A unit of concurrency that receives messages from other processes.
+pub trait Process: Send + 'static {
+    /// Called once when this [`Process`] starts, before it receives any messages.
+    /// Failing to start exits the process.
+    fn start(&mut self, _context: &mut Context) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Handles a single message sent to this [`Process`].
+    /// When the sender expects a reply, the returned [`Value`] (or error) is sent back to it.
+    /// Returning an error also exits the process.
+    fn receive(&mut self, context: &mut Context, message: Value) -> Result<Value, RuntimeError>;
+
+    /// Handles a [`Signal`] about a linked or monitored process.
+    /// By default, the abnormal exit of a linked process exits this one with the same reason,
+    /// while the exit of a monitored process is ignored.
+    fn signal(&mut self, _context: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+        match signal {
+            Signal::Exit(_, reason) if !reason.is_normal() => Err(reason),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<F> Process for F
+where
+    F: FnMut(&mut Context, Value) -> Result<Value, RuntimeError> + Send + 'static,
+{
+    fn receive(&mut self, context: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        self(context, message)
+    }
+}
+
+impl Process for Box<dyn Process> {
+    fn start(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        self.as_mut().start(context)
+    }
+
+    fn receive(&mut self, context: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        self.as_mut().receive(context, message)
+    }
+
+    fn signal(&mut self, context: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+        self.as_mut().signal(context, signal)
+    }
+}
@@ -0,0 +1,746 @@
+//! Nodes connect systems over TCP, so processes can send messages to processes on other machines.
+//!
+//! A connection starts with both nodes sending a handshake frame with their node identifier.
+//! Every frame is prefixed with its length as a 32-bit unsigned number, followed by a single byte tag:
+//!
+//! | Tag | Frame     | Payload                                                                  |
+//! |-----|-----------|--------------------------------------------------------------------------|
+//! | 1   | Handshake | The 64-bit identifier of the sending node.                               |
+//! | 2   | Send      | The recipient, the optional sender and the message.                      |
+//! | 3   | Call      | A 64-bit reference, followed by the same payload as a send.              |
+//! | 4   | Reply     | The reference of the call, followed by either 0 and a value or 1 and an error message as bytes. |
+//!
+//! Addresses and values use the [wire format](crate::runtime::wire); all numbers are in network byte order.
+//! A lost connection is detected when reading from or writing to it fails.
+
+use crate::runtime::process::system::{Envelope, Reply};
+use crate::runtime::process::{Address, Origin, System};
+use crate::runtime::wire::{self, Decoder, WireError};
+use crate::runtime::ByteString;
+use crate::{RuntimeError, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const HANDSHAKE: u8 = 1;
+const SEND: u8 = 2;
+const CALL: u8 = 3;
+const REPLY: u8 = 4;
+
+const OK: u8 = 0;
+const ERROR: u8 = 1;
+
+/// The maximum length in bytes of a single frame.
+const MAX_FRAME_LENGTH: usize = 64 * 1024 * 1024;
+
+/// How long to wait for the other node to send its handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An error that may occur while connecting to another node.
+#[derive(thiserror::Error, Debug)]
+pub enum NodeError {
+    #[error("Unable to communicate with another node. {0}")]
+    IO(#[from] io::Error),
+    #[error("Received a malformed frame from another node. {0}")]
+    Wire(#[from] WireError),
+    #[error("Only systems with a non-zero node identifier may connect to other nodes.")]
+    Anonymous,
+    #[error("Node {0} is either this node or is already connected.")]
+    DuplicateNode(u64),
+    #[error("Expected the other node to start with a handshake.")]
+    MissingHandshake,
+    #[error("A frame of {0} bytes exceeds the maximum frame length.")]
+    FrameTooLong(usize),
+}
+
+/// A unit of communication between two nodes.
+#[derive(Clone, Debug, PartialEq)]
+enum Frame {
+    Handshake(u64),
+    Send {
+        to: Address,
+        from: Option<Address>,
+        message: Value,
+    },
+    Call {
+        reference: u64,
+        to: Address,
+        from: Option<Address>,
+        message: Value,
+    },
+    Reply {
+        reference: u64,
+        result: Result<Value, String>,
+    },
+}
+
+impl Frame {
+    /// Encodes this frame along with its length prefix.
+    fn encode(&self) -> Result<Vec<u8>, WireError> {
+        let mut buffer = vec![0; 4];
+
+        match self {
+            Frame::Handshake(node) => {
+                buffer.push(HANDSHAKE);
+                buffer.extend_from_slice(&node.to_be_bytes());
+            }
+            Frame::Send { to, from, message } => {
+                buffer.push(SEND);
+                write_message(&mut buffer, to, from, message)?;
+            }
+            Frame::Call {
+                reference,
+                to,
+                from,
+                message,
+            } => {
+                buffer.push(CALL);
+                buffer.extend_from_slice(&reference.to_be_bytes());
+                write_message(&mut buffer, to, from, message)?;
+            }
+            Frame::Reply { reference, result } => {
+                buffer.push(REPLY);
+                buffer.extend_from_slice(&reference.to_be_bytes());
+
+                match result {
+                    Ok(value) => {
+                        buffer.push(OK);
+                        wire::write_value(&mut buffer, value)?;
+                    }
+                    Err(error) => {
+                        buffer.push(ERROR);
+                        wire::write_value(&mut buffer, &ByteString::from(error.as_bytes()).into())?;
+                    }
+                }
+            }
+        }
+
+        let length = buffer.len() - 4;
+
+        if length > MAX_FRAME_LENGTH {
+            return Err(WireError::TooLong(length));
+        }
+
+        buffer[..4].copy_from_slice(&(length as u32).to_be_bytes());
+
+        Ok(buffer)
+    }
+
+    /// Decodes a frame without its length prefix.
+    fn decode(bytes: &[u8]) -> Result<Self, WireError> {
+        let mut decoder = Decoder::new(bytes);
+        let frame = match decoder.read_u8()? {
+            HANDSHAKE => Frame::Handshake(decoder.read_u64()?),
+            SEND => {
+                let (to, from, message) = read_message(&mut decoder)?;
+
+                Frame::Send { to, from, message }
+            }
+            CALL => {
+                let reference = decoder.read_u64()?;
+                let (to, from, message) = read_message(&mut decoder)?;
+
+                Frame::Call {
+                    reference,
+                    to,
+                    from,
+                    message,
+                }
+            }
+            REPLY => {
+                let reference = decoder.read_u64()?;
+                let result = match decoder.read_u8()? {
+                    OK => Ok(decoder.read_value()?),
+                    ERROR => match decoder.read_value()? {
+                        Value::Bytes(bytes) => {
+                            Err(String::from_utf8_lossy(bytes.as_bytes()).into_owned())
+                        }
+                        _ => return Err(WireError::UnknownTag(ERROR)),
+                    },
+                    status => return Err(WireError::UnknownTag(status)),
+                };
+
+                Frame::Reply { reference, result }
+            }
+            tag => return Err(WireError::UnknownTag(tag)),
+        };
+
+        decoder.finish()?;
+
+        Ok(frame)
+    }
+
+    /// Reads a single length-prefixed frame from the stream.
+    fn read<R: Read>(reader: &mut R) -> Result<Self, NodeError> {
+        let mut length = [0; 4];
+
+        reader.read_exact(&mut length)?;
+
+        let length = u32::from_be_bytes(length) as usize;
+
+        if length > MAX_FRAME_LENGTH {
+            return Err(NodeError::FrameTooLong(length));
+        }
+
+        let mut bytes = vec![0; length];
+
+        reader.read_exact(&mut bytes)?;
+
+        Ok(Frame::decode(bytes.as_slice())?)
+    }
+}
+
+fn write_message(
+    buffer: &mut Vec<u8>,
+    to: &Address,
+    from: &Option<Address>,
+    message: &Value,
+) -> Result<(), WireError> {
+    wire::write_address(buffer, to);
+
+    match from {
+        Some(from) => {
+            buffer.push(1);
+            wire::write_address(buffer, from);
+        }
+        None => buffer.push(0),
+    }
+
+    wire::write_value(buffer, message)
+}
+
+fn read_message(decoder: &mut Decoder) -> Result<(Address, Option<Address>, Value), WireError> {
+    let to = decoder.read_address()?;
+    let from = match decoder.read_u8()? {
+        0 => None,
+        1 => Some(decoder.read_address()?),
+        byte => return Err(WireError::InvalidBoolean(byte)),
+    };
+
+    Ok((to, from, decoder.read_value()?))
+}
+
+/// An established connection to another node.
+pub(super) struct Connection {
+    node: u64,
+    stream: Mutex<TcpStream>,
+    pending: Mutex<HashMap<u64, Reply>>,
+    next: AtomicU64,
+}
+
+impl Connection {
+    fn new(node: u64, stream: TcpStream) -> Self {
+        Connection {
+            node,
+            stream: Mutex::new(stream),
+            pending: Default::default(),
+            next: AtomicU64::new(0),
+        }
+    }
+
+    /// The identifier of the node on the other end of this connection.
+    pub(super) fn node(&self) -> u64 {
+        self.node
+    }
+
+    fn stream(&self) -> MutexGuard<'_, TcpStream> {
+        self.stream
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn pending(&self) -> MutexGuard<'_, HashMap<u64, Reply>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self, frame: &Frame) -> Result<(), RuntimeError> {
+        let bytes = frame.encode()?;
+        let mut stream = self.stream();
+
+        stream.write_all(bytes.as_slice()).map_err(|error| {
+            debug!("Unable to write to node {}. {error}", self.node);
+            stream.shutdown(Shutdown::Both).ok();
+
+            RuntimeError::NodeDisconnected(self.node)
+        })
+    }
+
+    /// Sends a message to a process on the other node.
+    /// Replies to calls are sent once the other node responds or the connection is lost.
+    pub(super) fn send(&self, to: Address, envelope: Envelope) -> Result<(), RuntimeError> {
+        let Envelope {
+            message,
+            origin,
+            reply,
+        } = envelope;
+        let from = origin.sender();
+
+        let reply = match reply {
+            Some(reply) => reply,
+            None => return self.write(&Frame::Send { to, from, message }),
+        };
+
+        let reference = self.next.fetch_add(1, Ordering::Relaxed);
+        let frame = Frame::Call {
+            reference,
+            to,
+            from,
+            message,
+        };
+
+        // Registers the reply before writing, so a fast response cannot arrive before it is pending.
+        self.pending().insert(reference, reply);
+
+        self.write(&frame).inspect_err(|error| {
+            let reply = self.pending().remove(&reference);
+
+            if let Some(reply) = reply {
+                reply.send(Err(error.clone()));
+            }
+        })
+    }
+
+    /// Creates a [`Reply`] that sends the result of a call back to the other node.
+    fn reply(self: &Arc<Self>, reference: u64, to: Address) -> Reply {
+        let connection = Arc::clone(self);
+
+        Reply::new(to, move |result: Result<Value, RuntimeError>| {
+            let result = result.map_err(|error| error.to_string());
+
+            if let Err(error) = connection.write(&Frame::Reply { reference, result }) {
+                debug!(
+                    "Unable to reply to a call from node {}. {error}",
+                    connection.node
+                );
+            }
+        })
+    }
+
+    /// Closes the connection and fails all calls waiting on a reply.
+    pub(super) fn close(&self) {
+        self.stream().shutdown(Shutdown::Both).ok();
+
+        let pending: Vec<Reply> = self.pending().drain().map(|(_, reply)| reply).collect();
+
+        for reply in pending {
+            reply.send(Err(RuntimeError::NodeDisconnected(self.node)));
+        }
+    }
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
+/// Exchanges node identifiers with the other end of the stream and registers the connection.
+fn handshake(
+    system: &System,
+    mut stream: TcpStream,
+) -> Result<(Arc<Connection>, TcpStream), NodeError> {
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    stream.write_all(Frame::Handshake(system.node()).encode()?.as_slice())?;
+
+    let node = match Frame::read(&mut stream)? {
+        Frame::Handshake(0) => return Err(NodeError::Anonymous),
+        Frame::Handshake(node) if node == system.node() => {
+            return Err(NodeError::DuplicateNode(node))
+        }
+        Frame::Handshake(node) => node,
+        _ => return Err(NodeError::MissingHandshake),
+    };
+
+    stream.set_read_timeout(None)?;
+
+    let reader = stream.try_clone()?;
+    let connection = Arc::new(Connection::new(node, stream));
+
+    if !system.connected(Arc::clone(&connection)) {
+        connection.close();
+        return Err(NodeError::DuplicateNode(node));
+    }
+
+    Ok((connection, reader))
+}
+
+/// Handles the frames sent by the other node until the connection is lost.
+fn serve(system: System, connection: Arc<Connection>, mut reader: TcpStream) {
+    loop {
+        match Frame::read(&mut reader) {
+            Ok(Frame::Send { to, from, message }) => {
+                let envelope = Envelope {
+                    message,
+                    origin: Origin::new(from, None),
+                    reply: None,
+                };
+
+                system.receive(to, envelope);
+            }
+            Ok(Frame::Call {
+                reference,
+                to,
+                from,
+                message,
+            }) => {
+                let envelope = Envelope {
+                    message,
+                    origin: Origin::new(from, None),
+                    reply: Some(connection.reply(reference, to)),
+                };
+
+                system.receive(to, envelope);
+            }
+            Ok(Frame::Reply { reference, result }) => {
+                let reply = connection.pending().remove(&reference);
+
+                match reply {
+                    Some(reply) => {
+                        let address = reply.address();
+
+                        reply.send(result.map_err(|error| RuntimeError::Remote(address, error)));
+                    }
+                    None => warn!(
+                        "Received a reply from node {} for an unknown call.",
+                        connection.node()
+                    ),
+                }
+            }
+            Ok(Frame::Handshake(_)) => {
+                warn!(
+                    "Received an unexpected handshake from node {}.",
+                    connection.node()
+                );
+                break;
+            }
+            Err(NodeError::IO(error))
+                if matches!(
+                    error.kind(),
+                    ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset
+                ) =>
+            {
+                break
+            }
+            Err(error) => {
+                debug!("Unable to read from node {}. {error}", connection.node());
+                break;
+            }
+        }
+    }
+
+    system.disconnected(&connection);
+}
+
+/// Accepts connections from other nodes until it is closed or dropped.
+#[derive(Debug)]
+pub struct Listener {
+    address: SocketAddr,
+    closed: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Listener {
+    /// The local socket address other nodes can connect to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stops accepting connections from other nodes.
+    /// Established connections remain open.
+    pub fn close(self) {}
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+
+        let mut address = self.address;
+
+        if address.ip().is_unspecified() {
+            address.set_ip(match address {
+                SocketAddr::V4(_) => [127, 0, 0, 1].into(),
+                SocketAddr::V6(_) => [0, 0, 0, 0, 0, 0, 0, 1].into(),
+            });
+        }
+
+        // Wakes up the accepting thread so it can observe that the listener is closed.
+        TcpStream::connect(address).ok();
+
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl System {
+    /// Accepts connections from other nodes on the given socket address.
+    pub fn listen<A: ToSocketAddrs>(&self, address: A) -> Result<Listener, NodeError> {
+        if self.node() == 0 {
+            return Err(NodeError::Anonymous);
+        }
+
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let system = self.clone();
+        let accepting = Arc::clone(&closed);
+
+        let thread = thread::Builder::new()
+            .name(format!("listener-{address}"))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if accepting.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    match stream {
+                        Ok(stream) => system.accept(stream),
+                        Err(error) => warn!("Unable to accept a connection. {error}"),
+                    }
+                }
+            })?;
+
+        Ok(Listener {
+            address,
+            closed,
+            thread: Some(thread),
+        })
+    }
+
+    fn accept(&self, stream: TcpStream) {
+        let system = self.clone();
+        let thread =
+            thread::Builder::new()
+                .name("node".to_string())
+                .spawn(move || match handshake(&system, stream) {
+                    Ok((connection, reader)) => serve(system, connection, reader),
+                    Err(error) => warn!("Rejected a connection from another node. {error}"),
+                });
+
+        match thread {
+            Ok(thread) => self.track(thread),
+            Err(error) => warn!("Unable to spawn a thread for a connection. {error}"),
+        }
+    }
+
+    /// Connects to the node listening on the given socket address.
+    /// Returns the identifier of the connected node.
+    pub fn connect<A: ToSocketAddrs>(&self, address: A) -> Result<u64, NodeError> {
+        if self.node() == 0 {
+            return Err(NodeError::Anonymous);
+        }
+
+        let (connection, reader) = handshake(self, TcpStream::connect(address)?)?;
+        let node = connection.node();
+        let system = self.clone();
+
+        let thread = thread::Builder::new()
+            .name(format!("node-{node}"))
+            .spawn(move || serve(system, connection, reader))?;
+
+        self.track(thread);
+
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::process::{Context, ExitReason, Process, Signal};
+    use crate::runtime::Tolerance;
+    use std::sync::mpsc::{channel, Sender};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn echo(_: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        Ok(message)
+    }
+
+    fn nodes() -> (System, System, Listener) {
+        let local = System::new(1);
+        let remote = System::new(2);
+        let listener = remote.listen("127.0.0.1:0").unwrap();
+
+        assert_eq!(local.connect(listener.local_addr()).unwrap(), 2);
+
+        (local, remote, listener)
+    }
+
+    /// Reports every signal it receives to the given channel.
+    struct Watcher(Sender<Signal>);
+
+    impl Process for Watcher {
+        fn receive(&mut self, _: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+            Ok(message)
+        }
+
+        fn signal(&mut self, _: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+            self.0.send(signal).ok();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn frame_round_trip() {
+        let frames = [
+            Frame::Handshake(42),
+            Frame::Send {
+                to: Address::new(1, 2),
+                from: None,
+                message: Value::from(vec![0xff_u8]),
+            },
+            Frame::Call {
+                reference: 7,
+                to: Address::new(1, 2),
+                from: Some(Address::new(3, 4)),
+                message: Value::from(Tolerance::new(1, 0.5)),
+            },
+            Frame::Reply {
+                reference: 7,
+                result: Err("Failed.".to_string()),
+            },
+        ];
+
+        for frame in frames {
+            let bytes = frame.encode().unwrap();
+
+            assert_eq!(Frame::read(&mut bytes.as_slice()).unwrap(), frame);
+        }
+    }
+
+    #[test]
+    fn call_remote_process() {
+        let (local, remote, _listener) = nodes();
+        let address = remote.spawn(echo);
+        let message = Value::from(vec![
+            Value::from(Tolerance::new(3, 0.25)),
+            Value::from(vec![1_u8, 2, 3]),
+            Value::from(address),
+        ]);
+
+        assert_eq!(address.node(), 2);
+        assert_eq!(local.call(address, message.clone()), Ok(message));
+
+        local.shutdown();
+        remote.shutdown();
+    }
+
+    #[test]
+    fn remote_origin() {
+        let (local, remote, _listener) = nodes();
+        let sender = remote.spawn(|context: &mut Context, _: Value| {
+            Ok(context
+                .origin()
+                .sender()
+                .map(Value::from)
+                .unwrap_or_default())
+        });
+        let forwarder =
+            local.spawn(move |context: &mut Context, message: Value| context.call(sender, message));
+
+        assert_eq!(local.call(forwarder, Value::Unit), Ok(forwarder.into()));
+
+        local.shutdown();
+        remote.shutdown();
+    }
+
+    #[test]
+    fn remote_error() {
+        let (local, remote, _listener) = nodes();
+        let address = remote.spawn(|_: &mut Context, message: Value| {
+            Err(RuntimeError::UnexpectedType(message, "nothing".to_string()))
+        });
+        let missing = Address::new(2, 42);
+
+        assert!(matches!(
+            local.call(address, 1.into()),
+            Err(RuntimeError::Remote(failed, _)) if failed == address
+        ));
+        assert!(matches!(
+            local.call(missing, 1.into()),
+            Err(RuntimeError::Remote(failed, _)) if failed == missing
+        ));
+        assert_eq!(
+            local.send(Address::new(3, 1), Value::Unit),
+            Err(RuntimeError::ProcessNotFound(Address::new(3, 1)))
+        );
+
+        local.shutdown();
+        remote.shutdown();
+    }
+
+    #[test]
+    fn detect_disconnect() {
+        let (local, remote, _listener) = nodes();
+        let (sender, receiver) = channel();
+        let watcher = local.spawn(Watcher(sender));
+        let address = remote.spawn(echo);
+
+        local.monitor_node(watcher, 2).unwrap();
+        remote.shutdown();
+
+        assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(Signal::NodeDown(2)));
+        assert!(local.nodes().is_empty());
+        assert_eq!(
+            local.send(address, Value::Unit),
+            Err(RuntimeError::ProcessNotFound(address))
+        );
+
+        local.shutdown();
+    }
+
+    #[test]
+    fn disconnect_fails_pending_calls() {
+        let (local, remote, _listener) = nodes();
+        let (received, receiver) = channel();
+        let (release, blocked) = channel::<()>();
+        let blocked = Mutex::new(blocked);
+        let address = remote.spawn(move |_: &mut Context, message: Value| {
+            received.send(()).ok();
+            blocked.lock().unwrap().recv().ok();
+            Ok(message)
+        });
+
+        let caller = local.clone();
+        let call = thread::spawn(move || caller.call(address, Value::Unit));
+
+        receiver.recv_timeout(TIMEOUT).unwrap();
+
+        assert!(local.disconnect(2));
+        assert_eq!(call.join().unwrap(), Err(RuntimeError::NodeDisconnected(2)));
+
+        release.send(()).unwrap();
+        local.shutdown();
+        remote.shutdown();
+    }
+
+    #[test]
+    fn reject_duplicate_nodes() {
+        let system = System::new(1);
+        let listener = system.listen("127.0.0.1:0").unwrap();
+
+        assert!(matches!(
+            System::new(1).connect(listener.local_addr()),
+            Err(NodeError::DuplicateNode(1))
+        ));
+        assert!(matches!(
+            System::default().listen("127.0.0.1:0"),
+            Err(NodeError::Anonymous)
+        ));
+
+        listener.close();
+        system.shutdown();
+    }
+}
@@ -0,0 +1,378 @@
+//! Built-in procedures that let Tortuga code spawn processes and send messages to them.
+//!
+//! - `spawn!(handler)` spawns a process that handles each message by calling `handler` and returns its address.
+//! - `send!(address, message...)` sends a message without waiting for a reply.
+//! - `call!(address, message...)` sends a message and returns the reply.
+//! - `self!()` is the address of the current process.
+//! - `link!(address)` links the current process to another, so that either one exits when the other fails.
+//! - `supervise!(strategy, handler...)` spawns a [`Supervisor`] with a child for each handler and returns its address.
+//!   The strategies are numbered `1` (one-for-one), `2` (one-for-all) and `3` (rest-for-one).
+//!   Calling a supervisor with an index (e.g., `call!(supervisor, 0)`) returns the address of that child.
+//!
+//! A message with more than one value is sent as a tuple, and a tuple is spread into the arguments of a handler.
+//! So, `send!(counter, 1, 2)` calls the handler of `counter` as `handler(1, 2)`.
+//!
+//! Built-in procedures act on behalf of the process the calling code runs in (e.g., as the sender of a message),
+//! and messages record the location of the procedure call that sent them.
+//! Code outside of any process (e.g., the top-level of a program) runs in the interpreter's [`System`].
+
+use crate::compiler::Location;
+use crate::runtime::process::{
+    Address, ChildSpecification, Context, Origin, Process, Strategy, Supervisor, System,
+};
+use crate::runtime::{Function, FunctionReference, Tuple};
+use crate::{RuntimeError, Value};
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Current>> = const { RefCell::new(None) };
+    static LOCATION: Cell<Option<Location>> = const { Cell::new(None) };
+}
+
+/// Where the Tortuga code on the current thread is running.
+#[derive(Clone, Debug)]
+pub(crate) enum Current {
+    /// Outside of any process.
+    System(System),
+    /// Within a process.
+    Process(Context),
+}
+
+impl Current {
+    /// The [`System`] the current code is running in.
+    pub(crate) fn system(&self) -> &System {
+        match self {
+            Current::System(system) => system,
+            Current::Process(context) => context.system(),
+        }
+    }
+
+    /// The [`Address`] of the current process, if any.
+    pub(crate) fn address(&self) -> Option<Address> {
+        match self {
+            Current::System(_) => None,
+            Current::Process(context) => Some(context.address()),
+        }
+    }
+
+    /// The [`Context`] of the current process.
+    /// Fails when the current code is not running in a process.
+    pub(crate) fn context(&self, procedure: &str) -> Result<&Context, RuntimeError> {
+        match self {
+            Current::System(_) => Err(RuntimeError::OutsideProcess(procedure.to_string())),
+            Current::Process(context) => Ok(context),
+        }
+    }
+
+    fn origin(&self) -> Origin {
+        Origin::new(self.address(), LOCATION.with(Cell::get))
+    }
+}
+
+/// Restores the previous [`Current`] when dropped, even when the code it ran panicked.
+struct Guard(Option<Current>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Runs the given closure with [`Current`] as where the code on this thread is running.
+pub(crate) fn enter<F, R>(current: Current, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = Guard(CURRENT.with(|previous| previous.borrow_mut().replace(current)));
+
+    f()
+}
+
+/// Runs the given closure as a procedure called at the given [`Location`].
+pub(crate) fn at<F, R>(location: Location, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous = LOCATION.with(|current| current.replace(Some(location)));
+    let result = f();
+
+    LOCATION.with(|current| current.set(previous));
+
+    result
+}
+
+/// Where the Tortuga code on this thread is running.
+/// Fails when no Tortuga code is running on this thread.
+pub(crate) fn current(procedure: &str) -> Result<Current, RuntimeError> {
+    CURRENT
+        .with(|current| current.borrow().clone())
+        .ok_or_else(|| RuntimeError::OutsideProcess(procedure.to_string()))
+}
+
+/// A [`Process`] that handles each message by calling a Tortuga function or procedure.
+/// The result of the call is the reply to the message.
+#[derive(Clone, Debug)]
+pub struct FunctionProcess {
+    handler: FunctionReference,
+}
+
+impl FunctionProcess {
+    /// Creates a new [`FunctionProcess`] that handles messages with the referenced function.
+    pub fn new(handler: FunctionReference) -> Self {
+        FunctionProcess { handler }
+    }
+}
+
+impl Process for FunctionProcess {
+    fn receive(&mut self, context: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        let arguments = match message {
+            Value::Tuple(tuple) => tuple.as_slice().to_vec(),
+            message => vec![message],
+        };
+
+        enter(Current::Process(context.clone()), || {
+            Function::call(&self.handler, arguments.as_slice())
+        })
+    }
+}
+
+/// Combines the values of a message into a single [`Value`].
+fn message(values: &[Value]) -> Value {
+    match values {
+        [value] => value.clone(),
+        values => Value::from(Tuple::from(values)),
+    }
+}
+
+pub(crate) fn spawn(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    let handler = match arguments {
+        [Value::FunctionReference(handler)] => handler.clone(),
+        _ => return None,
+    };
+
+    Some(current("@spawn!").map(|current| {
+        let address = current.system().spawn(FunctionProcess::new(handler));
+
+        Value::from(address)
+    }))
+}
+
+pub(crate) fn send(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    let (address, values) = match arguments {
+        [Value::Address(address), values @ ..] if !values.is_empty() => (*address, values),
+        _ => return None,
+    };
+
+    Some(current("@send!").and_then(|current| {
+        current
+            .system()
+            .send_from(address, message(values), current.origin())
+            .map(|_| Value::Unit)
+    }))
+}
+
+pub(crate) fn call(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    let (address, values) = match arguments {
+        [Value::Address(address), values @ ..] if !values.is_empty() => (*address, values),
+        _ => return None,
+    };
+
+    Some(current("@call!").and_then(|current| {
+        current
+            .system()
+            .call_from(address, message(values), current.origin())
+    }))
+}
+
+pub(crate) fn address(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    if !arguments.is_empty() {
+        return None;
+    }
+
+    Some(current("@self!").and_then(|current| {
+        current
+            .context("@self!")
+            .map(|context| Value::from(context.address()))
+    }))
+}
+
+pub(crate) fn link(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    let address = match arguments {
+        [Value::Address(address)] => *address,
+        _ => return None,
+    };
+
+    Some(current("@link!").and_then(|current| {
+        current
+            .context("@link!")?
+            .link(address)
+            .map(|_| Value::Unit)
+    }))
+}
+
+pub(crate) fn supervise(arguments: &[Value]) -> Option<Result<Value, RuntimeError>> {
+    let (strategy, handlers) = match arguments {
+        [strategy, handlers @ ..] if !handlers.is_empty() => {
+            (Strategy::try_from(strategy).ok()?, handlers)
+        }
+        _ => return None,
+    };
+
+    let mut supervisor = Supervisor::new(strategy);
+
+    for handler in handlers {
+        let handler = match handler {
+            Value::FunctionReference(handler) => handler.clone(),
+            _ => return None,
+        };
+        let name = handler.function().to_string();
+
+        supervisor = supervisor.with_child(ChildSpecification::new(name.as_str(), move || {
+            FunctionProcess::new(handler.clone())
+        }));
+    }
+
+    Some(current("@supervise!").map(|current| Value::from(current.system().spawn(supervisor))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn spawn_and_call() {
+        let source = r###"
+            @add(@x, @y) = x + y
+            @p = spawn!(add)
+            call!(p, 40, 2)
+        "###;
+
+        assert_eq!(Interpreter::build_then_run(source), Ok(42.into()));
+    }
+
+    #[test]
+    fn send_from_process() {
+        let source = r###"
+            @echo(@x) = x
+            @forward!(@to, @x) = send!(to, x)
+            @p = spawn!(forward)
+            call!(p, spawn!(echo), 1)
+        "###;
+
+        assert_eq!(Interpreter::build_then_run(source), Ok(Value::Unit));
+    }
+
+    #[test]
+    fn address() {
+        let mut interpreter = Interpreter::default();
+        let source = r###"
+            @whoami!(@x) = self!()
+            @p = spawn!(whoami)
+            call!(p, 0)
+        "###;
+
+        let address = interpreter.run(source.parse().unwrap());
+
+        assert!(matches!(address, Ok(Value::Address(_))));
+        assert_eq!(interpreter.run("p".parse().unwrap()), address);
+        assert_eq!(
+            Interpreter::build_then_run("self!()"),
+            Err(RuntimeError::OutsideProcess("@self!".to_string()))
+        );
+
+        interpreter.system().shutdown();
+    }
+
+    #[test]
+    fn not_in_interpreter() {
+        let reference = FunctionReference::from(Function::builtin_procedure("spawn", spawn));
+
+        assert_eq!(
+            Function::call(&reference, &[reference.clone().into()]),
+            Err(RuntimeError::OutsideProcess("@spawn!".to_string()))
+        );
+    }
+
+    /// Waits for the given condition to hold, which depends on other processes.
+    fn eventually<F: FnMut() -> bool>(mut condition: F) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        false
+    }
+
+    fn address_of(interpreter: &mut Interpreter, name: &str) -> Address {
+        match interpreter.run(name.parse().unwrap()) {
+            Ok(Value::Address(address)) => address,
+            result => panic!("Expected {name} to be an address, found {result:?}."),
+        }
+    }
+
+    #[test]
+    fn link() {
+        let mut interpreter = Interpreter::default();
+        let source = r###"
+            @fragile(@x > 0) = x
+            @linker!(@x) = link!(x)
+            @f = spawn!(fragile)
+            @l = spawn!(linker)
+            call!(l, f)
+        "###;
+
+        assert_eq!(interpreter.run(source.parse().unwrap()), Ok(Value::Unit));
+
+        let system = interpreter.system().clone();
+        let fragile = address_of(&mut interpreter, "f");
+        let linker = address_of(&mut interpreter, "l");
+
+        assert!(system.call(fragile, (-1).into()).is_err());
+        assert!(eventually(|| !system.is_alive(linker)));
+        assert_eq!(
+            interpreter.run("link!(f)".parse().unwrap()),
+            Err(RuntimeError::OutsideProcess("@link!".to_string()))
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn supervise() {
+        let mut interpreter = Interpreter::default();
+        let source = r###"
+            @worker(@x > 0) = x
+            @s = supervise!(1, worker, worker)
+            @w = call!(s, 0)
+            call!(w, 1) + call!(call!(s, 1), 2)
+        "###;
+
+        assert_eq!(interpreter.run(source.parse().unwrap()), Ok(3.into()));
+
+        let system = interpreter.system().clone();
+        let supervisor = address_of(&mut interpreter, "s");
+        let worker = address_of(&mut interpreter, "w");
+
+        assert!(system.call(worker, (-1).into()).is_err());
+        assert!(eventually(|| matches!(
+            system.call(supervisor, 0.into()),
+            Ok(Value::Address(restarted)) if restarted != worker
+        )));
+        assert_eq!(
+            interpreter.run("call!(call!(s, 0), 4)".parse().unwrap()),
+            Ok(4.into())
+        );
+
+        system.shutdown();
+    }
+}
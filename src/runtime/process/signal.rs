@@ -0,0 +1,51 @@
+//! Signals are sent by the runtime to notify processes when a linked or monitored process exits,
+//! or when the connection to a monitored node is lost.
+
+use crate::runtime::process::Address;
+use crate::RuntimeError;
+use std::fmt;
+
+/// The reason a process exited.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExitReason {
+    /// The process was stopped after handling all of its messages.
+    Normal,
+    /// The process failed with a [`RuntimeError`].
+    Error(RuntimeError),
+    /// The process was killed by the runtime.
+    Killed,
+}
+
+impl ExitReason {
+    /// Tests whether this is a [`ExitReason::Normal`] exit.
+    pub fn is_normal(&self) -> bool {
+        matches!(self, ExitReason::Normal)
+    }
+}
+
+impl From<RuntimeError> for ExitReason {
+    fn from(error: RuntimeError) -> Self {
+        ExitReason::Error(error)
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExitReason::Normal => f.write_str("normal"),
+            ExitReason::Error(error) => write!(f, "{}", error),
+            ExitReason::Killed => f.write_str("killed"),
+        }
+    }
+}
+
+/// A notification from the runtime about another process or node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    /// A linked process exited.
+    Exit(Address, ExitReason),
+    /// A monitored process exited.
+    Down(Address, ExitReason),
+    /// The connection to a monitored node was lost.
+    NodeDown(u64),
+}
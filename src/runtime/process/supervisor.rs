@@ -0,0 +1,490 @@
+//! Supervisors start, monitor and restart other processes.
+
+use crate::runtime::process::{Address, Context, ExitReason, Process, Signal};
+use crate::{RuntimeError, Value};
+use std::any::type_name;
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Which children a [`Supervisor`] restarts when one of them exits.
+/// Tortuga code numbers the strategies in order, starting from `1` for [`Strategy::OneForOne`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    /// Only the exited child is restarted.
+    OneForOne = 1,
+    /// All children are restarted.
+    OneForAll = 2,
+    /// The exited child and the children started after it are restarted.
+    RestForOne = 3,
+}
+
+impl TryFrom<&Value> for Strategy {
+    type Error = RuntimeError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let strategy = match value {
+            Value::Number(number) if number.to_f64().fract() == 0.0 => {
+                match number.to_f64() as i64 {
+                    1 => Some(Strategy::OneForOne),
+                    2 => Some(Strategy::OneForAll),
+                    3 => Some(Strategy::RestForOne),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        strategy.ok_or_else(|| {
+            RuntimeError::UnexpectedType(value.clone(), type_name::<Self>().to_string())
+        })
+    }
+}
+
+/// When a child of a [`Supervisor`] is restarted.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Restart {
+    /// The child is always restarted.
+    #[default]
+    Permanent,
+    /// The child is restarted only if it exits abnormally.
+    Transient,
+    /// The child is never restarted.
+    Temporary,
+}
+
+impl Restart {
+    fn applies(&self, reason: &ExitReason) -> bool {
+        match self {
+            Restart::Permanent => true,
+            Restart::Transient => !reason.is_normal(),
+            Restart::Temporary => false,
+        }
+    }
+}
+
+/// Describes how to start a child of a [`Supervisor`] and when to restart it.
+pub struct ChildSpecification {
+    name: String,
+    start: Box<dyn FnMut() -> Box<dyn Process> + Send>,
+    restart: Restart,
+}
+
+impl ChildSpecification {
+    /// Creates a [`Restart::Permanent`] child that is started by calling the given function.
+    pub fn new<F, P>(name: &str, mut start: F) -> Self
+    where
+        F: FnMut() -> P + Send + 'static,
+        P: Process,
+    {
+        ChildSpecification {
+            name: name.to_string(),
+            start: Box::new(move || Box::new(start())),
+            restart: Restart::default(),
+        }
+    }
+
+    /// Sets when the child is restarted.
+    pub fn with_restart(mut self, restart: Restart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    /// The name of the child.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// When the child is restarted.
+    pub fn restart(&self) -> Restart {
+        self.restart
+    }
+}
+
+impl fmt::Debug for ChildSpecification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChildSpecification")
+            .field("name", &self.name)
+            .field("restart", &self.restart)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct Child {
+    specification: ChildSpecification,
+    address: Option<Address>,
+}
+
+/// A [`Process`] that starts its children linked to itself and restarts them according to a [`Strategy`].
+/// If more than the maximum number of restarts happen within the period,
+/// the supervisor kills its remaining children and exits with [`RuntimeError::RestartIntensityExceeded`].
+///
+/// Exits of linked processes that are not its children are logged and otherwise ignored.
+///
+/// Any message sent to a supervisor is answered with a tuple of the addresses of its children, in order,
+/// except for a (zero-based) index, which is answered with the address of the child at that index.
+/// Children that are not running (or do not exist) are represented by [`Value::Unit`].
+#[derive(Debug)]
+pub struct Supervisor {
+    strategy: Strategy,
+    intensity: usize,
+    period: Duration,
+    children: Vec<Child>,
+    restarts: VecDeque<Instant>,
+}
+
+impl Supervisor {
+    /// Creates a [`Supervisor`] with no children that allows 3 restarts every 5 seconds.
+    pub fn new(strategy: Strategy) -> Self {
+        Supervisor {
+            strategy,
+            intensity: 3,
+            period: Duration::from_secs(5),
+            children: Vec::new(),
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Sets the maximum number of restarts allowed within the given period.
+    pub fn with_intensity(mut self, intensity: usize, period: Duration) -> Self {
+        self.intensity = intensity;
+        self.period = period;
+        self
+    }
+
+    /// Adds a child to be started after the ones already added.
+    pub fn with_child(mut self, specification: ChildSpecification) -> Self {
+        self.children.push(Child {
+            specification,
+            address: None,
+        });
+        self
+    }
+
+    fn start_child(&mut self, context: &mut Context, index: usize) {
+        let child = &mut self.children[index];
+        let process = (child.specification.start)();
+
+        child.address = Some(context.spawn_link(process));
+    }
+
+    /// Unlinks and kills the running children from the given index onwards.
+    fn terminate_children(&mut self, context: &mut Context, from: usize) {
+        for child in self.children[from..].iter_mut() {
+            if let Some(address) = child.address.take() {
+                context.unlink(address);
+                context.system().kill(address).ok();
+            }
+        }
+    }
+
+    /// Records a restart and tests whether the restart intensity has been exceeded.
+    fn exceeded_intensity(&mut self) -> bool {
+        let now = Instant::now();
+
+        while let Some(restart) = self.restarts.front() {
+            if now.duration_since(*restart) > self.period {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        self.restarts.push_back(now);
+        self.restarts.len() > self.intensity
+    }
+
+    fn child_exited(
+        &mut self,
+        context: &mut Context,
+        index: usize,
+        reason: ExitReason,
+    ) -> Result<(), ExitReason> {
+        self.children[index].address = None;
+
+        let restart = self.children[index].specification.restart;
+
+        if restart == Restart::Temporary {
+            self.children.remove(index);
+        }
+
+        if !restart.applies(&reason) {
+            return Ok(());
+        }
+
+        if self.exceeded_intensity() {
+            self.terminate_children(context, 0);
+
+            return Err(RuntimeError::RestartIntensityExceeded(context.address()).into());
+        }
+
+        let from = match self.strategy {
+            Strategy::OneForOne => {
+                self.start_child(context, index);
+                return Ok(());
+            }
+            Strategy::OneForAll => 0,
+            Strategy::RestForOne => index,
+        };
+
+        self.terminate_children(context, from);
+
+        for index in from..self.children.len() {
+            self.start_child(context, index);
+        }
+
+        Ok(())
+    }
+}
+
+impl Process for Supervisor {
+    fn start(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        for index in 0..self.children.len() {
+            self.start_child(context, index);
+        }
+
+        Ok(())
+    }
+
+    fn receive(&mut self, _context: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        let mut addresses = self
+            .children
+            .iter()
+            .map(|child| child.address.map(Value::from).unwrap_or_default());
+
+        match message {
+            Value::Number(index) if index.to_f64() >= 0.0 && index.to_f64().fract() == 0.0 => {
+                Ok(addresses.nth(index.to_f64() as usize).unwrap_or_default())
+            }
+            _ => Ok(addresses.collect::<Vec<Value>>().into()),
+        }
+    }
+
+    fn signal(&mut self, context: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+        match signal {
+            Signal::Exit(address, reason) => {
+                match self
+                    .children
+                    .iter()
+                    .position(|child| child.address == Some(address))
+                {
+                    Some(index) => self.child_exited(context, index, reason),
+                    None if reason.is_normal() => Ok(()),
+                    None => {
+                        warn!(
+                            "Supervisor {} ignored the exit of process {address}, which is not one of its children: {reason}",
+                            context.address()
+                        );
+
+                        Ok(())
+                    }
+                }
+            }
+            Signal::Down(..) | Signal::NodeDown(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::process::System;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn worker() -> impl Process {
+        |context: &mut Context, message: Value| match message {
+            Value::Unit => {
+                context.system().stop(context.address());
+                Ok(Value::Unit)
+            }
+            Value::Boolean(true) => Ok(message),
+            _ => Err(RuntimeError::UnexpectedType(message, "Boolean".to_string())),
+        }
+    }
+
+    /// Reports every signal it receives to the given channel.
+    struct Watcher(Sender<Signal>);
+
+    impl Process for Watcher {
+        fn receive(&mut self, _: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+            Ok(message)
+        }
+
+        fn signal(&mut self, _: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+            self.0.send(signal).ok();
+            Ok(())
+        }
+    }
+
+    fn watcher(system: &System) -> (Address, Receiver<Signal>) {
+        let (sender, receiver) = channel();
+
+        (system.spawn(Watcher(sender)), receiver)
+    }
+
+    fn children(system: &System, supervisor: Address) -> Vec<Value> {
+        match system.call(supervisor, Value::Unit) {
+            Ok(Value::Tuple(children)) => children.iter().cloned().collect(),
+            result => panic!("Unexpected reply from supervisor: {:?}", result),
+        }
+    }
+
+    fn address(child: &Value) -> Address {
+        Address::try_from(child.clone()).unwrap()
+    }
+
+    /// Sends a message to a child and waits for the supervisor to be notified of its exit.
+    fn exit_child(system: &System, child: Address, message: Value) {
+        let (watcher, receiver) = watcher(system);
+
+        system.monitor(watcher, child).unwrap();
+        system.send(child, message).unwrap();
+        assert!(matches!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Down(address, _)) if address == child
+        ));
+    }
+
+    fn supervisor(strategy: Strategy) -> Supervisor {
+        Supervisor::new(strategy)
+            .with_child(ChildSpecification::new("a", worker))
+            .with_child(ChildSpecification::new("b", worker))
+            .with_child(ChildSpecification::new("c", worker))
+    }
+
+    #[test]
+    fn one_for_one() {
+        let system = System::default();
+        let supervisor = system.spawn(supervisor(Strategy::OneForOne));
+        let before = children(&system, supervisor);
+
+        exit_child(&system, address(&before[1]), 1.into());
+
+        let after = children(&system, supervisor);
+
+        assert_eq!(before[0], after[0]);
+        assert_ne!(before[1], after[1]);
+        assert_eq!(before[2], after[2]);
+        assert_eq!(
+            system.call(address(&after[1]), true.into()),
+            Ok(true.into())
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn one_for_all() {
+        let system = System::default();
+        let supervisor = system.spawn(supervisor(Strategy::OneForAll));
+        let before = children(&system, supervisor);
+
+        exit_child(&system, address(&before[1]), 1.into());
+
+        let after = children(&system, supervisor);
+
+        for (before, after) in before.iter().zip(after.iter()) {
+            assert_ne!(before, after);
+        }
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn rest_for_one() {
+        let system = System::default();
+        let supervisor = system.spawn(supervisor(Strategy::RestForOne));
+        let before = children(&system, supervisor);
+
+        exit_child(&system, address(&before[1]), 1.into());
+
+        let after = children(&system, supervisor);
+
+        assert_eq!(before[0], after[0]);
+        assert_ne!(before[1], after[1]);
+        assert_ne!(before[2], after[2]);
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn supervisor_survives_linked_process() {
+        let system = System::default();
+        let supervisor = system.spawn(supervisor(Strategy::OneForOne));
+        let before = children(&system, supervisor);
+        let stranger = system.spawn(worker());
+
+        system.link(supervisor, stranger).unwrap();
+        exit_child(&system, stranger, 1.into());
+
+        assert_eq!(children(&system, supervisor), before);
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn restart_types() {
+        let system = System::default();
+        let supervisor = system.spawn(
+            Supervisor::new(Strategy::OneForOne)
+                .with_child(ChildSpecification::new("permanent", worker))
+                .with_child(
+                    ChildSpecification::new("transient", worker).with_restart(Restart::Transient),
+                )
+                .with_child(
+                    ChildSpecification::new("temporary", worker).with_restart(Restart::Temporary),
+                ),
+        );
+        let before = children(&system, supervisor);
+
+        exit_child(&system, address(&before[0]), Value::Unit);
+        exit_child(&system, address(&before[1]), Value::Unit);
+        exit_child(&system, address(&before[2]), 1.into());
+
+        let after = children(&system, supervisor);
+
+        assert_eq!(after.len(), 2);
+        assert_ne!(before[0], after[0]);
+        assert_eq!(after[1], Value::Unit);
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn restart_intensity() {
+        let system = System::default();
+        let (watcher, receiver) = watcher(&system);
+        let supervisor = system.spawn(
+            Supervisor::new(Strategy::OneForOne)
+                .with_intensity(1, Duration::from_secs(60))
+                .with_child(ChildSpecification::new("a", worker)),
+        );
+
+        system.monitor(watcher, supervisor).unwrap();
+
+        exit_child(
+            &system,
+            address(&children(&system, supervisor)[0]),
+            1.into(),
+        );
+        exit_child(
+            &system,
+            address(&children(&system, supervisor)[0]),
+            1.into(),
+        );
+
+        assert_eq!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Down(
+                supervisor,
+                RuntimeError::RestartIntensityExceeded(supervisor).into()
+            ))
+        );
+
+        system.shutdown();
+    }
+}
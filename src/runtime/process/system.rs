@@ -0,0 +1,827 @@
+//! A system runs processes and routes messages between them.
+
+use crate::compiler::Location;
+use crate::runtime::process::node::Connection;
+use crate::runtime::process::{Address, ExitReason, Process, Signal};
+use crate::{RuntimeError, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use tracing::{debug, warn};
+
+/// Where a message was sent from.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Origin {
+    sender: Option<Address>,
+    location: Option<Location>,
+}
+
+impl Origin {
+    /// Creates a new [`Origin`] for a message.
+    pub fn new(sender: Option<Address>, location: Option<Location>) -> Self {
+        Origin { sender, location }
+    }
+
+    /// The [`Address`] of the process that sent the message, if it was sent by a process.
+    pub fn sender(&self) -> Option<Address> {
+        self.sender
+    }
+
+    /// The [`Location`] in the source code that sent the message, if known.
+    pub fn location(&self) -> Option<Location> {
+        self.location
+    }
+}
+
+type Callback = Box<dyn FnOnce(Result<Value, RuntimeError>) + Send>;
+
+/// A callback for the result of handling a message.
+/// Dropping a [`Reply`] without sending a result replies that the process was not found,
+/// so callers never wait on messages that were discarded.
+pub(super) struct Reply {
+    address: Address,
+    callback: Option<Callback>,
+}
+
+impl Reply {
+    /// Creates a [`Reply`] for a message sent to the process at the given [`Address`].
+    pub(super) fn new<F>(address: Address, callback: F) -> Self
+    where
+        F: FnOnce(Result<Value, RuntimeError>) + Send + 'static,
+    {
+        Reply {
+            address,
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    /// The [`Address`] of the process the message was sent to.
+    pub(super) fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Sends the result of handling the message.
+    pub(super) fn send(mut self, result: Result<Value, RuntimeError>) {
+        if let Some(callback) = self.callback.take() {
+            callback(result);
+        }
+    }
+}
+
+impl Drop for Reply {
+    fn drop(&mut self) {
+        if let Some(callback) = self.callback.take() {
+            callback(Err(RuntimeError::ProcessNotFound(self.address)));
+        }
+    }
+}
+
+impl fmt::Debug for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reply")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+/// A message along with its [`Origin`] and where to send the reply, if the sender expects one.
+#[derive(Debug)]
+pub(super) struct Envelope {
+    pub(super) message: Value,
+    pub(super) origin: Origin,
+    pub(super) reply: Option<Reply>,
+}
+
+/// An item delivered to the mailbox of a process.
+#[derive(Debug)]
+enum Delivery {
+    Message(Envelope),
+    Signal(Signal),
+    Kill,
+}
+
+/// The mailboxes, names, links and monitors of the running processes,
+/// along with the connections to other nodes.
+#[derive(Debug, Default)]
+struct Registry {
+    next: u64,
+    mailboxes: HashMap<Address, Sender<Delivery>>,
+    names: HashMap<String, Address>,
+    links: HashMap<Address, HashSet<Address>>,
+    monitors: HashMap<Address, HashSet<Address>>,
+    nodes: HashMap<u64, Arc<Connection>>,
+    node_monitors: HashMap<u64, HashSet<Address>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Registry {
+    fn link(&mut self, a: Address, b: Address) {
+        self.links.entry(a).or_default().insert(b);
+        self.links.entry(b).or_default().insert(a);
+    }
+}
+
+/// Runs each spawned [`Process`] on its own thread and delivers messages to their mailboxes.
+/// Cloning a [`System`] creates a new handle to the same set of processes.
+///
+/// Messages sent to addresses on other nodes are forwarded over the connection to that node.
+/// See [`System::listen`] and [`System::connect`].
+///
+/// # Example
+/// ```rust
+/// use tortuga::runtime::process::{Context, System};
+/// use tortuga::{RuntimeError, Value};
+///
+/// let system = System::default();
+/// let double = system.spawn(|_: &mut Context, message: Value| -> Result<Value, RuntimeError> {
+///     message * Value::from(2)
+/// });
+///
+/// assert_eq!(system.call(double, 21.into()), Ok(42.into()));
+///
+/// system.shutdown();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct System {
+    node: u64,
+    registry: Arc<Mutex<Registry>>,
+}
+
+/// The view of the [`System`] from within a running [`Process`].
+#[derive(Clone, Debug)]
+pub struct Context {
+    address: Address,
+    origin: Origin,
+    system: System,
+}
+
+impl Context {
+    /// The [`Address`] of the current process.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// The [`Origin`] of the message currently being received.
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+
+    /// The [`System`] the current process is running in.
+    pub fn system(&self) -> &System {
+        &self.system
+    }
+
+    /// Sends a message from the current process without waiting for a reply.
+    pub fn send(&self, address: Address, message: Value) -> Result<(), RuntimeError> {
+        self.system
+            .send_from(address, message, Origin::new(Some(self.address), None))
+    }
+
+    /// Sends a message from the current process and waits for the reply.
+    pub fn call(&self, address: Address, message: Value) -> Result<Value, RuntimeError> {
+        self.system
+            .call_from(address, message, Origin::new(Some(self.address), None))
+    }
+
+    /// Spawns a new [`Process`] linked to the current one.
+    pub fn spawn_link<P: Process>(&self, process: P) -> Address {
+        self.system.spawn_linked(process, Some(self.address))
+    }
+
+    /// Links the current process to the one at the given [`Address`].
+    pub fn link(&self, address: Address) -> Result<(), RuntimeError> {
+        self.system.link(self.address, address)
+    }
+
+    /// Removes the link between the current process and the one at the given [`Address`].
+    pub fn unlink(&self, address: Address) {
+        self.system.unlink(self.address, address)
+    }
+
+    /// Monitors the process at the given [`Address`] from the current process.
+    pub fn monitor(&self, address: Address) -> Result<(), RuntimeError> {
+        self.system.monitor(self.address, address)
+    }
+}
+
+impl System {
+    /// Creates a [`System`] for the node with the given identifier.
+    /// Connected nodes must have unique, non-zero identifiers.
+    pub fn new(node: u64) -> Self {
+        System {
+            node,
+            registry: Default::default(),
+        }
+    }
+
+    /// The identifier of the node this [`System`] runs on.
+    pub fn node(&self) -> u64 {
+        self.node
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn mailbox(&self, address: Address) -> Result<Sender<Delivery>, RuntimeError> {
+        self.registry()
+            .mailboxes
+            .get(&address)
+            .cloned()
+            .ok_or(RuntimeError::ProcessNotFound(address))
+    }
+
+    fn deliver(&self, address: Address, delivery: Delivery) -> Result<(), RuntimeError> {
+        if address.node() != self.node {
+            return match delivery {
+                Delivery::Message(envelope) => self.forward(address, envelope),
+                _ => Err(RuntimeError::ProcessNotFound(address)),
+            };
+        }
+
+        self.mailbox(address)?
+            .send(delivery)
+            .map_err(|_| RuntimeError::ProcessNotFound(address))
+    }
+
+    /// Sends a message to a process on another node.
+    fn forward(&self, address: Address, envelope: Envelope) -> Result<(), RuntimeError> {
+        let connection = self.registry().nodes.get(&address.node()).cloned();
+
+        match connection {
+            Some(connection) => connection.send(address, envelope),
+            None => Err(RuntimeError::ProcessNotFound(address)),
+        }
+    }
+
+    /// Delivers a message received from another node to a local process.
+    pub(super) fn receive(&self, address: Address, envelope: Envelope) {
+        if address.node() != self.node {
+            warn!("Discarding a message from another node for non-local process {address}.");
+            return;
+        }
+
+        if let Err(error) = self.deliver(address, Delivery::Message(envelope)) {
+            debug!("Unable to deliver a message from another node. {error}");
+        }
+    }
+
+    /// Adds a connection to another node.
+    /// Returns [`false`] if this node is already connected to the same node.
+    pub(super) fn connected(&self, connection: Arc<Connection>) -> bool {
+        let mut registry = self.registry();
+
+        if registry.nodes.contains_key(&connection.node()) {
+            return false;
+        }
+
+        debug!("Connected to node {}.", connection.node());
+        registry.nodes.insert(connection.node(), connection);
+
+        true
+    }
+
+    /// Removes a closed connection to another node and notifies the processes monitoring that node.
+    pub(super) fn disconnected(&self, connection: &Arc<Connection>) {
+        let node = connection.node();
+        let watchers = {
+            let mut registry = self.registry();
+
+            match registry.nodes.get(&node) {
+                Some(current) if Arc::ptr_eq(current, connection) => {
+                    registry.nodes.remove(&node);
+                    registry.node_monitors.remove(&node).unwrap_or_default()
+                }
+                _ => HashSet::new(),
+            }
+        };
+
+        connection.close();
+        warn!("Disconnected from node {node}.");
+
+        for watcher in watchers {
+            self.deliver(watcher, Delivery::Signal(Signal::NodeDown(node)))
+                .ok();
+        }
+    }
+
+    /// Keeps track of a thread to wait for on [`System::shutdown`].
+    pub(super) fn track(&self, thread: JoinHandle<()>) {
+        self.registry().threads.push(thread);
+    }
+
+    /// The identifiers of the nodes this [`System`] is connected to.
+    pub fn nodes(&self) -> Vec<u64> {
+        let mut nodes: Vec<u64> = self.registry().nodes.keys().copied().collect();
+
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Closes the connection to the given node.
+    /// Returns [`false`] if this [`System`] is not connected to the node.
+    pub fn disconnect(&self, node: u64) -> bool {
+        let connection = self.registry().nodes.get(&node).cloned();
+
+        match connection {
+            Some(connection) => {
+                self.disconnected(&connection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sends a [`Signal::NodeDown`] to the `watcher` when the connection to the given node is lost.
+    pub fn monitor_node(&self, watcher: Address, node: u64) -> Result<(), RuntimeError> {
+        let mut registry = self.registry();
+
+        if !registry.nodes.contains_key(&node) {
+            return Err(RuntimeError::NodeDisconnected(node));
+        }
+
+        registry
+            .node_monitors
+            .entry(node)
+            .or_default()
+            .insert(watcher);
+
+        Ok(())
+    }
+
+    /// Spawns a new [`Process`] and returns its [`Address`].
+    pub fn spawn<P: Process>(&self, process: P) -> Address {
+        self.spawn_linked(process, None)
+    }
+
+    fn spawn_linked<P: Process>(&self, process: P, parent: Option<Address>) -> Address {
+        let (sender, receiver) = channel::<Delivery>();
+        let mut registry = self.registry();
+
+        registry.next += 1;
+
+        let address = Address::new(self.node, registry.next);
+        let context = Context {
+            address,
+            origin: Origin::default(),
+            system: self.clone(),
+        };
+
+        let thread = thread::Builder::new()
+            .name(address.to_string())
+            .spawn(move || {
+                let system = context.system.clone();
+                let reason = run(process, context, receiver);
+
+                system.exited(address, reason);
+            })
+            .expect("Unable to spawn a thread for a process.");
+
+        registry.mailboxes.insert(address, sender);
+        registry.threads.push(thread);
+
+        if let Some(parent) = parent {
+            registry.link(parent, address);
+        }
+
+        address
+    }
+
+    /// Removes an exited process and notifies the processes linked to or monitoring it.
+    fn exited(&self, address: Address, reason: ExitReason) {
+        match &reason {
+            ExitReason::Normal => debug!("Process {address} stopped."),
+            reason => warn!("Process {address} exited: {reason}"),
+        }
+
+        let (links, monitors) = {
+            let mut registry = self.registry();
+
+            registry.mailboxes.remove(&address);
+            registry
+                .names
+                .retain(|_, registered| *registered != address);
+
+            let links = registry.links.remove(&address).unwrap_or_default();
+
+            for linked in &links {
+                if let Some(reverse) = registry.links.get_mut(linked) {
+                    reverse.remove(&address);
+                }
+            }
+
+            for watched in registry.monitors.values_mut() {
+                watched.remove(&address);
+            }
+
+            let monitors = registry.monitors.remove(&address).unwrap_or_default();
+
+            (links, monitors)
+        };
+
+        for linked in links {
+            let signal = Signal::Exit(address, reason.clone());
+            self.deliver(linked, Delivery::Signal(signal)).ok();
+        }
+
+        for watcher in monitors {
+            let signal = Signal::Down(address, reason.clone());
+            self.deliver(watcher, Delivery::Signal(signal)).ok();
+        }
+    }
+
+    /// Sends a message to the process at the given [`Address`] without waiting for a reply.
+    pub fn send(&self, address: Address, message: Value) -> Result<(), RuntimeError> {
+        self.send_from(address, message, Origin::default())
+    }
+
+    /// Sends a message with the given [`Origin`] without waiting for a reply.
+    pub fn send_from(
+        &self,
+        address: Address,
+        message: Value,
+        origin: Origin,
+    ) -> Result<(), RuntimeError> {
+        let envelope = Envelope {
+            message,
+            origin,
+            reply: None,
+        };
+
+        self.deliver(address, Delivery::Message(envelope))
+    }
+
+    /// Sends a message to the process at the given [`Address`] and waits for its reply.
+    pub fn call(&self, address: Address, message: Value) -> Result<Value, RuntimeError> {
+        self.call_from(address, message, Origin::default())
+    }
+
+    /// Sends a message with the given [`Origin`] and waits for the reply.
+    pub fn call_from(
+        &self,
+        address: Address,
+        message: Value,
+        origin: Origin,
+    ) -> Result<Value, RuntimeError> {
+        let (sender, receiver) = channel();
+        let reply = Reply::new(address, move |result| {
+            sender.send(result).ok();
+        });
+        let envelope = Envelope {
+            message,
+            origin,
+            reply: Some(reply),
+        };
+
+        self.deliver(address, Delivery::Message(envelope))?;
+
+        receiver
+            .recv()
+            .map_err(|_| RuntimeError::ProcessNotFound(address))?
+    }
+
+    /// Links two processes, so that when either one exits the other receives a [`Signal::Exit`].
+    pub fn link(&self, a: Address, b: Address) -> Result<(), RuntimeError> {
+        let mut registry = self.registry();
+
+        for address in [a, b] {
+            if !registry.mailboxes.contains_key(&address) {
+                return Err(RuntimeError::ProcessNotFound(address));
+            }
+        }
+
+        registry.link(a, b);
+
+        Ok(())
+    }
+
+    /// Removes the link between two processes, if any.
+    pub fn unlink(&self, a: Address, b: Address) {
+        let mut registry = self.registry();
+
+        if let Some(links) = registry.links.get_mut(&a) {
+            links.remove(&b);
+        }
+
+        if let Some(links) = registry.links.get_mut(&b) {
+            links.remove(&a);
+        }
+    }
+
+    /// Sends a [`Signal::Down`] to the `watcher` when the process at the `watched` [`Address`] exits.
+    pub fn monitor(&self, watcher: Address, watched: Address) -> Result<(), RuntimeError> {
+        let mut registry = self.registry();
+
+        if !registry.mailboxes.contains_key(&watched) {
+            return Err(RuntimeError::ProcessNotFound(watched));
+        }
+
+        registry
+            .monitors
+            .entry(watched)
+            .or_default()
+            .insert(watcher);
+
+        Ok(())
+    }
+
+    /// Stops monitoring the process at the `watched` [`Address`].
+    pub fn demonitor(&self, watcher: Address, watched: Address) {
+        if let Some(watchers) = self.registry().monitors.get_mut(&watched) {
+            watchers.remove(&watcher);
+        }
+    }
+
+    /// Registers a name for the process at the given [`Address`].
+    /// Returns the [`Address`] previously registered under the same name, if any.
+    pub fn register(&self, name: &str, address: Address) -> Option<Address> {
+        self.registry().names.insert(name.to_string(), address)
+    }
+
+    /// The [`Address`] of the process registered with the given name.
+    pub fn lookup(&self, name: &str) -> Option<Address> {
+        self.registry().names.get(name).copied()
+    }
+
+    /// Tests whether a process is running at the given [`Address`].
+    pub fn is_alive(&self, address: Address) -> bool {
+        self.registry().mailboxes.contains_key(&address)
+    }
+
+    /// Stops the process at the given [`Address`] once it has handled the messages already in its mailbox.
+    /// Returns [`false`] if no process is running at the given [`Address`].
+    pub fn stop(&self, address: Address) -> bool {
+        let mut registry = self.registry();
+
+        registry
+            .names
+            .retain(|_, registered| *registered != address);
+        registry.mailboxes.remove(&address).is_some()
+    }
+
+    /// Kills the process at the given [`Address`] once it has handled the messages already in its mailbox.
+    /// Unlike other exit signals, a kill cannot be handled by the process.
+    pub fn kill(&self, address: Address) -> Result<(), RuntimeError> {
+        self.deliver(address, Delivery::Kill)
+    }
+
+    /// Stops all processes, closes all connections to other nodes and waits for their threads to finish.
+    /// Must not be called from within a running [`Process`].
+    pub fn shutdown(&self) {
+        loop {
+            let (connections, threads) = {
+                let mut registry = self.registry();
+
+                registry.names.clear();
+                registry.mailboxes.clear();
+                registry.node_monitors.clear();
+
+                let connections: Vec<Arc<Connection>> = registry
+                    .nodes
+                    .drain()
+                    .map(|(_, connection)| connection)
+                    .collect();
+
+                (connections, std::mem::take(&mut registry.threads))
+            };
+
+            for connection in connections {
+                connection.close();
+            }
+
+            if threads.is_empty() {
+                break;
+            }
+
+            for thread in threads {
+                thread.join().ok();
+            }
+        }
+    }
+}
+
+/// Runs a process until its mailbox is closed or it exits.
+fn run<P: Process>(
+    mut process: P,
+    mut context: Context,
+    receiver: Receiver<Delivery>,
+) -> ExitReason {
+    if let Err(error) = process.start(&mut context) {
+        return error.into();
+    }
+
+    for delivery in receiver {
+        match delivery {
+            Delivery::Message(envelope) => {
+                context.origin = envelope.origin;
+
+                let result = process.receive(&mut context, envelope.message);
+                let failure = result.as_ref().err().cloned();
+
+                if let Some(reply) = envelope.reply {
+                    reply.send(result);
+                }
+
+                if let Some(error) = failure {
+                    return error.into();
+                }
+            }
+            Delivery::Signal(signal) => {
+                if let Err(reason) = process.signal(&mut context, signal) {
+                    return reason;
+                }
+            }
+            Delivery::Kill => return ExitReason::Killed,
+        }
+    }
+
+    ExitReason::Normal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::Sender;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn call_process() {
+        let system = System::default();
+        let mut total = Value::from(0);
+        let address = system.spawn(move |_: &mut Context, message: Value| {
+            total = (total.clone() + message)?;
+            Ok(total.clone())
+        });
+
+        system.send(address, 1.into()).unwrap();
+
+        assert_eq!(system.call(address, 2.into()), Ok(3.into()));
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn send_to_stopped_process() {
+        let system = System::default();
+        let address = system.spawn(|_: &mut Context, message: Value| Ok(message));
+
+        system.register("echo", address);
+
+        assert!(system.stop(address));
+        assert_eq!(system.lookup("echo"), None);
+        assert_eq!(
+            system.send(address, Value::Unit),
+            Err(RuntimeError::ProcessNotFound(address))
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn origin() {
+        let system = System::default();
+        let sender = system.spawn(|context: &mut Context, _: Value| {
+            Ok(context
+                .origin()
+                .sender()
+                .map(Value::from)
+                .unwrap_or_default())
+        });
+        let forwarder = system
+            .spawn(move |context: &mut Context, message: Value| context.call(sender, message));
+
+        assert_eq!(system.call(sender, Value::Unit), Ok(Value::Unit));
+        assert_eq!(system.call(forwarder, Value::Unit), Ok(forwarder.into()));
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn reply_with_error() {
+        let system = System::default();
+        let address = system.spawn(|_: &mut Context, message: Value| {
+            Err(RuntimeError::UnexpectedType(message, "nothing".to_string()))
+        });
+
+        assert_eq!(
+            system.call(address, 1.into()),
+            Err(RuntimeError::UnexpectedType(
+                1.into(),
+                "nothing".to_string()
+            ))
+        );
+
+        system.shutdown();
+    }
+
+    fn failing(_: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+        Err(RuntimeError::UnexpectedType(message, "nothing".to_string()))
+    }
+
+    /// Reports every signal it receives to the given channel.
+    struct Watcher(Sender<Signal>);
+
+    impl Process for Watcher {
+        fn receive(&mut self, _: &mut Context, message: Value) -> Result<Value, RuntimeError> {
+            Ok(message)
+        }
+
+        fn signal(&mut self, _: &mut Context, signal: Signal) -> Result<(), ExitReason> {
+            self.0.send(signal).ok();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn monitor() {
+        let system = System::default();
+        let (sender, receiver) = channel();
+        let watcher = system.spawn(Watcher(sender));
+        let watched = system.spawn(failing);
+
+        system.monitor(watcher, watched).unwrap();
+        system.send(watched, 1.into()).unwrap();
+
+        assert_eq!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Down(
+                watched,
+                ExitReason::Error(RuntimeError::UnexpectedType(
+                    1.into(),
+                    "nothing".to_string()
+                ))
+            ))
+        );
+        assert!(system.is_alive(watcher));
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn exit_propagates_over_links() {
+        let system = System::default();
+        let (sender, receiver) = channel();
+        let watcher = system.spawn(Watcher(sender));
+        let linked = system.spawn(|_: &mut Context, message: Value| Ok(message));
+        let failed = system.spawn(failing);
+
+        system.monitor(watcher, linked).unwrap();
+        system.link(linked, failed).unwrap();
+        system.send(failed, 1.into()).unwrap();
+
+        let reason = ExitReason::Error(RuntimeError::UnexpectedType(
+            1.into(),
+            "nothing".to_string(),
+        ));
+
+        assert_eq!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Down(linked, reason))
+        );
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn normal_exit_does_not_propagate() {
+        let system = System::default();
+        let (sender, receiver) = channel();
+        let watcher = system.spawn(Watcher(sender));
+        let stopped = system.spawn(|_: &mut Context, message: Value| Ok(message));
+
+        system.link(watcher, stopped).unwrap();
+        system.stop(stopped);
+
+        assert_eq!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Exit(stopped, ExitReason::Normal))
+        );
+        assert_eq!(system.call(watcher, 1.into()), Ok(1.into()));
+
+        system.shutdown();
+    }
+
+    #[test]
+    fn kill() {
+        let system = System::default();
+        let (sender, receiver) = channel();
+        let watcher = system.spawn(Watcher(sender));
+        let killed = system.spawn(Watcher(channel().0));
+
+        system.monitor(watcher, killed).unwrap();
+        system.kill(killed).unwrap();
+
+        assert_eq!(
+            receiver.recv_timeout(TIMEOUT),
+            Ok(Signal::Down(killed, ExitReason::Killed))
+        );
+
+        system.shutdown();
+    }
+}
@@ -14,6 +14,7 @@ use std::ops::{
 ///
 /// See <https://en.wikipedia.org/wiki/Interval_arithmetic#Interval_operators>
 #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Tolerance {
     center: Number,
     epsilon: Number,
@@ -38,6 +39,11 @@ impl Tolerance {
         self.center
     }
 
+    /// The distance from the center to either end of this [`Tolerance].
+    pub fn epsilon(&self) -> Number {
+        self.epsilon
+    }
+
     /// The maximum value of this [`Tolerance].
     pub fn max(&self) -> Number {
         self.center + self.epsilon
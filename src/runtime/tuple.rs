@@ -0,0 +1,99 @@
+//! Representation of tuples within the Tortuga runtime.
+
+use crate::Value;
+use std::fmt::{self, Write};
+use std::sync::Arc;
+
+/// An immutable sequence of unnamed fields that may hold values of any type.
+/// Tuples share their fields, so cloning a [`Tuple`] is cheap.
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Tuple(Arc<[Value]>);
+
+impl Tuple {
+    /// The field at the given index, if any.
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.0.get(index)
+    }
+
+    /// The number of fields in this [`Tuple`].
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Tests whether this [`Tuple`] has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an [`Iterator`] over the fields of this [`Tuple`].
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.0.iter()
+    }
+
+    /// The fields of this [`Tuple`] as a slice.
+    pub fn as_slice(&self) -> &[Value] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tuple {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl From<Vec<Value>> for Tuple {
+    fn from(fields: Vec<Value>) -> Self {
+        Tuple(fields.into())
+    }
+}
+
+impl From<&[Value]> for Tuple {
+    fn from(fields: &[Value]) -> Self {
+        Tuple(fields.into())
+    }
+}
+
+impl FromIterator<Value> for Tuple {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        Tuple(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('(')?;
+
+        for (index, field) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{}", field)?;
+        }
+
+        f.write_char(')')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let tuple = Tuple::from(vec![1.into(), Value::Unit, true.into()]);
+
+        assert_eq!(tuple.to_string(), "(1, {}, true)");
+    }
+
+    #[test]
+    fn get() {
+        let tuple = Tuple::from(vec![1.into(), 2.into()]);
+
+        assert_eq!(tuple.len(), 2);
+        assert_eq!(tuple.get(1), Some(&Value::from(2)));
+        assert_eq!(tuple.get(2), None);
+    }
+}
@@ -2,44 +2,61 @@
 
 use crate::runtime::environment::FunctionReference;
 use crate::runtime::epsilon::EpsilonOperator;
-use crate::runtime::{Number, Tolerance};
+use crate::runtime::process::Address;
+use crate::runtime::{ByteString, Number, Tolerance, Tuple};
 use crate::RuntimeError;
 use std::any::type_name;
 use std::cmp::Ordering;
 use std::convert::TryFrom;
 use std::fmt;
-use std::ops::{
-    Add, AddAssign, BitAnd, BitAndAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign,
-    Rem, RemAssign, Sub, SubAssign,
-};
+use std::ops::{Add, BitAnd, BitXor, Div, Mul, Rem, Sub};
 
 /// A value that may be created by a literal, or returned from a function.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum Value {
+    #[default]
     Unit,
     Boolean(bool),
     Number(Number),
     Tolerance(Tolerance),
     FunctionReference(FunctionReference),
+    Tuple(Tuple),
+    Bytes(ByteString),
+    Address(Address),
 }
 
 impl Value {
     /// The absolute value (i.e. positive) of this value.
-    pub fn abs(self) -> Self {
+    pub fn abs(self) -> Result<Self, RuntimeError> {
         match self {
-            Value::Number(a) => Value::Number(a.abs()),
-            _ => Self::Unit,
+            Value::Number(a) => Ok(Value::Number(a.abs())),
+            value => Err(unexpected::<Number>(value)),
         }
     }
 }
 
+/// The error for an operand of an unexpected type.
+fn unexpected<T>(value: Value) -> RuntimeError {
+    RuntimeError::UnexpectedType(value, type_name::<T>().to_string())
+}
+
+/// The error for an operator applied to operands of the wrong types.
+/// Reports the left-hand side, unless it is a number (or a tolerance, when `tolerance` is true).
+fn mismatched(lhs: Value, rhs: Value, tolerance: bool) -> RuntimeError {
+    match lhs {
+        Value::Number(_) => unexpected::<Number>(rhs),
+        Value::Tolerance(_) if tolerance => unexpected::<Number>(rhs),
+        lhs => unexpected::<Number>(lhs),
+    }
+}
+
 impl<I: Into<Value>> EpsilonOperator<I> for Value {
-    type Output = Value;
+    type Output = Result<Value, RuntimeError>;
 
     fn epsilon(self, rhs: I) -> Self::Output {
         match (self, rhs.into()) {
-            (Value::Number(a), Value::Number(b)) => Value::Tolerance(a.epsilon(b)),
-            _ => Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Tolerance(a.epsilon(b))),
+            (a, b) => Err(mismatched(a, b, false)),
         }
     }
 }
@@ -51,7 +68,41 @@ impl fmt::Display for Value {
             Value::Boolean(boolean) => write!(f, "{}", boolean),
             Value::Number(number) => write!(f, "{}", number),
             Value::Tolerance(tolerance) => write!(f, "{}", tolerance),
-            Value::FunctionReference(reference) => write!(f, "@{}", reference),
+            Value::FunctionReference(reference) => write!(f, "{}", reference),
+            Value::Tuple(tuple) => write!(f, "{}", tuple),
+            Value::Bytes(bytes) => write!(f, "{}", bytes),
+            Value::Address(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+/// Serializes a [`Value`] tagged by its variant.
+/// Function references are serialized as their name, since their declarations cannot be represented as data.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Unit => serializer.serialize_unit_variant("Value", 0, "Unit"),
+            Value::Boolean(boolean) => {
+                serializer.serialize_newtype_variant("Value", 1, "Boolean", boolean)
+            }
+            Value::Number(number) => {
+                serializer.serialize_newtype_variant("Value", 2, "Number", number)
+            }
+            Value::Tolerance(tolerance) => {
+                serializer.serialize_newtype_variant("Value", 3, "Tolerance", tolerance)
+            }
+            Value::FunctionReference(reference) => serializer.serialize_newtype_variant(
+                "Value",
+                4,
+                "FunctionReference",
+                reference.to_string().as_str(),
+            ),
+            Value::Tuple(tuple) => serializer.serialize_newtype_variant("Value", 5, "Tuple", tuple),
+            Value::Bytes(bytes) => serializer.serialize_newtype_variant("Value", 6, "Bytes", bytes),
+            Value::Address(address) => {
+                serializer.serialize_newtype_variant("Value", 7, "Address", address)
+            }
         }
     }
 }
@@ -86,151 +137,134 @@ impl From<Tolerance> for Value {
     }
 }
 
-impl From<bool> for Value {
-    fn from(boolean: bool) -> Self {
-        Value::Boolean(boolean)
+impl From<Tuple> for Value {
+    fn from(tuple: Tuple) -> Self {
+        Value::Tuple(tuple)
     }
 }
 
-impl Default for Value {
-    fn default() -> Self {
-        Value::Unit
+impl From<Vec<Value>> for Value {
+    fn from(fields: Vec<Value>) -> Self {
+        Value::Tuple(fields.into())
     }
 }
 
-impl Add for Value {
-    type Output = Self;
+impl From<ByteString> for Value {
+    fn from(bytes: ByteString) -> Self {
+        Value::Bytes(bytes)
+    }
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a + b),
-            (Value::Tolerance(a), Value::Number(b)) => Value::Tolerance(a + b),
-            (Value::Number(a), Value::Tolerance(b)) => Value::Tolerance(a + b),
-            (Value::Tolerance(a), Value::Tolerance(b)) => Value::Tolerance(a + b),
-            _ => Self::Unit,
-        }
+impl From<Vec<u8>> for Value {
+    fn from(bytes: Vec<u8>) -> Self {
+        Value::Bytes(bytes.into())
     }
 }
 
-impl AddAssign for Value {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+impl From<&[u8]> for Value {
+    fn from(bytes: &[u8]) -> Self {
+        Value::Bytes(bytes.into())
     }
 }
 
-impl Sub for Value {
-    type Output = Self;
+impl From<Address> for Value {
+    fn from(address: Address) -> Self {
+        Value::Address(address)
+    }
+}
 
-    fn sub(self, rhs: Self) -> Self::Output {
+impl From<bool> for Value {
+    fn from(boolean: bool) -> Self {
+        Value::Boolean(boolean)
+    }
+}
+
+impl Add for Value {
+    type Output = Result<Self, RuntimeError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a - b),
-            (Value::Tolerance(a), Value::Number(b)) => Value::Tolerance(a - b),
-            (Value::Number(a), Value::Tolerance(b)) => Value::Tolerance(a - b),
-            (Value::Tolerance(a), Value::Tolerance(b)) => Value::Tolerance(a - b),
-            _ => Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+            (Value::Tolerance(a), Value::Number(b)) => Ok(Value::Tolerance(a + b)),
+            (Value::Number(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a + b)),
+            (Value::Tolerance(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a + b)),
+            (a, b) => Err(mismatched(a, b, true)),
         }
     }
 }
 
-impl SubAssign for Value {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+impl Sub for Value {
+    type Output = Result<Self, RuntimeError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+            (Value::Tolerance(a), Value::Number(b)) => Ok(Value::Tolerance(a - b)),
+            (Value::Number(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a - b)),
+            (Value::Tolerance(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a - b)),
+            (a, b) => Err(mismatched(a, b, true)),
+        }
     }
 }
 
 impl Mul for Value {
-    type Output = Self;
+    type Output = Result<Self, RuntimeError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a * b),
-            (Value::Tolerance(a), Value::Number(b)) => Value::Tolerance(a * b),
-            (Value::Number(a), Value::Tolerance(b)) => Value::Tolerance(a * b),
-            _ => Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+            (Value::Tolerance(a), Value::Number(b)) => Ok(Value::Tolerance(a * b)),
+            (Value::Number(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a * b)),
+            (a, b) => Err(mismatched(a, b, true)),
         }
     }
 }
 
-impl MulAssign for Value {
-    fn mul_assign(&mut self, rhs: Self) {
-        *self = *self * rhs;
-    }
-}
-
 impl Div for Value {
-    type Output = Self;
+    type Output = Result<Self, RuntimeError>;
 
     fn div(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a / b),
-            (Value::Tolerance(a), Value::Number(b)) => Value::Tolerance(a / b),
-            (Value::Number(a), Value::Tolerance(b)) => Value::Tolerance(a / b),
-            _ => Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a / b)),
+            (Value::Tolerance(a), Value::Number(b)) => Ok(Value::Tolerance(a / b)),
+            (Value::Number(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a / b)),
+            (a, b) => Err(mismatched(a, b, true)),
         }
     }
 }
 
-impl DivAssign for Value {
-    fn div_assign(&mut self, rhs: Self) {
-        *self = *self / rhs;
-    }
-}
-
 impl Rem for Value {
-    type Output = Self;
+    type Output = Result<Self, RuntimeError>;
 
     fn rem(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a % b),
-            _ => Self::Unit,
-        }
-    }
-}
-
-impl RemAssign for Value {
-    fn rem_assign(&mut self, rhs: Self) {
-        match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => *a %= b,
-            (a, _) => *a = Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a % b)),
+            (a, b) => Err(mismatched(a, b, false)),
         }
     }
 }
 
 impl BitXor for Value {
-    type Output = Self;
+    type Output = Result<Self, RuntimeError>;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number(a ^ b),
-            (Value::Tolerance(a), Value::Number(b)) => Value::Tolerance(a ^ b),
-            (Value::Number(a), Value::Tolerance(b)) => Value::Tolerance(a ^ b),
-            _ => Self::Unit,
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a ^ b)),
+            (Value::Tolerance(a), Value::Number(b)) => Ok(Value::Tolerance(a ^ b)),
+            (Value::Number(a), Value::Tolerance(b)) => Ok(Value::Tolerance(a ^ b)),
+            (a, b) => Err(mismatched(a, b, true)),
         }
     }
 }
 
-impl BitXorAssign for Value {
-    fn bitxor_assign(&mut self, rhs: Self) {
-        *self = *self ^ rhs;
-    }
-}
-
 impl BitAnd for Value {
-    type Output = Self;
+    type Output = Result<Self, RuntimeError>;
 
     fn bitand(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(a && b),
-            _ => Self::Unit,
-        }
-    }
-}
-
-impl BitAndAssign for Value {
-    fn bitand_assign(&mut self, rhs: Self) {
-        match (self, rhs) {
-            (Value::Boolean(a), Value::Boolean(b)) => *a &= b,
-            (v, _) => *v = Self::Unit,
+            (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a && b)),
+            (Value::Boolean(_), b) => Err(unexpected::<bool>(b)),
+            (a, _) => Err(unexpected::<bool>(a)),
         }
     }
 }
@@ -245,6 +279,9 @@ impl PartialEq for Value {
             (Value::Number(a), Value::Tolerance(b)) => b.contains(a),
             (Value::Tolerance(a), Value::Number(b)) => a.contains(b),
             (Value::FunctionReference(a), Value::FunctionReference(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Address(a), Value::Address(b)) => a == b,
             _ => false,
         }
     }
@@ -273,11 +310,27 @@ impl PartialOrd for Value {
                     a.max().partial_cmp(&b.min())
                 }
             }
+            (Value::Tuple(a), Value::Tuple(b)) => a.partial_cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.partial_cmp(b),
             _ => None,
         }
     }
 }
 
+impl TryFrom<Value> for Address {
+    type Error = RuntimeError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Address(address) => Ok(address),
+            _ => Err(RuntimeError::UnexpectedType(
+                value,
+                type_name::<Self>().to_string(),
+            )),
+        }
+    }
+}
+
 impl TryFrom<Value> for FunctionReference {
     type Error = RuntimeError;
 
@@ -300,48 +353,32 @@ mod tests {
     fn add_number() {
         let a = Value::from(1);
         let b = Value::from(1);
-        let mut c = a;
-
-        c += b;
 
-        assert_eq!(a + b, c);
-        assert_eq!(c, 2.into());
+        assert_eq!(a + b, Ok(2.into()));
     }
 
     #[test]
     fn add_number_tolerance() {
         let a = Value::from(1);
         let b = Value::from(Number::from(1).epsilon(2));
-        let mut c = a;
 
-        c += b;
-
-        assert_eq!(a + b, c);
-        assert_eq!(c, Tolerance::new(2, 2).into());
+        assert_eq!(a + b, Ok(Tolerance::new(2, 2).into()));
     }
 
     #[test]
     fn add_tolerance_number() {
         let a = Value::from(Tolerance::new(1, 2));
         let b = Value::from(1);
-        let mut c = a;
-
-        c += b;
 
-        assert_eq!(a + b, c);
-        assert_eq!(c, Tolerance::new(2, 2).into());
+        assert_eq!(a + b, Ok(Tolerance::new(2, 2).into()));
     }
 
     #[test]
     fn add_tolerance() {
         let a = Value::from(Tolerance::new(1, 2));
         let b = Value::from(Tolerance::new(1, 2));
-        let mut c = a;
-
-        c += b;
 
-        assert_eq!(a + b, c);
-        assert_eq!(c, Tolerance::new(2, 4).into());
+        assert_eq!(a + b, Ok(Tolerance::new(2, 4).into()));
     }
 
     #[test]
@@ -349,55 +386,39 @@ mod tests {
         let a = Value::from(false);
         let b = Value::from(1);
 
-        assert_eq!(a + b, Value::Unit);
+        assert!(matches!(a + b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn sub_number() {
         let a = Value::from(1);
         let b = Value::from(1);
-        let mut c = a;
 
-        c -= b;
-
-        assert_eq!(a - b, c);
-        assert_eq!(c, 0.into());
+        assert_eq!(a - b, Ok(0.into()));
     }
 
     #[test]
     fn sub_number_tolerance() {
         let a = Value::from(1);
         let b = Value::from(Number::from(1).epsilon(2));
-        let mut c = a;
-
-        c -= b;
 
-        assert_eq!(a - b, c);
-        assert_eq!(c, Tolerance::new(0, 2).into());
+        assert_eq!(a - b, Ok(Tolerance::new(0, 2).into()));
     }
 
     #[test]
     fn sub_tolerance_number() {
         let a = Value::from(Tolerance::new(1, 2));
         let b = Value::from(1);
-        let mut c = a;
-
-        c -= b;
 
-        assert_eq!(a - b, c);
-        assert_eq!(c, Tolerance::new(0, 2).into());
+        assert_eq!(a - b, Ok(Tolerance::new(0, 2).into()));
     }
 
     #[test]
     fn sub_tolerance() {
         let a = Value::from(Tolerance::new(1, 2));
         let b = Value::from(Tolerance::new(1, 2));
-        let mut c = a;
 
-        c -= b;
-
-        assert_eq!(a - b, c);
-        assert_eq!(c, Tolerance::new(0, 4).into());
+        assert_eq!(a - b, Ok(Tolerance::new(0, 4).into()));
     }
 
     #[test]
@@ -405,42 +426,45 @@ mod tests {
         let a = Value::from(1);
         let b = Value::from(false);
 
-        assert_eq!(a - b, Value::Unit);
+        assert!(matches!(a - b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn mul_number() {
         let a = Value::from(2);
         let b = Value::from(2);
-        let mut c = a;
-
-        c *= b;
 
-        assert_eq!(a * b, c);
+        assert_eq!(a * b, Ok(4.into()));
     }
 
     #[test]
     fn mul_number_tolerance() {
         let a = Value::from(2);
         let b = Value::from(Number::from(2).epsilon(2));
-        let mut c = a;
 
-        c *= b;
-
-        assert_eq!(a * b, c);
-        assert_eq!(c, Tolerance::new(4, 2).into());
+        assert_eq!(a * b, Ok(Tolerance::new(4, 2).into()));
     }
 
     #[test]
     fn mul_tolerance_number() {
         let a = Value::from(Tolerance::new(2, 2));
         let b = Value::from(2);
-        let mut c = a;
 
-        c *= b;
+        assert_eq!(a * b, Ok(Tolerance::new(4, 2).into()));
+    }
+
+    #[test]
+    fn mul_tolerance() {
+        let a = Value::from(Tolerance::new(2, 2));
+        let b = Value::from(Tolerance::new(2, 2));
 
-        assert_eq!(a * b, c);
-        assert_eq!(c, Tolerance::new(4, 2).into());
+        assert_eq!(
+            a * b.clone(),
+            Err(RuntimeError::UnexpectedType(
+                b,
+                type_name::<Number>().to_string()
+            ))
+        );
     }
 
     #[test]
@@ -448,42 +472,31 @@ mod tests {
         let a = Value::from(1);
         let b = Value::Unit;
 
-        assert_eq!(a * b, Value::Unit);
+        assert!(matches!(a * b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn div_number() {
         let a = Value::from(4);
         let b = Value::from(2);
-        let mut c = a;
 
-        c /= b;
-
-        assert_eq!(a / b, c);
+        assert_eq!(a / b, Ok(2.into()));
     }
 
     #[test]
     fn div_number_tolerance() {
         let a = Value::from(4);
         let b = Value::from(Number::from(2).epsilon(2));
-        let mut c = a;
-
-        c /= b;
 
-        assert_eq!(a / b, c);
-        assert_eq!(c, Tolerance::new(2, 2).into());
+        assert_eq!(a / b, Ok(Tolerance::new(2, 2).into()));
     }
 
     #[test]
     fn div_tolerance_number() {
         let a = Value::from(Tolerance::new(4, 2));
         let b = Value::from(2);
-        let mut c = a;
-
-        c /= b;
 
-        assert_eq!(a / b, c);
-        assert_eq!(c, Tolerance::new(2, 2).into());
+        assert_eq!(a / b, Ok(Tolerance::new(2, 2).into()));
     }
 
     #[test]
@@ -491,18 +504,15 @@ mod tests {
         let a = Value::from(true);
         let b = Value::from(1);
 
-        assert_eq!(a / b, Value::Unit);
+        assert!(matches!(a / b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn rem_number() {
         let a = Value::from(5);
         let b = Value::from(3);
-        let mut c = a;
 
-        c %= b;
-
-        assert_eq!(a % b, c);
+        assert_eq!(a % b, Ok(2.into()));
     }
 
     #[test]
@@ -510,42 +520,31 @@ mod tests {
         let a = Value::from(true);
         let b = Value::from(true);
 
-        assert_eq!(a % b, Value::Unit);
+        assert!(matches!(a % b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn bitxor_number() {
         let a = Value::from(2);
         let b = Value::from(2);
-        let mut c = a;
-
-        c ^= b;
 
-        assert_eq!(a ^ b, c);
+        assert_eq!(a ^ b, Ok(4.into()));
     }
 
     #[test]
     fn bitxor_number_tolerance() {
         let a = Value::from(2);
         let b = Value::from(Number::from(2).epsilon(2));
-        let mut c = a;
-
-        c ^= b;
 
-        assert_eq!(a ^ b, c);
-        assert_eq!(c, Tolerance::new(4, 2).into());
+        assert_eq!(a ^ b, Ok(Tolerance::new(4, 2).into()));
     }
 
     #[test]
     fn bitxor_tolerance_number() {
         let a = Value::from(Tolerance::new(2, 2));
         let b = Value::from(2);
-        let mut c = a;
 
-        c ^= b;
-
-        assert_eq!(a ^ b, c);
-        assert_eq!(c, Tolerance::new(4, 2).into());
+        assert_eq!(a ^ b, Ok(Tolerance::new(4, 2).into()));
     }
 
     #[test]
@@ -553,18 +552,15 @@ mod tests {
         let a = Value::from(1);
         let b = Value::from(true);
 
-        assert_eq!(a ^ b, Value::Unit);
+        assert!(matches!(a ^ b, Err(RuntimeError::UnexpectedType(..))));
     }
 
     #[test]
     fn bitand_boolean() {
         let a = Value::from(true);
         let b = Value::from(false);
-        let mut c = a;
-
-        c &= b;
 
-        assert_eq!(a & b, c);
+        assert_eq!(a & b, Ok(false.into()));
     }
 
     #[test]
@@ -572,6 +568,49 @@ mod tests {
         let a = Value::from(1);
         let b = Value::from(1);
 
-        assert_eq!(a & b, Value::Unit);
+        assert!(matches!(a & b, Err(RuntimeError::UnexpectedType(..))));
+    }
+
+    #[test]
+    fn abs_other() {
+        let a = Value::from(Tolerance::new(-2, 1));
+
+        assert_eq!(
+            a.clone().abs(),
+            Err(RuntimeError::UnexpectedType(
+                a,
+                type_name::<Number>().to_string()
+            ))
+        );
+        assert_eq!(Value::from(-2).abs(), Ok(2.into()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_value() {
+        let tuple = Value::Tuple(vec![Value::Unit, Value::from(1.5), Value::Boolean(true)].into());
+
+        assert_eq!(
+            serde_json::to_string(&tuple).unwrap(),
+            r#"{"Tuple":["Unit",{"Number":1.5},{"Boolean":true}]}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_non_finite_number() {
+        let tuple = Value::Tuple(
+            vec![
+                Value::from(f64::INFINITY),
+                Value::from(f64::NEG_INFINITY),
+                Value::from(f64::NAN),
+            ]
+            .into(),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&tuple).unwrap(),
+            r#"{"Tuple":[{"Number":"inf"},{"Number":"-inf"},{"Number":"NaN"}]}"#
+        );
     }
 }
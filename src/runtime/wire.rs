@@ -0,0 +1,301 @@
+//! A binary encoding of [`Value`]s for sending messages between nodes.
+//! The system sends all numbers in network byte order (i.e., big endian).
+//!
+//! Every value starts with a single byte tag, followed by a payload that depends on the tag:
+//!
+//! | Tag | Value     | Payload                                           |
+//! |-----|-----------|---------------------------------------------------|
+//! | 0   | Unit      | None.                                             |
+//! | 1   | Boolean   | A single byte; 0 for false, 1 for true.           |
+//! | 2   | Number    | A 64-bit IEEE 754 floating-point number.          |
+//! | 3   | Tolerance | The center and epsilon as 64-bit numbers.         |
+//! | 4   | Tuple     | A 32-bit unsigned count, followed by the values.  |
+//! | 5   | Bytes     | A 32-bit unsigned length, followed by the bytes.  |
+//! | 6   | Address   | The 64-bit unsigned node and process identifiers. |
+//!
+//! Function references only have meaning within a single interpreter, so they cannot be encoded.
+
+use crate::runtime::process::Address;
+use crate::runtime::{ByteString, Tolerance, Tuple};
+use crate::Value;
+
+const UNIT: u8 = 0;
+const BOOLEAN: u8 = 1;
+const NUMBER: u8 = 2;
+const TOLERANCE: u8 = 3;
+const TUPLE: u8 = 4;
+const BYTES: u8 = 5;
+const ADDRESS: u8 = 6;
+
+/// The maximum number of nested tuples in a decoded value.
+pub const MAX_DEPTH: usize = 128;
+
+/// An error that may occur while encoding or decoding a [`Value`].
+#[derive(thiserror::Error, Clone, Debug, PartialEq)]
+pub enum WireError {
+    #[error("Reached the end of the input while decoding a value.")]
+    UnexpectedEnd,
+    #[error("Encountered an unknown value tag {0}.")]
+    UnknownTag(u8),
+    #[error("Expected a boolean to be encoded as 0 or 1, but found {0}.")]
+    InvalidBoolean(u8),
+    #[error("A length of {0} does not fit in 32 bits.")]
+    TooLong(usize),
+    #[error("Values may not be nested more than {MAX_DEPTH} tuples deep.")]
+    TooDeep,
+    #[error("Found {0} unexpected bytes after the end of the value.")]
+    TrailingBytes(usize),
+    #[error("Value {0} cannot be sent to another node.")]
+    Unsupported(Value),
+}
+
+/// Encodes a [`Value`] in the wire format.
+pub fn encode(value: &Value) -> Result<Vec<u8>, WireError> {
+    let mut buffer = Vec::new();
+
+    write_value(&mut buffer, value)?;
+
+    Ok(buffer)
+}
+
+/// Decodes a single [`Value`] that spans all of the given bytes.
+pub fn decode(bytes: &[u8]) -> Result<Value, WireError> {
+    let mut decoder = Decoder::new(bytes);
+    let value = decoder.read_value()?;
+
+    decoder.finish()?;
+
+    Ok(value)
+}
+
+/// Appends the wire format of a [`Value`] to the buffer.
+pub(crate) fn write_value(buffer: &mut Vec<u8>, value: &Value) -> Result<(), WireError> {
+    match value {
+        Value::Unit => buffer.push(UNIT),
+        Value::Boolean(boolean) => {
+            buffer.push(BOOLEAN);
+            buffer.push(u8::from(*boolean));
+        }
+        Value::Number(number) => {
+            buffer.push(NUMBER);
+            buffer.extend_from_slice(&number.to_f64().to_be_bytes());
+        }
+        Value::Tolerance(tolerance) => {
+            buffer.push(TOLERANCE);
+            buffer.extend_from_slice(&tolerance.center().to_f64().to_be_bytes());
+            buffer.extend_from_slice(&tolerance.epsilon().to_f64().to_be_bytes());
+        }
+        Value::Tuple(tuple) => {
+            buffer.push(TUPLE);
+            write_length(buffer, tuple.len())?;
+
+            for value in tuple.iter() {
+                write_value(buffer, value)?;
+            }
+        }
+        Value::Bytes(bytes) => {
+            buffer.push(BYTES);
+            write_length(buffer, bytes.len())?;
+            buffer.extend_from_slice(bytes.as_bytes());
+        }
+        Value::Address(address) => {
+            buffer.push(ADDRESS);
+            write_address(buffer, address);
+        }
+        Value::FunctionReference(_) => return Err(WireError::Unsupported(value.clone())),
+    }
+
+    Ok(())
+}
+
+/// Appends the node and process identifiers of an [`Address`] to the buffer.
+pub(crate) fn write_address(buffer: &mut Vec<u8>, address: &Address) {
+    buffer.extend_from_slice(&address.node().to_be_bytes());
+    buffer.extend_from_slice(&address.id().to_be_bytes());
+}
+
+fn write_length(buffer: &mut Vec<u8>, length: usize) -> Result<(), WireError> {
+    let length = u32::try_from(length).map_err(|_| WireError::TooLong(length))?;
+
+    buffer.extend_from_slice(&length.to_be_bytes());
+
+    Ok(())
+}
+
+/// Reads values in the wire format from a slice of bytes.
+#[derive(Debug)]
+pub(crate) struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Decoder { bytes }
+    }
+
+    /// Fails if any bytes remain to be read.
+    pub(crate) fn finish(&self) -> Result<(), WireError> {
+        match self.bytes.len() {
+            0 => Ok(()),
+            remaining => Err(WireError::TrailingBytes(remaining)),
+        }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], WireError> {
+        if self.bytes.len() < length {
+            return Err(WireError::UnexpectedEnd);
+        }
+
+        let (head, tail) = self.bytes.split_at(length);
+
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], WireError> {
+        let mut array = [0; N];
+
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, WireError> {
+        Ok(self.take_array::<1>()?[0])
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, WireError> {
+        Ok(u64::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, WireError> {
+        Ok(f64::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_length(&mut self) -> Result<usize, WireError> {
+        Ok(u32::from_be_bytes(self.take_array()?) as usize)
+    }
+
+    pub(crate) fn read_address(&mut self) -> Result<Address, WireError> {
+        Ok(Address::new(self.read_u64()?, self.read_u64()?))
+    }
+
+    pub(crate) fn read_value(&mut self) -> Result<Value, WireError> {
+        self.read_nested_value(0)
+    }
+
+    fn read_nested_value(&mut self, depth: usize) -> Result<Value, WireError> {
+        match self.read_u8()? {
+            UNIT => Ok(Value::Unit),
+            BOOLEAN => match self.read_u8()? {
+                0 => Ok(Value::Boolean(false)),
+                1 => Ok(Value::Boolean(true)),
+                byte => Err(WireError::InvalidBoolean(byte)),
+            },
+            NUMBER => Ok(Value::from(self.read_f64()?)),
+            TOLERANCE => {
+                let center = self.read_f64()?;
+                let epsilon = self.read_f64()?;
+
+                Ok(Value::from(Tolerance::new(center, epsilon)))
+            }
+            TUPLE if depth >= MAX_DEPTH => Err(WireError::TooDeep),
+            TUPLE => {
+                let length = self.read_length()?;
+                // Each value takes at least one byte, so a valid length can never exceed the remaining input.
+                let mut values = Vec::with_capacity(length.min(self.bytes.len()));
+
+                for _ in 0..length {
+                    values.push(self.read_nested_value(depth + 1)?);
+                }
+
+                Ok(Value::from(Tuple::from(values)))
+            }
+            BYTES => {
+                let length = self.read_length()?;
+
+                Ok(Value::from(ByteString::from(self.take(length)?)))
+            }
+            ADDRESS => Ok(Value::from(self.read_address()?)),
+            tag => Err(WireError::UnknownTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+
+    fn round_trip(value: Value) {
+        assert_eq!(decode(encode(&value).unwrap().as_slice()), Ok(value));
+    }
+
+    #[test]
+    fn round_trip_values() {
+        round_trip(Value::Unit);
+        round_trip(Value::Boolean(true));
+        round_trip(Value::from(-42.5));
+        round_trip(Value::from(Tolerance::new(3, 0.5)));
+        round_trip(Value::from(vec![0xca_u8, 0xfe]));
+        round_trip(Value::from(Address::new(2, 7)));
+        round_trip(Value::from(vec![
+            Value::from(1),
+            Value::from(vec![Value::Unit, Value::from(false)]),
+            Value::from(Vec::<Value>::new()),
+        ]));
+    }
+
+    #[test]
+    fn network_byte_order() {
+        assert_eq!(
+            encode(&Value::from(1)).unwrap(),
+            vec![NUMBER, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            encode(&Value::from(ByteString::from(b"ab"))).unwrap(),
+            vec![BYTES, 0, 0, 0, 2, b'a', b'b']
+        );
+        assert_eq!(
+            encode(&Value::from(Address::new(1, 258))).unwrap(),
+            vec![ADDRESS, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn function_reference() {
+        let value = Interpreter::build_then_run("@f(@x) = x\nf").unwrap();
+
+        assert_eq!(encode(&value), Err(WireError::Unsupported(value)));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(decode(&[]), Err(WireError::UnexpectedEnd));
+        assert_eq!(decode(&[42]), Err(WireError::UnknownTag(42)));
+        assert_eq!(decode(&[BOOLEAN, 2]), Err(WireError::InvalidBoolean(2)));
+        assert_eq!(decode(&[UNIT, UNIT]), Err(WireError::TrailingBytes(1)));
+        assert_eq!(
+            decode(&[TUPLE, 0xff, 0xff, 0xff, 0xff, UNIT]),
+            Err(WireError::UnexpectedEnd)
+        );
+        assert_eq!(
+            decode(&[BYTES, 0, 0, 0, 3, 1, 2]),
+            Err(WireError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn too_deep() {
+        let mut bytes = Vec::new();
+
+        for _ in 0..=MAX_DEPTH {
+            bytes.extend_from_slice(&[TUPLE, 0, 0, 0, 1]);
+        }
+
+        bytes.push(UNIT);
+
+        assert_eq!(decode(bytes.as_slice()), Err(WireError::TooDeep));
+    }
+}
@@ -0,0 +1,235 @@
+//! Run the assertions in Tortuga test files.
+//!
+//! A test file is a sequence of top-level expressions, like any other program.
+//! An expression followed by comparisons (e.g., `sqrt(25) = 5 = 2 + 3`) is an assertion that passes when every comparison holds.
+//! The other expressions run in order, so an assertion may call the functions declared before it.
+//! An assertion is named by the first line of its documentation comments (`;;`), or by its location otherwise.
+
+use crate::compiler::{Location, Parser};
+use crate::grammar::syntax::{Comparator, Comparisons, Expression, List};
+use crate::runtime::compare;
+use crate::{CompilationError, Interpreter, Program, RuntimeError, Value};
+use std::str::FromStr;
+
+/// The assertions in a test file, along with the expressions that run before them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suite {
+    statements: Vec<Statement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Statement {
+    Setup(Program),
+    Assertion(Assertion),
+}
+
+/// A sequence of comparisons that must all hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assertion {
+    name: String,
+    location: Location,
+    comparisons: Comparisons,
+}
+
+impl Assertion {
+    /// The name of this [`Assertion`].
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The start [`Location`] of this [`Assertion`] in the test file.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+/// The outcome of evaluating an [`Assertion`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    /// Every comparison holds.
+    Passed,
+    /// The first comparison that does not hold, with its evaluated left and right -hand sides.
+    Failed(Value, Comparator, Value),
+    /// Evaluating one of the sides of a comparison failed.
+    Error(RuntimeError),
+}
+
+impl Outcome {
+    /// Tests whether the assertion passed.
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Outcome::Passed)
+    }
+}
+
+impl FromStr for Suite {
+    type Err = CompilationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let statements = Parser::try_from(s)?
+            .parse_statements()?
+            .into_iter()
+            .map(|(program, doc, location)| match program {
+                Program::Comparisons(comparisons) => Statement::Assertion(Assertion {
+                    name: doc
+                        .as_deref()
+                        .and_then(|doc| doc.lines().next())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| location.to_string()),
+                    location,
+                    comparisons,
+                }),
+                program => Statement::Setup(program),
+            })
+            .collect();
+
+        Ok(Suite { statements })
+    }
+}
+
+impl Suite {
+    /// The assertions in this [`Suite`], in the order they appear in the test file.
+    pub fn assertions(&self) -> impl Iterator<Item = &Assertion> {
+        self.statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Assertion(assertion) => Some(assertion),
+                Statement::Setup(_) => None,
+            })
+    }
+
+    /// Runs the expressions in this [`Suite`] in order, evaluating each assertion the filter accepts.
+    /// Returns an error if one of the expressions outside an assertion fails.
+    pub fn run<F>(&self, mut filter: F) -> Result<Vec<(&Assertion, Outcome)>, RuntimeError>
+    where
+        F: FnMut(&Assertion) -> bool,
+    {
+        let mut interpreter = Interpreter::default();
+        let mut outcomes = Vec::new();
+
+        for statement in self.statements.iter() {
+            match statement {
+                Statement::Setup(program) => {
+                    interpreter.run(program.clone())?;
+                }
+                Statement::Assertion(assertion) if filter(assertion) => {
+                    outcomes.push((
+                        assertion,
+                        evaluate(&mut interpreter, &assertion.comparisons),
+                    ));
+                }
+                Statement::Assertion(_) => (),
+            }
+        }
+
+        Ok(outcomes)
+    }
+}
+
+/// Evaluates each side of the comparisons in order, stopping at the first comparison that does not hold.
+fn evaluate(interpreter: &mut Interpreter, comparisons: &Comparisons) -> Outcome {
+    let mut lhs = match run(interpreter, comparisons.lhs()) {
+        Ok(value) => value,
+        Err(error) => return Outcome::Error(error),
+    };
+
+    for comparison in comparisons.comparisons().iter() {
+        let rhs = match run(interpreter, comparison.rhs()) {
+            Ok(value) => value,
+            Err(error) => return Outcome::Error(error),
+        };
+
+        if compare(&lhs, comparison.comparator(), &rhs) != Value::Boolean(true) {
+            return Outcome::Failed(lhs, *comparison.comparator(), rhs);
+        }
+
+        lhs = rhs;
+    }
+
+    Outcome::Passed
+}
+
+fn run(interpreter: &mut Interpreter, expression: &Expression) -> Result<Value, RuntimeError> {
+    interpreter.run(List::new(expression.clone(), Vec::new()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+@square(@x) = x^2
+
+;; Squares are positive.
+square(-3) = 9 > 0
+
+square(2) = 5
+
+@cube(@x) = x * square(x)
+
+;; Cubes
+cube(2) = 8
+cube(1) = unknown(1)
+"#;
+
+    fn outcomes(suite: &Suite) -> Vec<(String, Outcome)> {
+        suite
+            .run(|_| true)
+            .unwrap()
+            .into_iter()
+            .map(|(assertion, outcome)| (assertion.name().to_string(), outcome))
+            .collect()
+    }
+
+    #[test]
+    fn assertions() {
+        let suite: Suite = SOURCE.parse().unwrap();
+        let names: Vec<&str> = suite.assertions().map(Assertion::name).collect();
+
+        assert_eq!(names, vec!["Squares are positive.", "7:1", "Cubes", "13:1"]);
+    }
+
+    #[test]
+    fn run_assertions() {
+        let suite: Suite = SOURCE.parse().unwrap();
+        let outcomes = outcomes(&suite);
+
+        assert_eq!(outcomes[0].1, Outcome::Passed);
+        assert_eq!(
+            outcomes[1].1,
+            Outcome::Failed(Value::from(4), Comparator::EqualTo, Value::from(5))
+        );
+        assert_eq!(outcomes[2].1, Outcome::Passed);
+        assert!(matches!(
+            outcomes[3].1,
+            Outcome::Error(RuntimeError::FunctionNotDefined(_))
+        ));
+    }
+
+    #[test]
+    fn chained_failure() {
+        let suite: Suite = "1 < 2 < 2 < 3".parse().unwrap();
+
+        assert_eq!(
+            outcomes(&suite)[0].1,
+            Outcome::Failed(Value::from(2), Comparator::LessThan, Value::from(2))
+        );
+    }
+
+    #[test]
+    fn filter_assertions() {
+        let suite: Suite = SOURCE.parse().unwrap();
+        let outcomes = suite
+            .run(|assertion| assertion.name().starts_with("Cubes"))
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].1, Outcome::Passed);
+    }
+
+    #[test]
+    fn failed_setup() {
+        let suite: Suite = "unknown(1)\n1 = 1".parse().unwrap();
+
+        assert!(suite.run(|_| true).is_err());
+    }
+}